@@ -0,0 +1,421 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use std::cell::Cell;
+use crate::{Solution, Status, Minimizer, BFGS};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::problem::{Objective, Gradient, Hessian, HessianVector, Constraints};
+
+
+/// Augmented Lagrangian method for equality and inequality constraints.
+///
+/// The problem `min f(x)` subject to `c(x) = 0` and `g(x) <= 0` is solved as a
+/// sequence of unconstrained minimizations of the augmented Lagrangian
+/// (Powell-Hestenes-Rockafellar form)
+///
+/// `L(x) = f + λ·c + (μ/2)|c|^2 + (1/2μ)Σ[max(0, ν + μg)^2 - ν^2]`
+///
+/// where `λ` and `ν` are the estimates of the Lagrange multipliers and `μ` is
+/// the penalty parameter. After each inner minimization the multipliers are
+/// updated with `λ = λ + μc` and `ν = max(0, ν + μg)`, and the penalty is
+/// increased when the constraint violation didn't decrease enough. Unlike a
+/// pure penalty method, `μ` doesn't need to go to infinity, so the inner
+/// problems stay well conditioned.
+///
+/// The inner problems are solved with any solver that implements
+/// [`Minimizer`] (BFGS by default). The inner problem implements `Objective`,
+/// `Gradient` and `HessianVector`, and also `Hessian` when the problem has
+/// `Hessian`, so `TrustNCG` can be used for problems written with hyperdual
+/// numbers.
+///
+/// # Example
+/// ``` rust
+///use optimization::AugmentedLagrangian;
+///use optimization::problem::{FnProblem, FnConstraints};
+///use optimization::number_system::DualScalar;
+///use ndarray::arr1;
+///
+///// closest point to the origin on the line x + y = 1
+///let mut problem = FnProblem::new(2, |x: &[DualScalar]| x[0].powi(2) + x[1].powi(2));
+///let mut constraints = FnConstraints::new(
+///    2,
+///    |x: &[DualScalar]| vec![x[0] + x[1] - 1.0],
+///    |_: &[DualScalar]| vec![],
+///);
+///let sol = AugmentedLagrangian::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+///assert!(sol.success);
+///assert!((sol.x[0] - 0.5).abs() < 1e-6);
+/// ```
+pub struct AugmentedLagrangian<S = BFGS> {
+    // max number of outer iterations
+    pub i_max: u32,
+
+    // constraint tolerance
+    pub ctol: f64,
+
+    // tolerance of the gradient of the lagrangian
+    pub gtol: f64,
+
+    // initial penalty parameter
+    pub mu_0: f64,
+
+    // max penalty parameter, the problem is considered infeasible when the
+    // penalty reaches it without satisfying the constraints
+    pub mu_max: f64,
+
+    // factor used to increase the penalty parameter
+    pub mu_growth: f64,
+
+    // solver used for the inner minimizations
+    pub inner: S,
+
+    // holder for function calls done outside of the inner solver
+    f_calls: Cell<u32>,
+}
+
+
+impl AugmentedLagrangian {
+    pub fn new() -> AugmentedLagrangian {
+        AugmentedLagrangian::with_solver(BFGS::new())
+    }
+}
+
+
+impl Default for AugmentedLagrangian {
+    fn default() -> AugmentedLagrangian {
+        AugmentedLagrangian::new()
+    }
+}
+
+
+impl<S> AugmentedLagrangian<S> {
+    /// Creates the method with the given solver for the inner minimizations.
+    pub fn with_solver(inner: S) -> AugmentedLagrangian<S> {
+        AugmentedLagrangian {
+            i_max: 100,
+            ctol: 1e-8,
+            gtol: 1e-6,
+            mu_0: 10.0,
+            mu_max: 1e8,
+            mu_growth: 10.0,
+            inner,
+            f_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P, C>(&mut self, x0: &Array1<f64>, problem: &mut P, constraints: &mut C) -> Solution
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+        S: for<'a> Minimizer<AugmentedProblem<'a, P, C>>,
+    {
+        self.minimize_with_observer(x0, problem, constraints, &mut NoObserver)
+    }
+
+    /// Minimizes the problem calling the observer after each outer iteration.
+    /// The objective function value of the iteration is `f` (not the augmented
+    /// lagrangian) and the gradient norm is the one of the last inner
+    /// minimization.
+    pub fn minimize_with_observer<P, C, O>(&mut self,
+                                           x0: &Array1<f64>,
+                                           problem: &mut P,
+                                           constraints: &mut C,
+                                           observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+        S: for<'a> Minimizer<AugmentedProblem<'a, P, C>>,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        let mut solution = Solution::new(x0.len());
+        let mut x_k = x0.clone();
+        let mut sub = AugmentedProblem::new(problem, constraints, x0.len(), self.mu_0);
+
+        // violation measure of the previous iteration
+        let mut measure_old = f64::INFINITY;
+        let mut grad_norm = f64::NAN;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            let inner = self.inner.minimize(&x_k, &mut sub);
+            solution.f_evals += inner.f_evals;
+            solution.f_grad_evals += inner.f_grad_evals;
+            solution.f_hess_evals += inner.f_hess_evals;
+            grad_norm = inner.grad_norm;
+            if inner.status == Status::NonFinite || !inner.x.iter().all(|x| x.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            let step_length = (&inner.x - &x_k).dot(&(&inner.x - &x_k)).sqrt();
+            x_k = inner.x;
+
+            sub.eval_constraints(&x_k);
+            let measure = sub.violation_measure();
+            sub.update_multipliers();
+
+            // the gradient of the augmented lagrangian is the gradient of the
+            // lagrangian with the updated multipliers
+            if measure <= self.ctol && grad_norm <= self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            sub.problem.update_x(&x_k);
+            let iteration = Iteration {
+                k,
+                x_k: &x_k,
+                f_k: sub.problem.eval_real(),
+                grad_norm,
+                step_length,
+                trust_radius: None,
+            };
+            self.f_calls.set(self.f_calls.get() + 1);
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            if measure > self.ctol && measure > 0.25*measure_old {
+                if sub.mu >= self.mu_max {
+                    solution.status = Status::Infeasible;
+                    break;
+                }
+                sub.mu = (sub.mu*self.mu_growth).min(self.mu_max);
+            }
+            measure_old = measure;
+
+            k += 1;
+        }
+
+        sub.eval_constraints(&x_k);
+        sub.problem.update_x(&x_k);
+        solution.x.assign(&x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = sub.problem.eval_real();
+        self.f_calls.set(self.f_calls.get() + 1);
+        solution.f_evals += self.f_calls.get();
+        solution.grad_norm = grad_norm;
+        solution.constraint_violation = Some(sub.violation());
+        solution.equality_multipliers = Some(sub.lambda);
+        solution.inequality_multipliers = Some(sub.nu);
+        solution
+    }
+}
+
+
+/// Inner problem of the [`AugmentedLagrangian`] method.
+///
+/// It is the augmented lagrangian of a problem for fixed multipliers and
+/// penalty parameter. It is only created by the method, the type is public so
+/// that it can appear in the `Minimizer` bound of the inner solver.
+pub struct AugmentedProblem<'a, P, C> {
+    // original problem
+    problem: &'a mut P,
+
+    // constraints of the problem
+    constraints: &'a mut C,
+
+    // multipliers of the equality constraints
+    lambda: Array1<f64>,
+
+    // multipliers of the inequality constraints
+    nu: Array1<f64>,
+
+    // penalty parameter
+    mu: f64,
+
+    // current position
+    x: Array1<f64>,
+
+    // direction of the last call to move_step
+    p: Array1<f64>,
+
+    // equality constraints at x
+    c: Array1<f64>,
+
+    // inequality constraints at x
+    g: Array1<f64>,
+
+    // jacobian of the equality constraints
+    jac_c: Array2<f64>,
+
+    // jacobian of the inequality constraints
+    jac_g: Array2<f64>,
+}
+
+
+impl<'a, P, C> AugmentedProblem<'a, P, C>
+where
+    P: Objective + Gradient,
+    C: Constraints,
+{
+    fn new(problem: &'a mut P, constraints: &'a mut C, n: usize, mu: f64) -> AugmentedProblem<'a, P, C> {
+        let m_eq = constraints.num_equality();
+        let m_ineq = constraints.num_inequality();
+        AugmentedProblem {
+            problem,
+            constraints,
+            lambda: Array::zeros(m_eq),
+            nu: Array::zeros(m_ineq),
+            mu,
+            x: Array::zeros(n),
+            p: Array::zeros(n),
+            c: Array::zeros(m_eq),
+            g: Array::zeros(m_ineq),
+            jac_c: Array::zeros((m_eq, n)),
+            jac_g: Array::zeros((m_ineq, n)),
+        }
+    }
+
+    fn eval_constraints(&mut self, x: &Array1<f64>) {
+        self.constraints.equality(x, &mut self.c);
+        self.constraints.inequality(x, &mut self.g);
+    }
+
+    fn eval_jacobians(&mut self) {
+        self.constraints.equality_jacobian(&self.x, &mut self.jac_c);
+        self.constraints.inequality_jacobian(&self.x, &mut self.jac_g);
+    }
+
+    // Multipliers after the first order update, the augmented lagrangian
+    // gradient is grad(f) + J_c^T*y_eq + J_g^T*y_ineq
+    fn shifted_multipliers(&self) -> (Array1<f64>, Array1<f64>) {
+        let y_eq = &self.lambda + &(self.mu*&self.c);
+        let y_ineq = (&self.nu + &(self.mu*&self.g)).mapv(|y| y.max(0.0));
+        (y_eq, y_ineq)
+    }
+
+    fn update_multipliers(&mut self) {
+        let (y_eq, y_ineq) = self.shifted_multipliers();
+        self.lambda = y_eq;
+        self.nu = y_ineq;
+    }
+
+    // Max violation of the constraints at the last evaluated position
+    fn violation(&self) -> f64 {
+        let eq = self.c.iter().fold(0.0, |acc: f64, c| acc.max(c.abs()));
+        self.g.iter().fold(eq, |acc, g| acc.max(*g))
+    }
+
+    // Violation of the constraints and of the complementarity of the
+    // inequality multipliers (an inactive constraint must have a zero
+    // multiplier)
+    fn violation_measure(&self) -> f64 {
+        let eq = self.c.iter().fold(0.0, |acc: f64, c| acc.max(c.abs()));
+        self.g.iter().zip(self.nu.iter())
+            .fold(eq, |acc, (g, nu)| acc.max(g.max(-nu/self.mu).abs()))
+    }
+
+    fn constraints_grad(&mut self) -> Array1<f64> {
+        let x = self.x.clone();
+        self.eval_constraints(&x);
+        self.eval_jacobians();
+        let (y_eq, y_ineq) = self.shifted_multipliers();
+        self.jac_c.t().dot(&y_eq) + self.jac_g.t().dot(&y_ineq)
+    }
+}
+
+
+impl<'a, P, C> Objective for AugmentedProblem<'a, P, C>
+where
+    P: Objective + Gradient,
+    C: Constraints,
+{
+    fn eval(&mut self) {
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        let f = self.problem.eval_real();
+        let x = self.x.clone();
+        self.eval_constraints(&x);
+        let mu = self.mu;
+        let eq = self.lambda.dot(&self.c) + 0.5*mu*self.c.dot(&self.c);
+        let ineq = self.nu.iter().zip(self.g.iter())
+            .map(|(nu, g)| (nu + mu*g).max(0.0).powi(2) - nu.powi(2))
+            .sum::<f64>()/(2.0*mu);
+        f + eq + ineq
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x = x + &(alpha*p);
+        self.p.assign(p);
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<'a, P, C> Gradient for AugmentedProblem<'a, P, C>
+where
+    P: Objective + Gradient,
+    C: Constraints,
+{
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.problem.grad(output);
+        *output += &self.constraints_grad();
+    }
+
+    fn diff(&mut self) -> f64 {
+        let diff = self.problem.diff();
+        diff + self.constraints_grad().dot(&self.p)
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.problem.f_evals()
+    }
+}
+
+
+impl<'a, P, C> HessianVector for AugmentedProblem<'a, P, C>
+where
+    P: Objective + Gradient,
+    C: Constraints,
+{}
+
+
+// The hessian is `hess(f) + Σ y_i*hess(c_i) + μJ_c^T*J_c + Σ z_i*hess(g_i) +
+// μJ_a^T*J_a`, where `y` and `z` are the shifted multipliers and `J_a` are the
+// rows of the active inequality constraints (the ones with `z_i > 0`).
+impl<'a, P, C> Hessian for AugmentedProblem<'a, P, C>
+where
+    P: Objective + Gradient + Hessian,
+    C: Constraints,
+{
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        self.problem.hess(output);
+        let x = self.x.clone();
+        self.eval_constraints(&x);
+        self.eval_jacobians();
+        *output += &(self.mu*self.jac_c.t().dot(&self.jac_c));
+        let (y_eq, y_ineq) = self.shifted_multipliers();
+        let mut curvature = Array2::zeros(output.raw_dim());
+        self.constraints.equality_hessian(&x, &y_eq, &mut curvature);
+        *output += &curvature;
+        self.constraints.inequality_hessian(&x, &y_ineq, &mut curvature);
+        *output += &curvature;
+        for (i, y) in y_ineq.iter().enumerate() {
+            if *y > 0.0 {
+                let row = self.jac_g.row(i);
+                for j in 0..x.len() {
+                    for l in 0..x.len() {
+                        output[[j, l]] += self.mu*row[j]*row[l];
+                    }
+                }
+            }
+        }
+    }
+
+    fn grad_evals(&self) -> u32 {
+        self.problem.grad_evals()
+    }
+}
@@ -5,20 +5,23 @@
 
 use ndarray::{Array2, Array1, Array};
 use crate::number_system::Dual;
-use crate::Solution;
-use crate::LineSearch;
-use crate::StepValues;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::{LineSearch, StrongWolfe, StepValues};
 use std::cell::Cell;
 use crate::problem::{Objective, Gradient};
 
 
-pub struct BFGS {
+pub struct BFGS<L = StrongWolfe> {
     // max number of iterations
     pub i_max: u32,
 
     // gradient tolerance
     pub gtol: f64,
 
+    // line search used to find the step length
+    pub line_search: L,
+
     // current step direction
     p_k: Array1<f64>,
 
@@ -41,7 +44,16 @@ pub struct BFGS {
 
 impl BFGS {
     pub fn new() -> BFGS {
+        BFGS::with_line_search(StrongWolfe::new())
+    }
+}
+
+
+impl<L: LineSearch> BFGS<L> {
+    /// Creates the solver with the given line search.
+    pub fn with_line_search(line_search: L) -> BFGS<L> {
         BFGS {
+            line_search,
             i_max: 1000,
             gtol: 1e-6,
             p_k: Array1::zeros(1),
@@ -53,13 +65,27 @@ impl BFGS {
         }
     }
 
-    #[allow(non_snake_case)]
     pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
     where
         P: Objective + Gradient,
     {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        let line_search_evals = (self.line_search.f_evals(), self.line_search.f_grad_evals());
+        let diff_evals = problem.f_evals();
         self.set_up_parameters(x0, problem);
-        let mut line_search = LineSearch::new();
 
         let mut alpha_1: f64;
         let mut step: StepValues;
@@ -74,19 +100,19 @@ impl BFGS {
         let mut sk_sk: Array2<f64> = Array::zeros(I.dim());
         let mut H_k: Array2<f64> = Array::eye(x0.dim());
 
-        let mut solution = Solution {
-            x: Array::zeros(self.x_k.raw_dim()),
-            success: false,
-            iter_num: 0,
-            f_evals: 0,
-            f_grad_evals: 0,
-            f_hess_evals: 0,
-        };
+        let mut solution = Solution::new(x0.len());
+
+        // whether p_k is the steepest descent direction
+        let mut steepest = true;
 
         let mut k: u32 = 1;
         while k < self.i_max {
+            if !self.f_k.re.is_finite() || !self.f_k.du.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
             if self.f_k.du.dot(&self.f_k.du).sqrt() < self.gtol {
-                solution.success = true;
+                solution.status = Status::GradientTolerance;
                 break;
             }
 
@@ -95,7 +121,22 @@ impl BFGS {
 
             alpha_1 = 1.0;
             step = StepValues{x_k: &self.x_k, f_k: &self.f_k, p_k: &self.p_k, alpha_1};
-            alpha_k = line_search.find_alpha(step, problem);
+            alpha_k = match self.line_search.find_alpha(step, problem) {
+                Ok(alpha) => alpha,
+                Err(error) if steepest => {
+                    solution.status = Status::LineSearchFailure(error);
+                    break;
+                },
+                Err(_) => {
+                    // restart the hessian approximation and try the steepest
+                    // descent direction
+                    H_k = Array::eye(x0.dim());
+                    self.p_k = -&self.f_k.du;
+                    steepest = true;
+                    continue;
+                },
+            };
+            steepest = false;
             // do the operation x_new = x_k + alpha*p_k
             self.x_k += &(alpha_k*&self.p_k);
             self.f_k_old.re = self.f_k.re;
@@ -122,19 +163,35 @@ impl BFGS {
             H_k = (&I - rho*&sk_yk).dot(&H_k).dot(&(&I - rho*&yk_sk)) + rho*&sk_sk;
 
             self.p_k = -H_k.dot(&self.f_k.du);
-//             println!("k={}, p_k={}, x_k={}, alpha_k={}", k, self.p_k, self.x_k, alpha_k);
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.re,
+                grad_norm: self.f_k.du.dot(&self.f_k.du).sqrt(),
+                step_length: alpha_k,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
 
             k += 1;
         }
 
         solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
         solution.iter_num = k;
+        solution.f = self.f_k.re;
+        solution.grad_norm = self.f_k.du.dot(&self.f_k.du).sqrt();
         // Note that grad evaluations counts both actual grad evaluations
         // (multivariate) and diff evaluations (univariate) which may not be
         // what one expects since diff may evaluate the object function only once
         // while grad will evaluate it multiple times
-        solution.f_evals = self.f_calls.get() + line_search.f_calls.get();
-        solution.f_grad_evals = self.f_grad_calls.get() + line_search.f_grad_calls.get();
+        solution.f_evals = self.f_calls.get() + self.line_search.f_evals() - line_search_evals.0
+                            + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + self.line_search.f_grad_evals() - line_search_evals.1;
         solution
     }
 
@@ -0,0 +1,211 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::trust_region::{self, TrustRegion};
+use crate::linalg;
+use std::cell::Cell;
+use crate::problem::LeastSquares;
+
+
+pub struct GaussNewton {
+    // max number of iterations
+    pub i_max: u32,
+
+    // max trust region radius
+    pub delta_max: f64,
+
+    // min trust region radius, the solver stops when the radius is smaller
+    pub delta_min: f64,
+
+    // gradient tolerance (norm of J^T*r)
+    pub gtol: f64,
+
+    // current step
+    p_k: Array1<f64>,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // residuals at the current position
+    r_k: Array1<f64>,
+
+    // jacobian at the current position
+    j_k: Array2<f64>,
+
+    // current function evaluation f = 0.5*r^T*r
+    f_k: f64,
+
+    // gradient of f, g = J^T*r
+    g_k: Array1<f64>,
+
+    // holder for residual calls
+    f_calls: Cell<u32>,
+
+    // holder for jacobian calls
+    f_jac_calls: Cell<u32>,
+}
+
+
+impl GaussNewton {
+    pub fn new() -> GaussNewton {
+        GaussNewton {
+            i_max: 1000,
+            delta_max: 100.0,
+            delta_min: 1e-12,
+            gtol: 1e-6,
+            p_k: Array1::zeros(1),
+            x_k: Array1::zeros(1),
+            r_k: Array1::zeros(1),
+            j_k: Array2::zeros((1, 1)),
+            f_k: 0.0,
+            g_k: Array1::zeros(1),
+            f_calls: Cell::new(0),
+            f_jac_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: LeastSquares,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: LeastSquares,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_jac_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let mut region = TrustRegion::new(1.0, self.delta_max);
+
+        let mut x_new: Array1<f64> = Array::zeros(x0.raw_dim());
+        let mut r_new: Array1<f64> = Array::zeros(self.r_k.len());
+        // gauss-newton step
+        let mut p_gn: Array1<f64> = Array::zeros(x0.raw_dim());
+        // the gauss-newton step only changes when a step is accepted
+        let mut update_gn = true;
+
+        let mut solution = Solution::new(x0.len());
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.is_finite() || !self.g_k.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.g_k.dot(&self.g_k).sqrt() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+            if region.delta < self.delta_min {
+                solution.status = Status::TrustRadiusCollapsed;
+                break;
+            }
+
+            if update_gn {
+                p_gn = linalg::lstsq(&self.j_k, &(-&self.r_k));
+            }
+            self.p_k = trust_region::dogleg(&self.j_k, &self.g_k, &p_gn, region.delta);
+
+            x_new.assign(&(&self.x_k + &self.p_k));
+            self.f_calls.set(self.f_calls.get() + 1);
+            problem.residuals(&x_new, &mut r_new);
+            let f_new = 0.5*r_new.dot(&r_new);
+
+            // predicted reduction of the model m(p) = 0.5*|r + J*p|^2
+            let jp = self.j_k.dot(&self.p_k);
+            let predicted = -self.g_k.dot(&self.p_k) - 0.5*jp.dot(&jp);
+            let mut rho = (self.f_k - f_new)/predicted;
+            if !f_new.is_finite() || predicted <= 0.0 {
+                // the trial step left the region where the function is
+                // defined, so it is always rejected
+                rho = f64::NEG_INFINITY;
+            }
+
+            let mut step_length = self.p_k.dot(&self.p_k).sqrt();
+            update_gn = region.update(rho, step_length);
+            if update_gn {
+                self.x_k.assign(&x_new);
+                self.r_k.assign(&r_new);
+                self.f_k = f_new;
+                self.eval_jacobian(problem);
+            }
+            else {
+                step_length = 0.0;
+            }
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k,
+                grad_norm: self.g_k.dot(&self.g_k).sqrt(),
+                step_length,
+                trust_radius: Some(region.delta),
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k;
+        solution.grad_norm = self.g_k.dot(&self.g_k).sqrt();
+        solution.trust_radius = Some(region.delta);
+        solution.residuals = Some(self.r_k.clone());
+        solution.jacobian = Some(self.j_k.clone());
+        // residual evaluations are counted as function evaluations and
+        // jacobian evaluations as gradient evaluations
+        solution.f_evals = self.f_calls.get();
+        solution.f_grad_evals = self.f_jac_calls.get();
+        solution
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        let m = problem.num_residuals();
+        self.x_k = Array::zeros(x0.raw_dim());
+        self.x_k.assign(x0);
+        self.p_k = Array::zeros(x0.raw_dim());
+        self.r_k = Array::zeros(m);
+        self.j_k = Array::zeros((m, x0.len()));
+        self.g_k = Array::zeros(x0.raw_dim());
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&self.x_k, &mut self.r_k);
+        self.f_k = 0.5*self.r_k.dot(&self.r_k);
+        self.eval_jacobian(problem);
+    }
+
+    fn eval_jacobian<P>(&mut self, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        self.f_jac_calls.set(self.f_jac_calls.get() + 1);
+        problem.jacobian(&self.x_k, &mut self.j_k);
+        self.g_k = self.j_k.t().dot(&self.r_k);
+    }
+}
+
+
+impl Default for GaussNewton {
+    fn default() -> GaussNewton {
+        GaussNewton::new()
+    }
+}
@@ -0,0 +1,442 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array, s};
+use std::cell::Cell;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::LineSearchError;
+use crate::linalg;
+use crate::problem::{Objective, Gradient, Hessian, Constraints};
+
+
+/// Primal-dual interior point method.
+///
+/// The inequality constraints `g(x) <= 0` are written with slack variables as
+/// `g(x) + s = 0`, `s > 0`, and the positivity of the slacks is enforced with
+/// the logarithmic barrier `-μΣln(s_i)`. Each iteration takes a Newton step
+/// on the perturbed KKT conditions
+///
+/// `grad(f) + J_c^T*λ + J_g^T*z = 0,  c = 0,  g + s = 0,  s_i*z_i = μ`
+///
+/// where the hessian of the lagrangian is calculated with `Hessian::hess`
+/// (e.g. from a problem written with hyperdual numbers) plus the hessians of
+/// the constraints weighted by the multipliers (`Constraints::equality_hessian`
+/// and `Constraints::inequality_hessian`). When the hessian is not positive
+/// definite on the constraints it is shifted by a multiple of the identity.
+///
+/// The slacks and multipliers stay positive with the fraction-to-boundary
+/// rule, and the step length of the primal variables is found with a
+/// backtracking line search on the merit function
+/// `f - μΣln(s_i) + ρ(|c|_1 + |g + s|_1)`. The barrier parameter is decreased
+/// superlinearly (`μ = min(kappa_mu*μ, μ^theta_mu)`) once the barrier problem
+/// is solved accurately enough ("Numerical Optimization", chapter 19).
+///
+/// The slacks always stay positive, so the inequality constraints hold
+/// strictly at the iterates where `g + s = 0`. For linear constraints every
+/// step keeps `g + s = 0` once it holds, so the limits are never crossed.
+/// Nonlinear constraints are only linearized by the steps and the iterates
+/// may violate them slightly until convergence.
+///
+/// # Example
+/// ``` rust
+///use optimization::InteriorPoint;
+///use optimization::problem::{FnProblem, FnConstraints};
+///use optimization::number_system::{DualScalar, HyperDualScalar};
+///use ndarray::arr1;
+///
+///// angle limit -1 <= x0 <= 0.5 on a pendulum like energy
+///let mut problem = FnProblem::new(1, |x: &[HyperDualScalar]| -x[0].cos() - x[0]*2.0);
+///let mut constraints = FnConstraints::new(
+///    1,
+///    |_: &[DualScalar]| vec![],
+///    |x: &[DualScalar]| vec![x[0] - 0.5, -x[0] - 1.0],
+///);
+///let sol = InteriorPoint::new().minimize(&arr1(&[0.0]), &mut problem, &mut constraints);
+///assert!(sol.success);
+///assert!((sol.x[0] - 0.5).abs() < 1e-6);
+/// ```
+pub struct InteriorPoint {
+    // max number of iterations
+    pub i_max: u32,
+
+    // tolerance of the KKT conditions
+    pub tol: f64,
+
+    // initial barrier parameter
+    pub mu_0: f64,
+
+    // linear decrease factor of the barrier parameter
+    pub kappa_mu: f64,
+
+    // superlinear decrease exponent of the barrier parameter
+    pub theta_mu: f64,
+
+    // min fraction-to-boundary parameter
+    pub tau_min: f64,
+
+    // sufficient decrease parameter of the merit line search
+    pub c1: f64,
+
+    // max number of merit function evaluations of each line search
+    pub ls_max: u32,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>,
+
+    // holder for hessian calls
+    f_hess_calls: Cell<u32>,
+}
+
+
+// Values of the problem at a position
+struct Point {
+    x: Array1<f64>,
+    f: f64,
+    grad: Array1<f64>,
+    c: Array1<f64>,
+    jac_c: Array2<f64>,
+    g: Array1<f64>,
+    jac_g: Array2<f64>,
+}
+
+
+impl Point {
+    // Gradient of the lagrangian grad(f) + J_c^T*λ + J_g^T*z
+    fn lagrangian_grad(&self, lambda: &Array1<f64>, z: &Array1<f64>) -> Array1<f64> {
+        &self.grad + &self.jac_c.t().dot(lambda) + self.jac_g.t().dot(z)
+    }
+}
+
+
+impl InteriorPoint {
+    pub fn new() -> InteriorPoint {
+        InteriorPoint {
+            i_max: 200,
+            tol: 1e-8,
+            mu_0: 0.1,
+            kappa_mu: 0.2,
+            theta_mu: 1.5,
+            tau_min: 0.99,
+            c1: 1e-4,
+            ls_max: 40,
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+            f_hess_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P, C>(&mut self, x0: &Array1<f64>, problem: &mut P, constraints: &mut C) -> Solution
+    where
+        P: Objective + Gradient + Hessian,
+        C: Constraints,
+    {
+        self.minimize_with_observer(x0, problem, constraints, &mut NoObserver)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn minimize_with_observer<P, C, O>(&mut self,
+                                           x0: &Array1<f64>,
+                                           problem: &mut P,
+                                           constraints: &mut C,
+                                           observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient + Hessian,
+        C: Constraints,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        self.f_hess_calls.set(0);
+        let diff_evals = problem.f_evals();
+        let hess_evals = problem.grad_evals();
+        let n = x0.len();
+        let m_eq = constraints.num_equality();
+        let m_ineq = constraints.num_inequality();
+
+        let mut point = self.eval_point(x0, problem, constraints);
+        let mut mu = self.mu_0;
+        let mut s_k = point.g.mapv(|g| (-g).max(1e-2));
+        let mut z_k = s_k.mapv(|s| mu/s);
+        let mut lambda: Array1<f64> = Array::zeros(m_eq);
+        let mut rho = 0.0;
+        // last regularization of the hessian
+        let mut delta_w = 0.0;
+
+        let mut W: Array2<f64> = Array::zeros((n, n));
+        let mut W_c: Array2<f64> = Array::zeros((n, n));
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            let grad_L = point.lagrangian_grad(&lambda, &z_k);
+            if !point.f.is_finite() || !grad_L.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if kkt_error(&grad_L, &point, &s_k, &z_k, 0.0) <= self.tol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+            // solve the barrier problems until one is accurate enough
+            while kkt_error(&grad_L, &point, &s_k, &z_k, mu) <= 10.0*mu && mu > self.tol/10.0 {
+                mu = (self.kappa_mu*mu).min(mu.powf(self.theta_mu)).max(self.tol/10.0);
+            }
+
+            // hessian of the lagrangian
+            self.f_calls.set(self.f_calls.get() + 1);
+            self.f_hess_calls.set(self.f_hess_calls.get() + 1);
+            problem.update_x(&point.x);
+            problem.eval_real();
+            problem.hess(&mut W);
+            constraints.equality_hessian(&point.x, &lambda, &mut W_c);
+            W += &W_c;
+            constraints.inequality_hessian(&point.x, &z_k, &mut W_c);
+            W += &W_c;
+
+            // newton step of the primal variables with the slacks and the
+            // inequality multipliers eliminated
+            let sigma = &z_k/&s_k;
+            let r_g = &point.g + &s_k;
+            let mut H = W.clone();
+            for i in 0..m_ineq {
+                let row = point.jac_g.row(i);
+                for a in 0..n {
+                    for b in 0..n {
+                        H[[a, b]] += sigma[i]*row[a]*row[b];
+                    }
+                }
+            }
+            let rhs_x = -(&point.grad + &point.jac_g.t().dot(&(mu/&s_k + &sigma*&r_g)));
+            let (dx, lambda_new, dxHdx) = match newton_step(&H, &point.jac_c, &rhs_x, &point.c, &mut delta_w) {
+                Some(step) => step,
+                None => {
+                    solution.status = Status::NonFinite;
+                    break;
+                },
+            };
+            let ds = -&r_g - point.jac_g.dot(&dx);
+            let dz = mu/&s_k - &z_k - &sigma*&ds;
+            let dlambda = &lambda_new - &lambda;
+
+            // fraction-to-boundary rule
+            let tau = self.tau_min.max(1.0 - mu);
+            let alpha_s = max_step(&s_k, &ds, tau);
+            let alpha_z = max_step(&z_k, &dz, tau);
+
+            // penalty parameter that makes the step a descent direction of
+            // the merit function
+            let infeasibility = l1(&point.c) + l1(&r_g);
+            let dbarrier = point.grad.dot(&dx) - mu*(&ds/&s_k).sum();
+            if infeasibility > 0.0 {
+                let rho_trial = (dbarrier + 0.5*dxHdx.max(0.0))/(0.9*infeasibility);
+                if rho < rho_trial {
+                    rho = rho_trial + 1.0;
+                }
+            }
+            let merit_0 = point.f - mu*s_k.mapv(f64::ln).sum() + rho*infeasibility;
+            let dmerit_0 = dbarrier - rho*infeasibility;
+
+            let mut alpha = alpha_s;
+            let mut accepted = false;
+            for _ in 0..self.ls_max {
+                let x_trial = &point.x + &(alpha*&dx);
+                let s_trial = &s_k + &(alpha*&ds);
+                let merit = self.eval_merit(&x_trial, &s_trial, mu, rho, problem, constraints);
+                if merit <= merit_0 + self.c1*alpha*dmerit_0.min(0.0) {
+                    accepted = true;
+                    break;
+                }
+                alpha *= if merit.is_finite() { 0.5 } else { 0.1 };
+            }
+            if !accepted {
+                solution.status = Status::LineSearchFailure(LineSearchError::MaxIterations);
+                break;
+            }
+
+            point = self.eval_point(&(&point.x + &(alpha*&dx)), problem, constraints);
+            s_k += &(alpha*&ds);
+            lambda += &(alpha*&dlambda);
+            z_k += &(alpha_z*&dz);
+            // keep the multipliers close to the central path
+            for i in 0..m_ineq {
+                z_k[i] = z_k[i].max(mu/(1e10*s_k[i])).min(1e10*mu/s_k[i]);
+            }
+
+            let grad_L = point.lagrangian_grad(&lambda, &z_k);
+            let iteration = Iteration {
+                k,
+                x_k: &point.x,
+                f_k: point.f,
+                grad_norm: grad_L.dot(&grad_L).sqrt(),
+                step_length: alpha,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        let grad_L = point.lagrangian_grad(&lambda, &z_k);
+        let violation = point.g.iter().fold(max_abs(&point.c), |acc, g| acc.max(*g));
+        solution.x.assign(&point.x);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = point.f;
+        solution.grad_norm = grad_L.dot(&grad_L).sqrt();
+        solution.f_evals = self.f_calls.get() + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + problem.grad_evals() - hess_evals;
+        solution.f_hess_evals = self.f_hess_calls.get();
+        solution.constraint_violation = Some(violation);
+        solution.kkt_residual = Some(kkt_error(&grad_L, &point, &s_k, &z_k, 0.0));
+        solution.equality_multipliers = Some(lambda);
+        solution.inequality_multipliers = Some(z_k);
+        solution
+    }
+
+    fn eval_point<P, C>(&self, x: &Array1<f64>, problem: &mut P, constraints: &mut C) -> Point
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+    {
+        let n = x.len();
+        let m_eq = constraints.num_equality();
+        let m_ineq = constraints.num_inequality();
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(x);
+        let mut point = Point {
+            x: x.clone(),
+            f: problem.eval_real(),
+            grad: Array::zeros(n),
+            c: Array::zeros(m_eq),
+            jac_c: Array::zeros((m_eq, n)),
+            g: Array::zeros(m_ineq),
+            jac_g: Array::zeros((m_ineq, n)),
+        };
+        problem.grad(&mut point.grad);
+        constraints.equality(x, &mut point.c);
+        constraints.equality_jacobian(x, &mut point.jac_c);
+        constraints.inequality(x, &mut point.g);
+        constraints.inequality_jacobian(x, &mut point.jac_g);
+        point
+    }
+
+    fn eval_merit<P, C>(&self,
+                        x: &Array1<f64>,
+                        s_k: &Array1<f64>,
+                        mu: f64,
+                        rho: f64,
+                        problem: &mut P,
+                        constraints: &mut C) -> f64
+    where
+        P: Objective,
+        C: Constraints,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.update_x(x);
+        let f = problem.eval_real();
+        let mut c = Array::zeros(constraints.num_equality());
+        let mut g = Array::zeros(constraints.num_inequality());
+        constraints.equality(x, &mut c);
+        constraints.inequality(x, &mut g);
+        f - mu*s_k.mapv(f64::ln).sum() + rho*(l1(&c) + l1(&(g + s_k)))
+    }
+}
+
+
+impl Default for InteriorPoint {
+    fn default() -> InteriorPoint {
+        InteriorPoint::new()
+    }
+}
+
+
+// Solves the system [H + δI, J^T; J, 0][dx; λ] = [rhs; -c]. The shift δ is
+// increased until the curvature along dx is positive, which happens when H is
+// positive definite on the null space of J. Returns dx, λ and dx^T*H*dx, or
+// None when the shift blows up.
+#[allow(non_snake_case)]
+fn newton_step(H: &Array2<f64>,
+               J: &Array2<f64>,
+               rhs: &Array1<f64>,
+               c: &Array1<f64>,
+               delta_w: &mut f64) -> Option<(Array1<f64>, Array1<f64>, f64)>
+{
+    let n = rhs.len();
+    let m = c.len();
+    let mut K: Array2<f64> = Array::zeros((n + m, n + m));
+    K.slice_mut(s![..n, ..n]).assign(H);
+    K.slice_mut(s![n.., ..n]).assign(J);
+    K.slice_mut(s![..n, n..]).assign(&J.t());
+    let mut b: Array1<f64> = Array::zeros(n + m);
+    b.slice_mut(s![..n]).assign(rhs);
+    b.slice_mut(s![n..]).assign(&(-c));
+
+    // try first without shift, then from a fraction of the last shift
+    let mut delta = 0.0;
+    while delta < 1e20 {
+        for i in 0..n {
+            K[[i, i]] = H[[i, i]] + delta;
+        }
+        // redundant equality constraints make the system singular, they are
+        // regularized with a small negative diagonal
+        let solution = linalg::solve(&K, &b).or_else(|| {
+            let mut K_c = K.clone();
+            for i in n..n + m {
+                K_c[[i, i]] = -1e-8;
+            }
+            linalg::solve(&K_c, &b)
+        });
+        if let Some(solution) = solution {
+            let dx = solution.slice(s![..n]).to_owned();
+            let dxHdx = dx.dot(&H.dot(&dx)) + delta*dx.dot(&dx);
+            if dxHdx >= 1e-8*dx.dot(&dx) {
+                *delta_w = delta;
+                return Some((dx, solution.slice(s![n..]).to_owned(), dxHdx))
+            }
+        }
+        delta = if delta == 0.0 {
+            if *delta_w == 0.0 { 1e-4 } else { (*delta_w/3.0).max(1e-20) }
+        }
+        else {
+            8.0*delta
+        };
+    }
+    None
+}
+
+
+// Largest step in (0, 1] such that v + alpha*dv >= (1 - tau)*v
+fn max_step(v: &Array1<f64>, dv: &Array1<f64>, tau: f64) -> f64 {
+    v.iter().zip(dv.iter()).fold(1.0, |alpha: f64, (v, dv)| {
+        if *dv < 0.0 { alpha.min(-tau*v/dv) } else { alpha }
+    })
+}
+
+
+// Error of the KKT conditions of the barrier problem with parameter mu
+#[allow(non_snake_case)]
+fn kkt_error(grad_L: &Array1<f64>, point: &Point, s_k: &Array1<f64>, z_k: &Array1<f64>, mu: f64) -> f64 {
+    let complementarity = s_k.iter().zip(z_k.iter()).fold(0.0, |acc: f64, (s, z)| acc.max((s*z - mu).abs()));
+    max_abs(grad_L).max(max_abs(&point.c)).max(max_abs(&(&point.g + s_k))).max(complementarity)
+}
+
+
+fn max_abs(x: &Array1<f64>) -> f64 {
+    x.iter().fold(0.0, |acc: f64, x_i| acc.max(x_i.abs()))
+}
+
+
+fn l1(x: &Array1<f64>) -> f64 {
+    x.iter().map(|x_i| x_i.abs()).sum()
+}
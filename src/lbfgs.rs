@@ -0,0 +1,266 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array};
+use std::collections::VecDeque;
+use crate::number_system::Dual;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::{LineSearch, StrongWolfe, StepValues};
+use std::cell::Cell;
+use crate::problem::{Objective, Gradient};
+
+
+pub struct LBFGS<L = StrongWolfe> {
+    // max number of iterations
+    pub i_max: u32,
+
+    // gradient tolerance
+    pub gtol: f64,
+
+    // line search used to find the step length
+    pub line_search: L,
+
+    // number of correction pairs {s_k, y_k} kept in memory
+    pub m: usize,
+
+    // current step direction
+    p_k: Array1<f64>,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // current function evaluation
+    f_k: Dual,
+
+    // last function evaluation
+    f_k_old: Dual,
+
+    // history of the last m steps s_k = x_k+1 - x_k
+    s: VecDeque<Array1<f64>>,
+
+    // history of the last m gradient changes y_k = grad_k+1 - grad_k
+    y: VecDeque<Array1<f64>>,
+
+    // history of 1/(y_k^T s_k)
+    rho: VecDeque<f64>,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>
+}
+
+
+impl LBFGS {
+    pub fn new() -> LBFGS {
+        LBFGS::with_line_search(StrongWolfe::new())
+    }
+}
+
+
+impl<L: LineSearch> LBFGS<L> {
+    /// Creates the solver with the given line search.
+    pub fn with_line_search(line_search: L) -> LBFGS<L> {
+        LBFGS {
+            line_search,
+            i_max: 1000,
+            gtol: 1e-6,
+            m: 10,
+            p_k: Array1::zeros(1),
+            x_k: Array1::zeros(1),
+            f_k: Dual::new(1),
+            f_k_old: Dual::new(1),
+            s: VecDeque::new(),
+            y: VecDeque::new(),
+            rho: VecDeque::new(),
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective + Gradient,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        let line_search_evals = (self.line_search.f_evals(), self.line_search.f_grad_evals());
+        let diff_evals = problem.f_evals();
+        self.set_up_parameters(x0, problem);
+
+        let mut alpha_1: f64;
+        let mut step: StepValues;
+        let mut alpha_k: f64;
+
+        let mut s_k: Array1<f64>;
+        let mut y_k: Array1<f64>;
+        let mut yk_sk: f64;
+
+        let mut solution = Solution::new(x0.len());
+
+        // whether p_k is the steepest descent direction
+        let mut steepest = true;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.re.is_finite() || !self.f_k.du.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.f_k.du.dot(&self.f_k.du).sqrt() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            alpha_1 = 1.0;
+            step = StepValues{x_k: &self.x_k, f_k: &self.f_k, p_k: &self.p_k, alpha_1};
+            alpha_k = match self.line_search.find_alpha(step, problem) {
+                Ok(alpha) => alpha,
+                Err(error) if steepest => {
+                    solution.status = Status::LineSearchFailure(error);
+                    break;
+                },
+                Err(_) => {
+                    // forget the stored pairs and try the steepest descent
+                    // direction
+                    self.s.clear();
+                    self.y.clear();
+                    self.rho.clear();
+                    self.p_k = -&self.f_k.du;
+                    steepest = true;
+                    continue;
+                },
+            };
+            steepest = false;
+            // do the operation x_new = x_k + alpha*p_k
+            self.x_k += &(alpha_k*&self.p_k);
+            self.f_k_old.re = self.f_k.re;
+            self.f_k_old.du.assign(&self.f_k.du);
+
+            self.eval_func(problem);
+            s_k = alpha_k * &self.p_k;
+            y_k = &self.f_k.du - &self.f_k_old.du;
+
+            // The pair is only stored when the curvature condition holds,
+            // otherwise the implicit hessian approximation would not be
+            // positive definite
+            yk_sk = y_k.dot(&s_k);
+            if self.m > 0 && yk_sk > f64::EPSILON*y_k.dot(&y_k) {
+                while self.s.len() >= self.m {
+                    self.s.pop_front();
+                    self.y.pop_front();
+                    self.rho.pop_front();
+                }
+                self.s.push_back(s_k);
+                self.y.push_back(y_k);
+                self.rho.push_back(1.0/yk_sk);
+            }
+
+            self.two_loop_recursion();
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.re,
+                grad_norm: self.f_k.du.dot(&self.f_k.du).sqrt(),
+                step_length: alpha_k,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k.re;
+        solution.grad_norm = self.f_k.du.dot(&self.f_k.du).sqrt();
+        // Note that grad evaluations counts both actual grad evaluations
+        // (multivariate) and diff evaluations (univariate) which may not be
+        // what one expects since diff may evaluate the object function only once
+        // while grad will evaluate it multiple times
+        solution.f_evals = self.f_calls.get() + self.line_search.f_evals() - line_search_evals.0
+                            + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + self.line_search.f_grad_evals() - line_search_evals.1;
+        solution
+    }
+
+    // L-BFGS two-loop recursion obtained from "Numerical Optimization"
+    // (Algorithm 7.4). It calculates p_k = -H_k*grad_k without forming H_k.
+    fn two_loop_recursion(&mut self) {
+        let m = self.s.len();
+        let mut alpha = vec![0.0; m];
+
+        self.p_k.assign(&self.f_k.du);
+        for i in (0..m).rev() {
+            alpha[i] = self.rho[i]*self.s[i].dot(&self.p_k);
+            self.p_k.scaled_add(-alpha[i], &self.y[i]);
+        }
+
+        // initial hessian approximation H_k^0 = gamma_k*I (7.20)
+        if m > 0 {
+            let gamma = self.s[m-1].dot(&self.y[m-1])/self.y[m-1].dot(&self.y[m-1]);
+            self.p_k *= gamma;
+        }
+
+        for (i, alpha_i) in alpha.iter().enumerate() {
+            let beta = self.rho[i]*self.y[i].dot(&self.p_k);
+            self.p_k.scaled_add(alpha_i - beta, &self.s[i]);
+        }
+        self.p_k *= -1.0;
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: Objective + Gradient,
+    {
+        let mut new_x_k = Array::zeros(x0.raw_dim());
+        new_x_k.assign(x0);
+        self.x_k = new_x_k;
+        self.f_k = Dual::new(x0.len());
+        self.eval_func(problem);
+        self.f_k_old = Dual::new(x0.len());
+        self.f_k_old.re = 1.0;
+        self.p_k = -&self.f_k.du;
+        self.s.clear();
+        self.y.clear();
+        self.rho.clear();
+    }
+
+    fn eval_func<P>(&mut self, problem: &mut P)
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(&self.x_k);
+        self.f_k.re = problem.eval_real();
+        problem.grad(&mut self.f_k.du);
+    }
+}
+
+
+impl Default for LBFGS {
+    fn default() -> LBFGS {
+        LBFGS::new()
+    }
+}
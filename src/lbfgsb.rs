@@ -0,0 +1,477 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use std::collections::VecDeque;
+use crate::number_system::Dual;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::ActiveBound;
+use crate::linalg;
+use crate::line_search::LineSearchError;
+use std::cell::Cell;
+use crate::problem::{Objective, Gradient};
+
+
+// Bound constrained limited-memory BFGS. The algorithm is based on the paper
+// "A Limited Memory Algorithm for Bound Constrained Optimization" by Byrd, Lu,
+// Nocedal and Zhu. The limited-memory hessian approximation is kept in its
+// compact form B = theta*I - W*M*W^T, with W = [Y, theta*S].
+pub struct LBFGSB {
+    // max number of iterations
+    pub i_max: u32,
+
+    // projected gradient tolerance
+    pub gtol: f64,
+
+    // number of correction pairs {s_k, y_k} kept in memory
+    pub m: usize,
+
+    // lower bounds of the variables (can be -infinity)
+    pub lower: Array1<f64>,
+
+    // upper bounds of the variables (can be infinity)
+    pub upper: Array1<f64>,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // current function evaluation
+    f_k: Dual,
+
+    // history of the last m steps s_k = x_k+1 - x_k
+    s: VecDeque<Array1<f64>>,
+
+    // history of the last m gradient changes y_k = grad_k+1 - grad_k
+    y: VecDeque<Array1<f64>>,
+
+    // scaling factor of the compact representation
+    theta: f64,
+
+    // W = [Y, theta*S] matrix of the compact representation (n x 2m)
+    w: Array2<f64>,
+
+    // M matrix of the compact representation (2m x 2m)
+    mm: Array2<f64>,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>
+}
+
+
+impl LBFGSB {
+    pub fn new(lower: Array1<f64>, upper: Array1<f64>) -> LBFGSB {
+        LBFGSB {
+            i_max: 1000,
+            gtol: 1e-6,
+            m: 10,
+            lower,
+            upper,
+            x_k: Array1::zeros(1),
+            f_k: Dual::new(1),
+            s: VecDeque::new(),
+            y: VecDeque::new(),
+            theta: 1.0,
+            w: Array2::zeros((1, 0)),
+            mm: Array2::zeros((0, 0)),
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective + Gradient,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        assert_eq!(x0.len(), self.lower.len(), "lower bounds have wrong dimension");
+        assert_eq!(x0.len(), self.upper.len(), "upper bounds have wrong dimension");
+        let diff_evals = problem.f_evals();
+        self.set_up_parameters(x0, problem);
+
+        let mut x_cp: Array1<f64>;
+        let mut c: Array1<f64>;
+        let mut d_k: Array1<f64>;
+        let mut phi_0_grad: f64;
+        let mut alpha_k: f64;
+        let mut s_k: Array1<f64>;
+        let mut y_k: Array1<f64>;
+        let mut g_k_old: Array1<f64> = Array::zeros(x0.raw_dim());
+
+        let mut solution = Solution::new(x0.len());
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.re.is_finite() || !self.f_k.du.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.projected_grad_norm() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            let cauchy = self.cauchy_point();
+            x_cp = cauchy.0;
+            c = cauchy.1;
+            d_k = self.subspace_minimization(&x_cp, &c) - &self.x_k;
+            phi_0_grad = self.f_k.du.dot(&d_k);
+
+            if phi_0_grad >= 0.0 {
+                // The hessian approximation lost its positive definiteness
+                // (rounding errors), so we restart it and use the projected
+                // steepest descent direction.
+                self.s.clear();
+                self.y.clear();
+                self.update_compact_form();
+                d_k = self.cauchy_point().0 - &self.x_k;
+                phi_0_grad = self.f_k.du.dot(&d_k);
+                if phi_0_grad >= 0.0 {
+                    solution.status = Status::LineSearchFailure(LineSearchError::NotDescentDirection);
+                    break;
+                }
+            }
+
+            alpha_k = match self.backtracking(&d_k, phi_0_grad, problem) {
+                Ok(alpha) => alpha,
+                Err(error) if self.s.is_empty() => {
+                    solution.status = Status::LineSearchFailure(error);
+                    break;
+                },
+                Err(_) => {
+                    // restart the hessian approximation so the next direction
+                    // is the projected steepest descent direction
+                    self.s.clear();
+                    self.y.clear();
+                    self.update_compact_form();
+                    continue;
+                },
+            };
+
+            s_k = alpha_k*&d_k;
+            self.x_k += &s_k;
+            g_k_old.assign(&self.f_k.du);
+            self.eval_func(problem);
+            y_k = &self.f_k.du - &g_k_old;
+
+            // only store pairs that keep the approximation positive definite
+            if self.m > 0 && y_k.dot(&s_k) > f64::EPSILON*y_k.dot(&y_k) {
+                while self.s.len() >= self.m {
+                    self.s.pop_front();
+                    self.y.pop_front();
+                }
+                self.s.push_back(s_k);
+                self.y.push_back(y_k);
+                self.update_compact_form();
+            }
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.re,
+                grad_norm: self.projected_grad_norm(),
+                step_length: alpha_k,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k.re;
+        solution.grad_norm = self.projected_grad_norm();
+        solution.f_evals = self.f_calls.get() + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get();
+        solution.active_bounds = self.active_bounds();
+        solution
+    }
+
+    // Generalized Cauchy point (Algorithm CP of Byrd et al.). The piecewise
+    // linear path x(t) = P(x_k - t*g_k) is explored segment by segment until
+    // the first local minimizer of the quadratic model along the path is
+    // found. It returns the Cauchy point and the vector c = W^T(x_cp - x_k).
+    fn cauchy_point(&self) -> (Array1<f64>, Array1<f64>) {
+        let n = self.x_k.len();
+        let g = &self.f_k.du;
+        let mut x_cp = self.x_k.clone();
+        let mut d: Array1<f64> = Array::zeros(n);
+
+        // breakpoints t_i where each variable reaches one of its bounds
+        let mut breakpoints: Vec<(f64, usize)> = Vec::new();
+        for i in 0..n {
+            let t_i = if g[i] < 0.0 {
+                (self.x_k[i] - self.upper[i])/g[i]
+            }
+            else if g[i] > 0.0 {
+                (self.x_k[i] - self.lower[i])/g[i]
+            }
+            else {
+                f64::INFINITY
+            };
+            if t_i > 0.0 {
+                d[i] = -g[i];
+                if t_i < f64::INFINITY {
+                    breakpoints.push((t_i, i));
+                }
+            }
+        }
+        breakpoints.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut p = self.w.t().dot(&d);
+        let mut c: Array1<f64> = Array::zeros(p.raw_dim());
+        let mut f_grad = -d.dot(&d);
+        if f_grad == 0.0 {
+            return (x_cp, c);
+        }
+        let mut f_hess = -self.theta*f_grad - p.dot(&self.mm.dot(&p));
+        let mut dt_min = -f_grad/f_hess.max(f64::EPSILON);
+        let mut t_old = 0.0;
+
+        for &(t_b, b) in breakpoints.iter() {
+            let dt = t_b - t_old;
+            if dt_min < dt {
+                break;
+            }
+            // variable b is fixed at its bound from now on
+            x_cp[b] = if d[b] > 0.0 { self.upper[b] } else { self.lower[b] };
+            let z_b = x_cp[b] - self.x_k[b];
+            let g_b = g[b];
+            let w_b = self.w.row(b);
+            c.scaled_add(dt, &p);
+
+            f_grad += dt*f_hess + g_b.powi(2) + self.theta*g_b*z_b
+                      - g_b*w_b.dot(&self.mm.dot(&c));
+            f_hess += -self.theta*g_b.powi(2) - 2.0*g_b*w_b.dot(&self.mm.dot(&p))
+                      - g_b.powi(2)*w_b.dot(&self.mm.dot(&w_b));
+            p.scaled_add(g_b, &w_b);
+            d[b] = 0.0;
+            dt_min = -f_grad/f_hess.max(f64::EPSILON);
+            t_old = t_b;
+        }
+
+        dt_min = dt_min.max(0.0);
+        t_old += dt_min;
+        for i in 0..n {
+            if d[i] != 0.0 {
+                x_cp[i] = self.x_k[i] + t_old*d[i];
+            }
+        }
+        c.scaled_add(dt_min, &p);
+        (x_cp, c)
+    }
+
+    // Direct primal subspace minimization. The quadratic model is minimized
+    // over the variables that are free at the Cauchy point and the resulting
+    // step is truncated so it stays inside the bounds.
+    fn subspace_minimization(&self, x_cp: &Array1<f64>, c: &Array1<f64>) -> Array1<f64> {
+        let free: Vec<usize> = (0..x_cp.len())
+            .filter(|&i| x_cp[i] > self.lower[i] && x_cp[i] < self.upper[i])
+            .collect();
+        let mut x_bar = x_cp.clone();
+        if free.is_empty() {
+            return x_bar;
+        }
+
+        // reduced gradient r = Z^T(g + theta*(x_cp - x_k) - W*M*c)
+        let r = &self.f_k.du + &(self.theta*(x_cp - &self.x_k)) - self.w.dot(&self.mm.dot(c));
+        let mut d_u: Array1<f64> = free.iter().map(|&i| -r[i]/self.theta).collect();
+
+        let two_m = self.w.ncols();
+        if two_m > 0 {
+            // Sherman-Morrison-Woodbury formula for the inverse of the reduced
+            // hessian theta*I - Z^T*W*M*W^T*Z
+            let mut v: Array1<f64> = Array::zeros(two_m);
+            let mut wz_wz: Array2<f64> = Array::zeros((two_m, two_m));
+            for &i in free.iter() {
+                let w_i = self.w.row(i);
+                v.scaled_add(r[i], &w_i);
+                for a in 0..two_m {
+                    for b in 0..two_m {
+                        wz_wz[[a, b]] += w_i[a]*w_i[b];
+                    }
+                }
+            }
+            v = self.mm.dot(&v);
+            let n_mat = Array::eye(two_m) - self.mm.dot(&wz_wz)/self.theta;
+            match linalg::solve(&n_mat, &v) {
+                Some(v) => {
+                    for (j, &i) in free.iter().enumerate() {
+                        d_u[j] -= self.w.row(i).dot(&v)/self.theta.powi(2);
+                    }
+                }
+                None => return x_bar,
+            }
+        }
+
+        // largest step (up to one) that keeps the free variables feasible
+        let mut alpha_star: f64 = 1.0;
+        for (j, &i) in free.iter().enumerate() {
+            if d_u[j] > 0.0 {
+                alpha_star = alpha_star.min((self.upper[i] - x_cp[i])/d_u[j]);
+            }
+            else if d_u[j] < 0.0 {
+                alpha_star = alpha_star.min((self.lower[i] - x_cp[i])/d_u[j]);
+            }
+        }
+        for (j, &i) in free.iter().enumerate() {
+            x_bar[i] += alpha_star*d_u[j];
+        }
+        x_bar
+    }
+
+    // Backtracking line search with quadratic interpolation. Since both x_k
+    // and x_k + d_k are feasible, every trial point in [0, 1] is feasible.
+    fn backtracking<P>(&self,
+                       d_k: &Array1<f64>,
+                       phi_0_grad: f64,
+                       problem: &mut P) -> Result<f64, LineSearchError>
+    where
+        P: Objective + Gradient,
+    {
+        let c1 = 1e-4;
+        let phi_0 = self.f_k.re;
+        let mut alpha = 1.0;
+        let mut x_new: Array1<f64>;
+        let mut phi = phi_0;
+
+        for _ in 0..50 {
+            x_new = &self.x_k + &(alpha*d_k);
+            self.f_calls.set(self.f_calls.get() + 1);
+            problem.update_x(&x_new);
+            phi = problem.eval_real();
+            if phi <= phi_0 + c1*alpha*phi_0_grad {
+                return Ok(alpha);
+            }
+            // minimizer of the quadratic interpolating phi(0), phi'(0), phi(alpha)
+            let alpha_q = -phi_0_grad*alpha.powi(2)/(2.0*(phi - phi_0 - phi_0_grad*alpha));
+            alpha = if alpha_q.is_finite() {
+                alpha_q.max(0.1*alpha).min(0.5*alpha)
+            }
+            else {
+                0.5*alpha
+            };
+        }
+        if phi.is_finite() {
+            Err(LineSearchError::MaxIterations)
+        }
+        else {
+            Err(LineSearchError::NonFinite)
+        }
+    }
+
+    fn update_compact_form(&mut self) {
+        let n = self.x_k.len();
+        let m = self.s.len();
+        if m == 0 {
+            self.theta = 1.0;
+            self.w = Array::zeros((n, 0));
+            self.mm = Array::zeros((0, 0));
+            return;
+        }
+
+        self.theta = self.y[m-1].dot(&self.y[m-1])/self.s[m-1].dot(&self.y[m-1]);
+        self.w = Array::zeros((n, 2*m));
+        for j in 0..m {
+            self.w.column_mut(j).assign(&self.y[j]);
+            self.w.column_mut(m+j).assign(&(self.theta*&self.s[j]));
+        }
+
+        // M^-1 = [-D   L^T        ]
+        //        [ L   theta*S^T*S]
+        let mut m_inv: Array2<f64> = Array::zeros((2*m, 2*m));
+        for i in 0..m {
+            m_inv[[i, i]] = -self.s[i].dot(&self.y[i]);
+            for j in 0..i {
+                let l_ij = self.s[i].dot(&self.y[j]);
+                m_inv[[m+i, j]] = l_ij;
+                m_inv[[j, m+i]] = l_ij;
+            }
+            for j in 0..m {
+                m_inv[[m+i, m+j]] = self.theta*self.s[i].dot(&self.s[j]);
+            }
+        }
+        match linalg::inverse(&m_inv) {
+            Some(mm) => self.mm = mm,
+            None => {
+                self.s.clear();
+                self.y.clear();
+                self.update_compact_form();
+            }
+        }
+    }
+
+    fn projected_grad_norm(&self) -> f64 {
+        let mut norm = 0.0;
+        for i in 0..self.x_k.len() {
+            let x_i = (self.x_k[i] - self.f_k.du[i]).max(self.lower[i]).min(self.upper[i]);
+            norm += (x_i - self.x_k[i]).powi(2);
+        }
+        norm.sqrt()
+    }
+
+    fn active_bounds(&self) -> Vec<ActiveBound> {
+        let mut active = Vec::with_capacity(self.x_k.len());
+        for i in 0..self.x_k.len() {
+            if self.x_k[i] <= self.lower[i] {
+                active.push(ActiveBound::Lower);
+            }
+            else if self.x_k[i] >= self.upper[i] {
+                active.push(ActiveBound::Upper);
+            }
+            else {
+                active.push(ActiveBound::Free);
+            }
+        }
+        active
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: Objective + Gradient,
+    {
+        // make sure that the starting position is feasible
+        self.x_k = Array::zeros(x0.raw_dim());
+        for i in 0..x0.len() {
+            self.x_k[i] = x0[i].max(self.lower[i]).min(self.upper[i]);
+        }
+        self.f_k = Dual::new(x0.len());
+        self.eval_func(problem);
+        self.s.clear();
+        self.y.clear();
+        self.update_compact_form();
+    }
+
+    fn eval_func<P>(&mut self, problem: &mut P)
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(&self.x_k);
+        self.f_k.re = problem.eval_real();
+        problem.grad(&mut self.f_k.du);
+    }
+}
@@ -0,0 +1,290 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::linalg;
+use std::cell::Cell;
+use crate::problem::LeastSquares;
+
+
+pub struct LevenbergMarquardt {
+    // max number of iterations
+    pub i_max: u32,
+
+    // gradient tolerance (norm of J^T*r)
+    pub gtol: f64,
+
+    // initial damping parameter, relative to the scaling diag(J^T*J) of the
+    // damping term
+    pub tau: f64,
+
+    // max damping parameter, the solver stops when the damping is larger
+    pub lambda_max: f64,
+
+    // use geodesic acceleration (second order correction of the step)
+    pub geodesic_acceleration: bool,
+
+    // max ratio 2*|a|/|v| between the acceleration and the velocity for a
+    // step with geodesic acceleration to be accepted
+    pub acceleration_ratio: f64,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // residuals at the current position
+    r_k: Array1<f64>,
+
+    // jacobian at the current position
+    j_k: Array2<f64>,
+
+    // current function evaluation f = 0.5*r^T*r
+    f_k: f64,
+
+    // gradient of f, g = J^T*r
+    g_k: Array1<f64>,
+
+    // holder for residual calls
+    f_calls: Cell<u32>,
+
+    // holder for jacobian calls
+    f_jac_calls: Cell<u32>,
+}
+
+
+impl LevenbergMarquardt {
+    pub fn new() -> LevenbergMarquardt {
+        LevenbergMarquardt {
+            i_max: 1000,
+            gtol: 1e-6,
+            tau: 1e-3,
+            lambda_max: 1e16,
+            geodesic_acceleration: false,
+            acceleration_ratio: 0.75,
+            x_k: Array1::zeros(1),
+            r_k: Array1::zeros(1),
+            j_k: Array2::zeros((1, 1)),
+            f_k: 0.0,
+            g_k: Array1::zeros(1),
+            f_calls: Cell::new(0),
+            f_jac_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: LeastSquares,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: LeastSquares,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_jac_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let n = x0.len();
+
+        // J^T*J and scaling of the damping term
+        let mut jtj: Array2<f64> = self.j_k.t().dot(&self.j_k);
+        let mut scale: Array1<f64> = Array::zeros(n);
+        self.update_scale(&jtj, &mut scale);
+
+        // the scaling already has the units of J^T*J, so the damping parameter
+        // is dimensionless
+        let mut lambda = self.tau;
+        let mut nu = 2.0;
+
+        let mut x_new: Array1<f64> = Array::zeros(n);
+        let mut r_new: Array1<f64> = Array::zeros(self.r_k.len());
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.is_finite() || !self.g_k.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.g_k.dot(&self.g_k).sqrt() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+            if lambda > self.lambda_max {
+                solution.status = Status::TrustRadiusCollapsed;
+                break;
+            }
+
+            // damped normal equations (J^T*J + lambda*D)*v = -J^T*r
+            let mut a = jtj.clone();
+            for i in 0..n {
+                a[[i, i]] += lambda*scale[i];
+            }
+            let mut step_length = 0.0;
+            let step = match solve_scaled(&a, &scale, &(-&self.g_k)) {
+                Some(v) => self.accelerate(v, &a, &scale, problem),
+                None => None,
+            };
+
+            let mut rho = f64::NEG_INFINITY;
+            if let Some((step, v)) = &step {
+                x_new.assign(&(&self.x_k + step));
+                self.f_calls.set(self.f_calls.get() + 1);
+                problem.residuals(&x_new, &mut r_new);
+                let f_new = 0.5*r_new.dot(&r_new);
+                // predicted reduction of the linear model for the velocity v
+                let predicted = 0.5*v.dot(&(lambda*&scale*v - &self.g_k));
+                if f_new.is_finite() && predicted > 0.0 {
+                    rho = (self.f_k - f_new)/predicted;
+                }
+                if rho > 0.0 {
+                    step_length = step.dot(step).sqrt();
+                    self.x_k.assign(&x_new);
+                    self.r_k.assign(&r_new);
+                    self.f_k = f_new;
+                    self.eval_jacobian(problem);
+                    jtj = self.j_k.t().dot(&self.j_k);
+                    self.update_scale(&jtj, &mut scale);
+                }
+            }
+
+            // damping update from Nielsen (1999)
+            if rho > 0.0 {
+                lambda *= f64::max(1.0/3.0, 1.0 - (2.0*rho - 1.0).powi(3));
+                nu = 2.0;
+            }
+            else {
+                lambda *= nu;
+                nu *= 2.0;
+            }
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k,
+                grad_norm: self.g_k.dot(&self.g_k).sqrt(),
+                step_length,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k;
+        solution.grad_norm = self.g_k.dot(&self.g_k).sqrt();
+        solution.residuals = Some(self.r_k.clone());
+        solution.jacobian = Some(self.j_k.clone());
+        // residual evaluations are counted as function evaluations and
+        // jacobian evaluations as gradient evaluations
+        solution.f_evals = self.f_calls.get();
+        solution.f_grad_evals = self.f_jac_calls.get();
+        solution
+    }
+
+    // Adds the geodesic acceleration to the velocity v (Transtrum and Sethna,
+    // "Improvements to the Levenberg-Marquardt algorithm for nonlinear
+    // least-squares minimization"). The second directional derivative of the
+    // residuals is approximated with finite differences. Returns the step and
+    // the velocity, or None when the acceleration is too large compared with
+    // the velocity (the step is then rejected).
+    fn accelerate<P>(&self,
+                     v: Array1<f64>,
+                     a: &Array2<f64>,
+                     scale: &Array1<f64>,
+                     problem: &mut P) -> Option<(Array1<f64>, Array1<f64>)>
+    where
+        P: LeastSquares,
+    {
+        if !self.geodesic_acceleration {
+            return Some((v.clone(), v))
+        }
+        let h = 0.1;
+        let mut r_h: Array1<f64> = Array::zeros(self.r_k.len());
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&(&self.x_k + &(h*&v)), &mut r_h);
+        let r_vv = 2.0/h*((r_h - &self.r_k)/h - self.j_k.dot(&v));
+        let acc = solve_scaled(a, scale, &(-self.j_k.t().dot(&r_vv)))?;
+
+        let v_norm = v.dot(&v).sqrt();
+        if 2.0*acc.dot(&acc).sqrt() > self.acceleration_ratio*v_norm
+                || !acc.iter().all(|a_i| a_i.is_finite()) {
+            return None
+        }
+        Some((&v + &(0.5*&acc), v))
+    }
+
+    // Scaling of the damping term D = diag(J^T*J) (Marquardt). The scaling
+    // never decreases so the damping is invariant to the scale of the variables
+    // (Moré, "The Levenberg-Marquardt algorithm: implementation and theory").
+    fn update_scale(&self, jtj: &Array2<f64>, scale: &mut Array1<f64>) {
+        for i in 0..scale.len() {
+            scale[i] = f64::max(scale[i], jtj[[i, i]]);
+            if scale[i] == 0.0 {
+                scale[i] = 1.0;
+            }
+        }
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        let m = problem.num_residuals();
+        self.x_k = Array::zeros(x0.raw_dim());
+        self.x_k.assign(x0);
+        self.r_k = Array::zeros(m);
+        self.j_k = Array::zeros((m, x0.len()));
+        self.g_k = Array::zeros(x0.raw_dim());
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&self.x_k, &mut self.r_k);
+        self.f_k = 0.5*self.r_k.dot(&self.r_k);
+        self.eval_jacobian(problem);
+    }
+
+    fn eval_jacobian<P>(&mut self, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        self.f_jac_calls.set(self.f_jac_calls.get() + 1);
+        problem.jacobian(&self.x_k, &mut self.j_k);
+        self.g_k = self.j_k.t().dot(&self.r_k);
+    }
+}
+
+
+impl Default for LevenbergMarquardt {
+    fn default() -> LevenbergMarquardt {
+        LevenbergMarquardt::new()
+    }
+}
+
+
+// Solves the damped normal equations A*v = b in the variables scaled by
+// sqrt(D), where the diagonal of the matrix is close to one. J^T*J alone can
+// be too ill-conditioned for the LU factorization when the columns of the
+// jacobian have very different scales.
+fn solve_scaled(a: &Array2<f64>, scale: &Array1<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let d = scale.mapv(f64::sqrt);
+    let mut a_scaled = a.clone();
+    for ((i, j), a_ij) in a_scaled.indexed_iter_mut() {
+        *a_ij /= d[i]*d[j];
+    }
+    let u = linalg::solve(&a_scaled, &(b/&d))?;
+    Some(u/d)
+}
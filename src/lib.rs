@@ -9,12 +9,27 @@ pub mod geometry;
 pub mod problem;
 
 
-mod line_search;
-use line_search::LineSearch;
-use line_search::StepValues;
+pub mod line_search;
 
-mod solution;
-use solution::Solution;
+pub mod scalar;
+
+pub mod root;
+
+pub mod qp;
+
+pub mod projection;
+
+pub mod solution;
+pub use solution::{Solution, Status, ActiveBound};
+
+pub mod observer;
+
+mod linalg;
+
+mod trust_region;
+
+mod minimizer;
+pub use minimizer::Minimizer;
 
 mod steepest_descent;
 pub use steepest_descent::SteepestDescent;
@@ -25,5 +40,38 @@ pub use ncg::NCG;
 mod bfgs;
 pub use bfgs::BFGS;
 
+mod lbfgs;
+pub use lbfgs::LBFGS;
+
+mod lbfgsb;
+pub use lbfgsb::LBFGSB;
+
+mod newton_cg;
+pub use newton_cg::NewtonCG;
+
+mod levenberg_marquardt;
+pub use levenberg_marquardt::LevenbergMarquardt;
+
+mod gauss_newton;
+pub use gauss_newton::GaussNewton;
+
 mod trust_ncg;
 pub use trust_ncg::TrustNCG;
+
+mod nelder_mead;
+pub use nelder_mead::NelderMead;
+
+mod powell;
+pub use powell::Powell;
+
+mod augmented_lagrangian;
+pub use augmented_lagrangian::{AugmentedLagrangian, AugmentedProblem};
+
+mod sqp;
+pub use sqp::SQP;
+
+mod interior_point;
+pub use interior_point::InteriorPoint;
+
+mod spg;
+pub use spg::SPG;
@@ -0,0 +1,283 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+// Small dense linear algebra routines used internally by the solvers. They
+// are meant for the small matrices that appear in the algorithms (compact
+// quasi-Newton representations, subproblems, etc) and not as a general
+// purpose linear algebra library.
+
+
+use ndarray::{Array1, Array2, Array};
+
+
+// Solves the linear system A*x = b using gaussian elimination with partial
+// pivoting. Returns None when the matrix is (numerically) singular.
+pub fn solve(a: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let n = b.len();
+    let mut lu = a.to_owned();
+    let mut x = b.to_owned();
+    let scale = a.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    if scale == 0.0 {
+        return None;
+    }
+
+    for k in 0..n {
+        // find the pivot row
+        let mut pivot = k;
+        for i in k+1..n {
+            if lu[[i, k]].abs() > lu[[pivot, k]].abs() {
+                pivot = i;
+            }
+        }
+        if lu[[pivot, k]].abs() <= f64::EPSILON*scale {
+            return None;
+        }
+        if pivot != k {
+            for j in 0..n {
+                lu.swap([k, j], [pivot, j]);
+            }
+            x.swap(k, pivot);
+        }
+
+        for i in k+1..n {
+            let factor = lu[[i, k]]/lu[[k, k]];
+            for j in k..n {
+                lu[[i, j]] -= factor*lu[[k, j]];
+            }
+            x[i] -= factor*x[k];
+        }
+    }
+
+    // back substitution
+    for k in (0..n).rev() {
+        let mut sum = x[k];
+        for j in k+1..n {
+            sum -= lu[[k, j]]*x[j];
+        }
+        x[k] = sum/lu[[k, k]];
+    }
+    Some(x)
+}
+
+
+// Calculates the inverse of the square matrix A. Returns None when the matrix
+// is (numerically) singular.
+pub fn inverse(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = a.nrows();
+    let mut inv: Array2<f64> = Array::zeros((n, n));
+    let mut e: Array1<f64> = Array::zeros(n);
+    for j in 0..n {
+        e.fill(0.0);
+        e[j] = 1.0;
+        let col = solve(a, &e)?;
+        inv.column_mut(j).assign(&col);
+    }
+    Some(inv)
+}
+
+
+// Solves the linear least-squares problem min |A*x - b| using a householder
+// QR factorization with column pivoting. The columns that are (numerically)
+// linearly dependent are given a zero value, so rank deficient matrices give
+// a basic solution instead of a blown up one.
+pub fn lstsq(a: &Array2<f64>, b: &Array1<f64>) -> Array1<f64> {
+    lstsq_rank(a, b, default_rtol(a)).0
+}
+
+
+// Numerical rank of the matrix A, obtained from the same QR factorization as
+// lstsq.
+pub fn rank(a: &Array2<f64>) -> usize {
+    lstsq_rank(a, &Array::zeros(a.nrows()), default_rtol(a)).1
+}
+
+
+// Least-squares solution of A*x = b and rank of A. A column is considered
+// linearly dependent when its remaining norm is smaller than rtol times the
+// norm of the first pivot column.
+pub fn lstsq_rank(a: &Array2<f64>, b: &Array1<f64>, rtol: f64) -> (Array1<f64>, usize) {
+    let (m, n) = a.dim();
+    let mut qr = a.to_owned();
+    let mut qtb = b.to_owned();
+    let mut perm: Vec<usize> = (0..n).collect();
+    let mut norms: Vec<f64> = (0..n).map(|j| qr.column(j).dot(&qr.column(j))).collect();
+    let steps = usize::min(m, n);
+
+    let mut rank = 0;
+    let mut tol = 0.0;
+    for k in 0..steps {
+        // move the column with the largest remaining norm to the front
+        let mut pivot = k;
+        for j in k+1..n {
+            if norms[j] > norms[pivot] {
+                pivot = j;
+            }
+        }
+        if pivot != k {
+            for i in 0..m {
+                qr.swap([i, k], [i, pivot]);
+            }
+            norms.swap(k, pivot);
+            perm.swap(k, pivot);
+        }
+
+        // householder reflection that zeroes the column below the diagonal
+        let mut alpha = 0.0;
+        for i in k..m {
+            alpha += qr[[i, k]].powi(2);
+        }
+        alpha = alpha.sqrt();
+        if k == 0 {
+            tol = rtol*alpha;
+        }
+        if alpha <= tol {
+            break;
+        }
+        if qr[[k, k]] > 0.0 {
+            alpha = -alpha;
+        }
+        let mut v: Array1<f64> = Array::zeros(m - k);
+        for i in k..m {
+            v[i - k] = qr[[i, k]];
+        }
+        v[0] -= alpha;
+        let v_norm2 = v.dot(&v);
+        if v_norm2 > 0.0 {
+            for j in k..n {
+                let mut dot = 0.0;
+                for i in k..m {
+                    dot += v[i - k]*qr[[i, j]];
+                }
+                let factor = 2.0*dot/v_norm2;
+                for i in k..m {
+                    qr[[i, j]] -= factor*v[i - k];
+                }
+            }
+            let mut dot = 0.0;
+            for i in k..m {
+                dot += v[i - k]*qtb[i];
+            }
+            let factor = 2.0*dot/v_norm2;
+            for i in k..m {
+                qtb[i] -= factor*v[i - k];
+            }
+        }
+        rank += 1;
+
+        // remaining norms of the columns
+        for j in k+1..n {
+            norms[j] = 0.0;
+            for i in k+1..m {
+                norms[j] += qr[[i, j]].powi(2);
+            }
+        }
+    }
+
+    // back substitution with the first rank rows of R
+    let mut z: Array1<f64> = Array::zeros(n);
+    for k in (0..rank).rev() {
+        let mut sum = qtb[k];
+        for j in k+1..rank {
+            sum -= qr[[k, j]]*z[j];
+        }
+        z[k] = sum/qr[[k, k]];
+    }
+    let mut x: Array1<f64> = Array::zeros(n);
+    for k in 0..n {
+        x[perm[k]] = z[k];
+    }
+    (x, rank)
+}
+
+
+// Relative tolerance of the rank decisions of lstsq and rank
+fn default_rtol(a: &Array2<f64>) -> f64 {
+    f64::EPSILON*(usize::max(a.nrows(), a.ncols()) as f64)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, inverse, lstsq, rank};
+    use ndarray::{arr1, arr2, Array2, Array};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_solve() {
+        // the first pivot is zero so this system needs row swapping
+        let a = arr2(&[[0.0, 2.0, 1.0],
+                       [1.0, 1.0, 0.0],
+                       [2.0, 0.0, 3.0]]);
+        let b = arr1(&[5.0, 3.0, 11.0]);
+        let x = solve(&a, &b).unwrap();
+        let b_test = a.dot(&x);
+        for i in 0..3 {
+            assert_abs_diff_eq!(b_test[i], b[i], epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        let a = arr2(&[[1.0, 2.0],
+                       [2.0, 4.0]]);
+        let b = arr1(&[1.0, 1.0]);
+        assert!(solve(&a, &b).is_none());
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = arr2(&[[4.0, 1.0, 0.0],
+                       [1.0, 3.0, 1.0],
+                       [0.0, 1.0, 2.0]]);
+        let inv = inverse(&a).unwrap();
+        let eye: Array2<f64> = Array::eye(3);
+        let test = a.dot(&inv);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_abs_diff_eq!(test[[i, j]], eye[[i, j]], epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lstsq() {
+        // overdetermined system, the solution satisfies the normal equations
+        let a = arr2(&[[1.0, 1.0],
+                       [1.0, 2.0],
+                       [1.0, 3.0]]);
+        let b = arr1(&[1.0, 2.0, 2.0]);
+        let x = lstsq(&a, &b);
+        let residual = a.t().dot(&(a.dot(&x) - &b));
+        for i in 0..2 {
+            assert_abs_diff_eq!(residual[i], 0.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_lstsq_rank_deficient() {
+        // the second column is twice the first one
+        let a = arr2(&[[1.0, 2.0, 0.0],
+                       [2.0, 4.0, 1.0],
+                       [3.0, 6.0, 1.0]]);
+        let b = arr1(&[1.0, 3.0, 4.0]);
+        let x = lstsq(&a, &b);
+        assert!(x.iter().all(|x_i| x_i.is_finite()));
+        let residual = a.t().dot(&(a.dot(&x) - &b));
+        for i in 0..3 {
+            assert_abs_diff_eq!(residual[i], 0.0, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_rank() {
+        let a = arr2(&[[1.0, 2.0, 0.0],
+                       [2.0, 4.0, 1.0],
+                       [3.0, 6.0, 1.0]]);
+        assert_eq!(rank(&a), 2);
+        assert_eq!(rank(&a.t().to_owned()), 2);
+        assert_eq!(rank(&arr2(&[[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]])), 2);
+        assert_eq!(rank(&Array2::zeros((2, 2))), 0);
+    }
+}
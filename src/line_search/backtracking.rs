@@ -0,0 +1,150 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use crate::problem::{Objective, Gradient};
+use crate::line_search::{LineSearch, LineSearchError, StepValues};
+use std::cell::Cell;
+
+
+/// Backtracking line search with the Armijo (sufficient decrease) condition.
+///
+/// The step length is reduced until `phi(alpha) <= phi(0) + c1*alpha*phi'(0)`.
+/// The new trial step is the minimum of a quadratic (first reduction) or cubic
+/// interpolation of `phi`, safeguarded to the interval
+/// `[rho_lo*alpha, rho_hi*alpha]` ("Numerical Optimization", section 3.5).
+/// Only `Objective::eval_real` is called at the trial steps, so this line
+/// search is cheap when the gradient is expensive, but the curvature condition
+/// is not enforced.
+pub struct Backtracking {
+    // sufficient decrease parameter
+    pub c1: f64,
+
+    // min reduction factor of the step length
+    pub rho_lo: f64,
+
+    // max reduction factor of the step length
+    pub rho_hi: f64,
+
+    // max number of function evaluations
+    pub i_max: u32,
+
+    f_calls: Cell<u32>,
+}
+
+
+impl Backtracking {
+    pub fn new() -> Backtracking {
+        Backtracking {
+            c1: 1e-4,
+            rho_lo: 0.1,
+            rho_hi: 0.5,
+            i_max: 50,
+            f_calls: Cell::new(0),
+        }
+    }
+
+    fn eval_phi<P>(&self, step: &StepValues, alpha: f64, problem: &mut P) -> f64
+    where
+        P: Objective,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.move_step(step.x_k, step.p_k, alpha);
+        problem.eval_real()
+    }
+
+    // Minimum of the interpolation of phi. The quadratic uses phi(0),
+    // phi'(0) and phi(alpha), the cubic also uses the previous trial step.
+    fn interpolate(&self,
+                   phi_0: f64,
+                   dphi_0: f64,
+                   alpha: f64,
+                   phi: f64,
+                   previous: Option<(f64, f64)>) -> f64 {
+        match previous {
+            None => -dphi_0*alpha.powi(2)/(2.0*(phi - phi_0 - dphi_0*alpha)),
+            Some((alpha_p, phi_p)) => {
+                let r = phi - phi_0 - dphi_0*alpha;
+                let r_p = phi_p - phi_0 - dphi_0*alpha_p;
+                let den = alpha.powi(2)*alpha_p.powi(2)*(alpha - alpha_p);
+                let a = (alpha_p.powi(2)*r - alpha.powi(2)*r_p)/den;
+                let b = (-alpha_p.powi(3)*r + alpha.powi(3)*r_p)/den;
+                if a == 0.0 {
+                    // the cubic is a quadratic
+                    -dphi_0/(2.0*b)
+                }
+                else {
+                    (-b + (b.powi(2) - 3.0*a*dphi_0).sqrt())/(3.0*a)
+                }
+            },
+        }
+    }
+}
+
+
+impl LineSearch for Backtracking {
+    fn find_alpha<P>(&mut self, step: StepValues, problem: &mut P) -> Result<f64, LineSearchError>
+    where
+        P: Objective + Gradient,
+    {
+        let phi_0 = step.f_k.re;
+        let dphi_0 = step.f_k.du.dot(step.p_k);
+        if !phi_0.is_finite() || !dphi_0.is_finite() {
+            return Err(LineSearchError::NonFinite)
+        }
+        if dphi_0 >= 0.0 {
+            return Err(LineSearchError::NotDescentDirection)
+        }
+
+        let mut alpha = step.alpha_1;
+        let mut previous: Option<(f64, f64)> = None;
+        let mut phi = phi_0;
+        let mut i: u32 = 0;
+        while i < self.i_max {
+            // the step is too small to change x
+            if alpha <= f64::EPSILON*step.alpha_1 {
+                return Err(LineSearchError::IntervalCollapsed)
+            }
+            phi = self.eval_phi(&step, alpha, problem);
+            if phi <= phi_0 + self.c1*alpha*dphi_0 {
+                return Ok(alpha)
+            }
+            let mut alpha_new = if phi.is_finite() {
+                self.interpolate(phi_0, dphi_0, alpha, phi, previous)
+            }
+            else {
+                self.rho_lo*alpha
+            };
+            // the interpolation may fail (e.g. complex roots of the cubic)
+            if !alpha_new.is_finite() {
+                alpha_new = self.rho_hi*alpha;
+            }
+            alpha_new = alpha_new.max(self.rho_lo*alpha).min(self.rho_hi*alpha);
+            previous = Some((alpha, phi));
+            alpha = alpha_new;
+            i += 1;
+        }
+        if phi.is_finite() {
+            Err(LineSearchError::MaxIterations)
+        }
+        else {
+            Err(LineSearchError::NonFinite)
+        }
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.f_calls.get()
+    }
+
+    fn f_grad_evals(&self) -> u32 {
+        0
+    }
+}
+
+
+impl Default for Backtracking {
+    fn default() -> Backtracking {
+        Backtracking::new()
+    }
+}
@@ -0,0 +1,308 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use crate::number_system::DualScalar;
+use crate::problem::{Objective, Gradient};
+use crate::line_search::{LineSearch, LineSearchError, StepValues};
+use std::cell::Cell;
+
+
+/// Line search of Hager and Zhang.
+///
+/// This is the line search of CG_DESCENT ("Algorithm 851: CG_DESCENT, a
+/// conjugate gradient method with guaranteed descent"). The interval of
+/// uncertainty is reduced with a double secant step and the search stops when
+/// either the Wolfe conditions or the approximate Wolfe conditions are
+/// satisfied. The approximate Wolfe conditions only use the derivative of
+/// `phi`, so they can be satisfied with high accuracy near the minimum where
+/// the function values suffer from cancellation errors.
+///
+/// Only the weak Wolfe conditions are enforced, so the conjugate directions of
+/// `NCG` may not be descent directions with this line search.
+pub struct HagerZhang {
+    // sufficient decrease parameter
+    pub delta: f64,
+
+    // curvature parameter
+    pub sigma: f64,
+
+    // the function value is allowed to increase by epsilon*|f_k| when the
+    // approximate Wolfe conditions are used
+    pub epsilon: f64,
+
+    // position of the bisection step in the update of the interval
+    pub theta: f64,
+
+    // min decrease of the interval width after a double secant step, a
+    // bisection step is done otherwise
+    pub gamma: f64,
+
+    // expansion factor of the step length while bracketing the minimum
+    pub rho: f64,
+
+    // accept steps satisfying the approximate Wolfe conditions
+    pub approximate_wolfe: bool,
+
+    // max number of function evaluations
+    pub i_max: u32,
+
+    f_calls: Cell<u32>,
+    f_grad_calls: Cell<u32>,
+
+    // values at the start of the search
+    phi_0: DualScalar,
+
+    // max function value accepted by the approximate Wolfe conditions
+    phi_lim: f64,
+
+    // function evaluations of the current search
+    evals: u32,
+}
+
+
+// Step length and the value and derivative of phi at that step
+#[derive(Clone, Copy)]
+struct Point {
+    alpha: f64,
+    phi: DualScalar,
+}
+
+
+// Result of a stage of the search: either an accepted step length, a failure
+// or a new interval [a, b] bracketing the minimum.
+enum Search {
+    Done(f64),
+    Failed(LineSearchError),
+    Bracket(Point, Point),
+}
+
+
+impl HagerZhang {
+    pub fn new() -> HagerZhang {
+        HagerZhang {
+            delta: 0.1,
+            sigma: 0.9,
+            epsilon: 1e-6,
+            theta: 0.5,
+            gamma: 0.66,
+            rho: 5.0,
+            approximate_wolfe: true,
+            i_max: 100,
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+            phi_0: DualScalar::new(),
+            phi_lim: 0.0,
+            evals: 0,
+        }
+    }
+
+    // Evaluates phi at alpha. The error holds the end of the search when the
+    // step satisfies the termination conditions or when the max number of
+    // evaluations is reached.
+    fn trial<P>(&mut self, step: &StepValues, alpha: f64, problem: &mut P) -> Result<Point, Search>
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        self.evals += 1;
+        problem.move_step(step.x_k, step.p_k, alpha);
+        let re = problem.eval_real();
+        let du = problem.diff();
+        let point = Point{alpha, phi: DualScalar{re, du}};
+
+        if self.is_wolfe(&point) {
+            Err(Search::Done(alpha))
+        }
+        else if self.evals >= self.i_max && !(re.is_finite() && du.is_finite()) {
+            Err(Search::Failed(LineSearchError::NonFinite))
+        }
+        else if self.evals >= self.i_max {
+            Err(Search::Failed(LineSearchError::MaxIterations))
+        }
+        else {
+            Ok(point)
+        }
+    }
+
+    fn is_wolfe(&self, c: &Point) -> bool {
+        let phi_0 = self.phi_0;
+        if c.phi.du < self.sigma*phi_0.du {
+            return false
+        }
+        let wolfe = c.phi.re - phi_0.re <= self.delta*c.alpha*phi_0.du;
+        let approximate = self.approximate_wolfe
+            && (2.0*self.delta - 1.0)*phi_0.du >= c.phi.du
+            && c.phi.re <= self.phi_lim;
+        wolfe || approximate
+    }
+
+    // Finds an interval satisfying the opposite slope condition, starting
+    // from the trial step c.
+    fn bracket<P>(&mut self, step: &StepValues, mut c: Point, problem: &mut P) -> Search
+    where
+        P: Objective + Gradient,
+    {
+        let mut a = Point{alpha: 0.0, phi: self.phi_0};
+        loop {
+            if c.phi.du >= 0.0 {
+                return Search::Bracket(a, c)
+            }
+            if c.phi.re <= self.phi_lim {
+                a = c;
+                c = match self.trial(step, self.rho*c.alpha, problem) {
+                    Ok(point) => point,
+                    Err(search) => return search,
+                };
+            }
+            else {
+                // the function value is too large (or not finite)
+                let zero = Point{alpha: 0.0, phi: self.phi_0};
+                return self.update3(step, zero, c, problem)
+            }
+        }
+    }
+
+    // Updates the interval [a, b] with the trial step c.
+    fn update<P>(&mut self, step: &StepValues, a: Point, b: Point, alpha_c: f64, problem: &mut P) -> Search
+    where
+        P: Objective + Gradient,
+    {
+        let inside = alpha_c > a.alpha && alpha_c < b.alpha;
+        if !inside {
+            return Search::Bracket(a, b)
+        }
+        let c = match self.trial(step, alpha_c, problem) {
+            Ok(point) => point,
+            Err(search) => return search,
+        };
+        if c.phi.du >= 0.0 {
+            Search::Bracket(a, c)
+        }
+        else if c.phi.re <= self.phi_lim {
+            Search::Bracket(c, b)
+        }
+        else {
+            self.update3(step, a, c, problem)
+        }
+    }
+
+    // Shrinks the interval [a, b] when the derivative at b is negative but the
+    // function value is too large (steps U3a-U3c of the paper).
+    fn update3<P>(&mut self, step: &StepValues, mut a: Point, mut b: Point, problem: &mut P) -> Search
+    where
+        P: Objective + Gradient,
+    {
+        loop {
+            let alpha_d = (1.0 - self.theta)*a.alpha + self.theta*b.alpha;
+            let d = match self.trial(step, alpha_d, problem) {
+                Ok(point) => point,
+                Err(search) => return search,
+            };
+            if d.phi.du >= 0.0 {
+                return Search::Bracket(a, d)
+            }
+            if d.phi.re <= self.phi_lim {
+                a = d;
+            }
+            else {
+                b = d;
+            }
+        }
+    }
+
+    // Double secant step.
+    fn secant2<P>(&mut self, step: &StepValues, a: Point, b: Point, problem: &mut P) -> Search
+    where
+        P: Objective + Gradient,
+    {
+        let alpha_c = secant(&a, &b);
+        let (a_new, b_new) = match self.update(step, a, b, alpha_c, problem) {
+            Search::Bracket(a_new, b_new) => (a_new, b_new),
+            done => return done,
+        };
+        let alpha_cbar = if alpha_c == b_new.alpha {
+            secant(&b, &b_new)
+        }
+        else if alpha_c == a_new.alpha {
+            secant(&a, &a_new)
+        }
+        else {
+            return Search::Bracket(a_new, b_new)
+        };
+        self.update(step, a_new, b_new, alpha_cbar, problem)
+    }
+}
+
+
+// Step length where the secant of the derivative of phi through a and b is zero
+fn secant(a: &Point, b: &Point) -> f64 {
+    (a.alpha*b.phi.du - b.alpha*a.phi.du)/(b.phi.du - a.phi.du)
+}
+
+
+impl LineSearch for HagerZhang {
+    fn find_alpha<P>(&mut self, step: StepValues, problem: &mut P) -> Result<f64, LineSearchError>
+    where
+        P: Objective + Gradient,
+    {
+        self.phi_0 = DualScalar{re: step.f_k.re, du: step.f_k.du.dot(step.p_k)};
+        if !self.phi_0.re.is_finite() || !self.phi_0.du.is_finite() {
+            return Err(LineSearchError::NonFinite)
+        }
+        if self.phi_0.du >= 0.0 {
+            return Err(LineSearchError::NotDescentDirection)
+        }
+        self.phi_lim = self.phi_0.re + self.epsilon*self.phi_0.re.abs();
+        self.evals = 0;
+
+        let search = match self.trial(&step, step.alpha_1, problem) {
+            Ok(c) => self.bracket(&step, c, problem),
+            Err(search) => search,
+        };
+        let (mut a, mut b) = match search {
+            Search::Bracket(a, b) => (a, b),
+            Search::Done(alpha) => return Ok(alpha),
+            Search::Failed(error) => return Err(error),
+        };
+        loop {
+            // the interval collapsed without finding an acceptable step
+            if b.alpha - a.alpha <= f64::EPSILON*b.alpha {
+                return Err(LineSearchError::IntervalCollapsed)
+            }
+            let width = b.alpha - a.alpha;
+            let mut search = self.secant2(&step, a, b, problem);
+            if let Search::Bracket(a_new, b_new) = search {
+                if b_new.alpha - a_new.alpha > self.gamma*width {
+                    let alpha_c = 0.5*(a_new.alpha + b_new.alpha);
+                    search = self.update(&step, a_new, b_new, alpha_c, problem);
+                }
+            }
+            match search {
+                Search::Bracket(a_new, b_new) => {
+                    a = a_new;
+                    b = b_new;
+                },
+                Search::Done(alpha) => return Ok(alpha),
+                Search::Failed(error) => return Err(error),
+            }
+        }
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.f_calls.get()
+    }
+
+    fn f_grad_evals(&self) -> u32 {
+        self.f_grad_calls.get()
+    }
+}
+
+
+impl Default for HagerZhang {
+    fn default() -> HagerZhang {
+        HagerZhang::new()
+    }
+}
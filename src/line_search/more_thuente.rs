@@ -0,0 +1,348 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use crate::number_system::DualScalar;
+use crate::problem::{Objective, Gradient};
+use crate::line_search::{LineSearch, LineSearchError, StepValues};
+use std::cell::Cell;
+
+
+/// Line search of Moré and Thuente.
+///
+/// This is the line search of MINPACK-2 (`dcsrch`) described in "Line search
+/// algorithms with guaranteed sufficient decrease". It finds a step length
+/// that satisfies the strong Wolfe conditions and the safeguarded
+/// interpolation steps keep the interval of uncertainty shrinking, which makes
+/// it robust when the function is very flat near the minimum.
+pub struct MoreThuente {
+    // sufficient decrease parameter
+    pub c1: f64,
+
+    // curvature parameter
+    pub c2: f64,
+
+    // relative tolerance of the interval of uncertainty
+    pub xtol: f64,
+
+    // max number of function evaluations
+    pub i_max: u32,
+
+    // max step length
+    pub alpha_max: f64,
+
+    f_calls: Cell<u32>,
+    f_grad_calls: Cell<u32>,
+}
+
+
+// Interval of uncertainty used by the safeguarded step. The end points store
+// the step length and the function value and derivative at that step.
+struct Interval {
+    stx: f64,
+    fx: f64,
+    dx: f64,
+    sty: f64,
+    fy: f64,
+    dy: f64,
+    brackt: bool,
+}
+
+
+impl MoreThuente {
+    pub fn new() -> MoreThuente {
+        MoreThuente {
+            c1: 1e-4,
+            c2: 0.9,
+            xtol: 1e-10,
+            i_max: 100,
+            alpha_max: 1e10,
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+        }
+    }
+
+    fn eval_phi<P>(&self, step: &StepValues, alpha: f64, problem: &mut P) -> DualScalar
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.move_step(step.x_k, step.p_k, alpha);
+        let re = problem.eval_real();
+        let du = problem.diff();
+        DualScalar{re, du}
+    }
+}
+
+
+impl LineSearch for MoreThuente {
+    fn find_alpha<P>(&mut self, step: StepValues, problem: &mut P) -> Result<f64, LineSearchError>
+    where
+        P: Objective + Gradient,
+    {
+        let finit = step.f_k.re;
+        let ginit = step.f_k.du.dot(step.p_k);
+        if !finit.is_finite() || !ginit.is_finite() {
+            return Err(LineSearchError::NonFinite)
+        }
+        if ginit >= 0.0 {
+            return Err(LineSearchError::NotDescentDirection)
+        }
+        let gtest = self.c1*ginit;
+        let alpha_min = 0.0;
+        let mut width = self.alpha_max - alpha_min;
+        let mut width1 = 2.0*width;
+
+        let mut stage1 = true;
+        let mut interval = Interval {
+            stx: 0.0,
+            fx: finit,
+            dx: ginit,
+            sty: 0.0,
+            fy: finit,
+            dy: ginit,
+            brackt: false,
+        };
+        let mut stp = step.alpha_1.max(alpha_min).min(self.alpha_max);
+        let mut stmin = 0.0;
+        let mut stmax = stp + 4.0*stp;
+
+        let mut non_finite = false;
+        let mut i: u32 = 0;
+        while i < self.i_max {
+            let phi = self.eval_phi(&step, stp, problem);
+            let f = phi.re;
+            let g = phi.du;
+            non_finite = !f.is_finite() || !g.is_finite();
+            if non_finite {
+                // step back towards the best step found so far
+                stp = interval.stx + 0.5*(stp - interval.stx);
+                stmax = stp;
+                i += 1;
+                continue;
+            }
+            let ftest = finit + stp*gtest;
+
+            // strong Wolfe conditions
+            if f <= ftest && g.abs() <= -self.c2*ginit {
+                return Ok(stp)
+            }
+            if stage1 && f <= ftest && g >= 0.0 {
+                stage1 = false;
+            }
+            // the function keeps decreasing up to the max step length
+            if stp == self.alpha_max && f <= ftest && g <= gtest {
+                return Ok(stp)
+            }
+            // the interval is too small to make progress
+            if (interval.brackt && (stp <= stmin || stp >= stmax
+                                    || stmax - stmin <= self.xtol*stmax))
+                    || (stp == alpha_min && (f > ftest || g >= gtest)) {
+                return Err(LineSearchError::IntervalCollapsed)
+            }
+
+            // In the first stage a modified function is used so the step
+            // approaches a point with sufficient decrease
+            if stage1 && f <= interval.fx && f > ftest {
+                interval.fx -= interval.stx*gtest;
+                interval.fy -= interval.sty*gtest;
+                interval.dx -= gtest;
+                interval.dy -= gtest;
+                stp = interval.step(stp, f - stp*gtest, g - gtest, stmin, stmax);
+                interval.fx += interval.stx*gtest;
+                interval.fy += interval.sty*gtest;
+                interval.dx += gtest;
+                interval.dy += gtest;
+            }
+            else {
+                stp = interval.step(stp, f, g, stmin, stmax);
+            }
+
+            // force a sufficient decrease of the interval
+            if interval.brackt {
+                if (interval.sty - interval.stx).abs() >= 0.66*width1 {
+                    stp = interval.stx + 0.5*(interval.sty - interval.stx);
+                }
+                width1 = width;
+                width = (interval.sty - interval.stx).abs();
+            }
+
+            if interval.brackt {
+                stmin = interval.stx.min(interval.sty);
+                stmax = interval.stx.max(interval.sty);
+            }
+            else {
+                stmin = stp + 1.1*(stp - interval.stx);
+                stmax = stp + 4.0*(stp - interval.stx);
+            }
+            stp = stp.max(alpha_min).min(self.alpha_max);
+
+            // when no further progress can be made the best step is used
+            if interval.brackt && (stp <= stmin || stp >= stmax
+                                   || stmax - stmin <= self.xtol*stmax) {
+                stp = interval.stx;
+            }
+            i += 1;
+        }
+
+        if non_finite {
+            Err(LineSearchError::NonFinite)
+        }
+        else {
+            Err(LineSearchError::MaxIterations)
+        }
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.f_calls.get()
+    }
+
+    fn f_grad_evals(&self) -> u32 {
+        self.f_grad_calls.get()
+    }
+}
+
+
+impl Default for MoreThuente {
+    fn default() -> MoreThuente {
+        MoreThuente::new()
+    }
+}
+
+
+impl Interval {
+    // Safeguarded step of MINPACK-2 (dcstep). It updates the interval of
+    // uncertainty with the trial step stp and returns the new trial step.
+    fn step(&mut self, stp: f64, fp: f64, dp: f64, stpmin: f64, stpmax: f64) -> f64 {
+        let (stx, fx, dx) = (self.stx, self.fx, self.dx);
+        let (sty, fy, dy) = (self.sty, self.fy, self.dy);
+        let sgnd = dp*dx.signum();
+        let stpf;
+
+        if fp > fx {
+            // higher function value, the minimum is bracketed
+            let theta = 3.0*(fx - fp)/(stp - stx) + dx + dp;
+            let s = theta.abs().max(dx.abs()).max(dp.abs());
+            let mut gamma = s*((theta/s).powi(2) - (dx/s)*(dp/s)).sqrt();
+            if stp < stx {
+                gamma = -gamma;
+            }
+            let p = (gamma - dx) + theta;
+            let q = ((gamma - dx) + gamma) + dp;
+            let stpc = stx + p/q*(stp - stx);
+            let stpq = stx + dx/((fx - fp)/(stp - stx) + dx)/2.0*(stp - stx);
+            if (stpc - stx).abs() < (stpq - stx).abs() {
+                stpf = stpc;
+            }
+            else {
+                stpf = stpc + (stpq - stpc)/2.0;
+            }
+            self.brackt = true;
+        }
+        else if sgnd < 0.0 {
+            // derivatives with opposite sign, the minimum is bracketed
+            let theta = 3.0*(fx - fp)/(stp - stx) + dx + dp;
+            let s = theta.abs().max(dx.abs()).max(dp.abs());
+            let mut gamma = s*((theta/s).powi(2) - (dx/s)*(dp/s)).sqrt();
+            if stp > stx {
+                gamma = -gamma;
+            }
+            let p = (gamma - dp) + theta;
+            let q = ((gamma - dp) + gamma) + dx;
+            let stpc = stp + p/q*(stx - stp);
+            let stpq = stp + dp/(dp - dx)*(stx - stp);
+            if (stpc - stp).abs() > (stpq - stp).abs() {
+                stpf = stpc;
+            }
+            else {
+                stpf = stpq;
+            }
+            self.brackt = true;
+        }
+        else if dp.abs() < dx.abs() {
+            // the magnitude of the derivative decreases
+            let theta = 3.0*(fx - fp)/(stp - stx) + dx + dp;
+            let s = theta.abs().max(dx.abs()).max(dp.abs());
+            let mut gamma = s*f64::max(0.0, (theta/s).powi(2) - (dx/s)*(dp/s)).sqrt();
+            if stp > stx {
+                gamma = -gamma;
+            }
+            let p = (gamma - dp) + theta;
+            let q = (gamma + (dx - dp)) + gamma;
+            let r = p/q;
+            let stpc = if r < 0.0 && gamma != 0.0 {
+                stp + r*(stx - stp)
+            }
+            else if stp > stx {
+                stpmax
+            }
+            else {
+                stpmin
+            };
+            let stpq = stp + dp/(dp - dx)*(stx - stp);
+
+            if self.brackt {
+                let mut new_stp = if (stpc - stp).abs() < (stpq - stp).abs() {
+                    stpc
+                }
+                else {
+                    stpq
+                };
+                if stp > stx {
+                    new_stp = new_stp.min(stp + 0.66*(sty - stp));
+                }
+                else {
+                    new_stp = new_stp.max(stp + 0.66*(sty - stp));
+                }
+                stpf = new_stp;
+            }
+            else {
+                let new_stp = if (stpc - stp).abs() > (stpq - stp).abs() {
+                    stpc
+                }
+                else {
+                    stpq
+                };
+                stpf = new_stp.min(stpmax).max(stpmin);
+            }
+        }
+        else if self.brackt {
+            // the magnitude of the derivative doesn't decrease
+            let theta = 3.0*(fp - fy)/(sty - stp) + dy + dp;
+            let s = theta.abs().max(dy.abs()).max(dp.abs());
+            let mut gamma = s*((theta/s).powi(2) - (dy/s)*(dp/s)).sqrt();
+            if stp > sty {
+                gamma = -gamma;
+            }
+            let p = (gamma - dp) + theta;
+            let q = ((gamma - dp) + gamma) + dy;
+            stpf = stp + p/q*(sty - stp);
+        }
+        else if stp > stx {
+            stpf = stpmax;
+        }
+        else {
+            stpf = stpmin;
+        }
+
+        // update the interval of uncertainty
+        if fp > fx {
+            self.sty = stp;
+            self.fy = fp;
+            self.dy = dp;
+        }
+        else {
+            if sgnd < 0.0 {
+                self.sty = stx;
+                self.fy = fx;
+                self.dy = dx;
+            }
+            self.stx = stp;
+            self.fx = fp;
+            self.dx = dp;
+        }
+        stpf
+    }
+}
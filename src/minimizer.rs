@@ -0,0 +1,122 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::Array1;
+use crate::{Solution, SteepestDescent, NCG, BFGS, LBFGS, LBFGSB, NewtonCG, TrustNCG, NelderMead,
+            Powell, SPG};
+use crate::line_search::LineSearch;
+use crate::problem::{Objective, Gradient, Hessian, HessianVector};
+use crate::projection::Projection;
+
+
+/// Common interface of the unconstrained solvers.
+///
+/// Methods that solve a sequence of unconstrained problems (e.g.
+/// [`AugmentedLagrangian`](crate::AugmentedLagrangian)) are generic over this
+/// trait, so any solver whose requirements are met by the problem `P` can be
+/// used for the inner minimizations. It is implemented for all the solvers
+/// that work with the `Objective`, `Gradient`, `Hessian` and `HessianVector`
+/// traits.
+pub trait Minimizer<P> {
+    /// Minimizes `problem` starting from `x0`.
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution;
+}
+
+
+impl<P, L> Minimizer<P> for SteepestDescent<L>
+where
+    P: Objective + Gradient,
+    L: LineSearch,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        SteepestDescent::minimize(self, x0, problem)
+    }
+}
+
+impl<P, L> Minimizer<P> for NCG<L>
+where
+    P: Objective + Gradient,
+    L: LineSearch,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        NCG::minimize(self, x0, problem)
+    }
+}
+
+impl<P, L> Minimizer<P> for BFGS<L>
+where
+    P: Objective + Gradient,
+    L: LineSearch,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        BFGS::minimize(self, x0, problem)
+    }
+}
+
+impl<P, L> Minimizer<P> for LBFGS<L>
+where
+    P: Objective + Gradient,
+    L: LineSearch,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        LBFGS::minimize(self, x0, problem)
+    }
+}
+
+impl<P> Minimizer<P> for LBFGSB
+where
+    P: Objective + Gradient,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        LBFGSB::minimize(self, x0, problem)
+    }
+}
+
+impl<P, L> Minimizer<P> for NewtonCG<L>
+where
+    P: Objective + Gradient + HessianVector,
+    L: LineSearch,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        NewtonCG::minimize(self, x0, problem)
+    }
+}
+
+impl<P> Minimizer<P> for TrustNCG
+where
+    P: Objective + Gradient + Hessian,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        TrustNCG::minimize(self, x0, problem)
+    }
+}
+
+impl<P> Minimizer<P> for NelderMead
+where
+    P: Objective,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        NelderMead::minimize(self, x0, problem)
+    }
+}
+
+impl<P> Minimizer<P> for Powell
+where
+    P: Objective,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        Powell::minimize(self, x0, problem)
+    }
+}
+
+impl<P, S> Minimizer<P> for SPG<S>
+where
+    P: Objective + Gradient,
+    S: Projection,
+{
+    fn minimize(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution {
+        SPG::minimize(self, x0, problem)
+    }
+}
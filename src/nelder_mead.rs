@@ -0,0 +1,275 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use std::cell::Cell;
+use crate::problem::Objective;
+
+
+// Derivative-free simplex method of Nelder and Mead. The function is only
+// evaluated with `Objective::update_x` and `Objective::eval_real`, so it can
+// be used when the gradient is not available. With `adaptive` the expansion,
+// contraction and shrink coefficients depend on the dimension of the problem
+// (Gao and Han, "Implementing the Nelder-Mead simplex algorithm with adaptive
+// parameters"), which works better for problems with many variables.
+pub struct NelderMead {
+    // max number of iterations
+    pub i_max: u32,
+
+    // the solver stops when the simplex is smaller than xtol (max distance
+    // between the best vertex and the others) and the function values at the
+    // vertices differ less than ftol
+    pub xtol: f64,
+    pub ftol: f64,
+
+    // use the dimension dependent coefficients
+    pub adaptive: bool,
+
+    // relative size of the initial simplex around x0 (the step is absolute for
+    // the components of x0 that are zero)
+    pub initial_step: f64,
+
+    // initial simplex given as the n + 1 rows of the matrix. It replaces the
+    // simplex built around x0.
+    pub initial_simplex: Option<Array2<f64>>,
+
+    // vertices of the simplex
+    simplex: Vec<Array1<f64>>,
+
+    // function values at the vertices
+    values: Vec<f64>,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+}
+
+
+impl NelderMead {
+    pub fn new() -> NelderMead {
+        NelderMead {
+            i_max: 10000,
+            xtol: 1e-8,
+            ftol: 1e-8,
+            adaptive: false,
+            initial_step: 0.05,
+            initial_simplex: None,
+            simplex: Vec::new(),
+            values: Vec::new(),
+            f_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let n = x0.len();
+
+        // reflection, expansion, contraction and shrink coefficients
+        let (rho, chi, psi, sigma) = if self.adaptive {
+            let n = n as f64;
+            (1.0, 1.0 + 2.0/n, 0.75 - 0.5/n, 1.0 - 1.0/n)
+        }
+        else {
+            (1.0, 2.0, 0.5, 0.5)
+        };
+
+        let mut centroid: Array1<f64> = Array::zeros(n);
+        let mut x_best_old: Array1<f64> = Array::zeros(n);
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            self.sort();
+            if !self.values[0].is_finite() {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.size() <= self.xtol && self.values[n] - self.values[0] <= self.ftol {
+                solution.status = Status::XTolerance;
+                break;
+            }
+            x_best_old.assign(&self.simplex[0]);
+
+            // centroid of all the vertices except the worst one
+            centroid.fill(0.0);
+            for x_i in &self.simplex[..n] {
+                centroid += x_i;
+            }
+            centroid /= n as f64;
+
+            let x_r = &centroid + &(rho*(&centroid - &self.simplex[n]));
+            let f_r = self.eval(&x_r, problem);
+
+            if f_r < self.values[0] {
+                let x_e = &centroid + &(chi*(&x_r - &centroid));
+                let f_e = self.eval(&x_e, problem);
+                if f_e < f_r {
+                    self.replace_worst(x_e, f_e);
+                }
+                else {
+                    self.replace_worst(x_r, f_r);
+                }
+            }
+            else if f_r < self.values[n - 1] {
+                self.replace_worst(x_r, f_r);
+            }
+            else if f_r < self.values[n] {
+                // outside contraction
+                let x_c = &centroid + &(psi*(&x_r - &centroid));
+                let f_c = self.eval(&x_c, problem);
+                if f_c <= f_r {
+                    self.replace_worst(x_c, f_c);
+                }
+                else {
+                    self.shrink(sigma, problem);
+                }
+            }
+            else {
+                // inside contraction
+                let x_c = &centroid + &(psi*(&self.simplex[n] - &centroid));
+                let f_c = self.eval(&x_c, problem);
+                if f_c < self.values[n] {
+                    self.replace_worst(x_c, f_c);
+                }
+                else {
+                    self.shrink(sigma, problem);
+                }
+            }
+
+            self.sort();
+            let step = &self.simplex[0] - &x_best_old;
+            let iteration = Iteration {
+                k,
+                x_k: &self.simplex[0],
+                f_k: self.values[0],
+                grad_norm: f64::NAN,
+                step_length: step.dot(&step).sqrt(),
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.simplex[0]);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.values[0];
+        solution.f_evals = self.f_calls.get();
+        solution
+    }
+
+    // Evaluates the objective function. NaN values are replaced by infinity so
+    // the vertex is the worst one of the simplex.
+    fn eval<P>(&self, x: &Array1<f64>, problem: &mut P) -> f64
+    where
+        P: Objective,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.update_x(x);
+        let f = problem.eval_real();
+        if f.is_nan() {
+            f64::INFINITY
+        }
+        else {
+            f
+        }
+    }
+
+    fn replace_worst(&mut self, x: Array1<f64>, f: f64) {
+        let n = self.values.len() - 1;
+        self.simplex[n] = x;
+        self.values[n] = f;
+    }
+
+    // Moves all the vertices towards the best one
+    fn shrink<P>(&mut self, sigma: f64, problem: &mut P)
+    where
+        P: Objective,
+    {
+        for i in 1..self.simplex.len() {
+            let x_i = &self.simplex[0] + &(sigma*(&self.simplex[i] - &self.simplex[0]));
+            self.values[i] = self.eval(&x_i, problem);
+            self.simplex[i] = x_i;
+        }
+    }
+
+    // Sorts the vertices from the best to the worst function value
+    fn sort(&mut self) {
+        let mut order: Vec<usize> = (0..self.values.len()).collect();
+        // the sort is stable so ties keep the older vertex first
+        order.sort_by(|&a, &b| self.values[a].total_cmp(&self.values[b]));
+        self.simplex = order.iter().map(|&i| self.simplex[i].clone()).collect();
+        self.values = order.iter().map(|&i| self.values[i]).collect();
+    }
+
+    // Max distance (infinity norm) between the best vertex and the others
+    fn size(&self) -> f64 {
+        let mut size: f64 = 0.0;
+        for x_i in &self.simplex[1..] {
+            for (a, b) in x_i.iter().zip(self.simplex[0].iter()) {
+                size = size.max((a - b).abs());
+            }
+        }
+        size
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: Objective,
+    {
+        let n = x0.len();
+        self.simplex = match &self.initial_simplex {
+            Some(simplex) => {
+                assert_eq!(simplex.dim(), (n + 1, n), "initial simplex has wrong dimension");
+                simplex.outer_iter().map(|x_i| x_i.to_owned()).collect()
+            },
+            None => {
+                let mut simplex = vec![x0.clone()];
+                for i in 0..n {
+                    let mut x_i = x0.clone();
+                    if x_i[i] != 0.0 {
+                        x_i[i] *= 1.0 + self.initial_step;
+                    }
+                    else {
+                        x_i[i] = 0.005*self.initial_step;
+                    }
+                    simplex.push(x_i);
+                }
+                simplex
+            },
+        };
+        let simplex = std::mem::take(&mut self.simplex);
+        self.values = simplex.iter().map(|x_i| self.eval(x_i, problem)).collect();
+        self.simplex = simplex;
+    }
+}
+
+
+impl Default for NelderMead {
+    fn default() -> NelderMead {
+        NelderMead::new()
+    }
+}
@@ -0,0 +1,274 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array};
+use crate::number_system::Dual;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::{LineSearch, StrongWolfe, StepValues};
+use std::cell::Cell;
+use crate::problem::{Objective, Gradient, HessianVector};
+
+
+pub struct NewtonCG<L = StrongWolfe> {
+    // max number of iterations
+    pub i_max: u32,
+
+    // gradient tolerance
+    pub gtol: f64,
+
+    // line search used to find the step length
+    pub line_search: L,
+
+    // max number of conjugate gradient iterations per step
+    pub j_max: u32,
+
+    // current step direction
+    p_k: Array1<f64>,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // current function evaluation
+    f_k: Dual,
+
+    // conjugate gradient residual
+    r: Array1<f64>,
+
+    // conjugate gradient direction
+    d: Array1<f64>,
+
+    // hessian-vector product B*d
+    bd: Array1<f64>,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>,
+
+    // holder for hessian-vector product calls
+    f_hess_calls: Cell<u32>,
+}
+
+
+impl NewtonCG {
+    pub fn new() -> NewtonCG {
+        NewtonCG::with_line_search(StrongWolfe::new())
+    }
+}
+
+
+impl<L: LineSearch> NewtonCG<L> {
+    /// Creates the solver with the given line search.
+    pub fn with_line_search(line_search: L) -> NewtonCG<L> {
+        NewtonCG {
+            line_search,
+            i_max: 1000,
+            gtol: 1e-6,
+            j_max: 200,
+            p_k: Array1::zeros(1),
+            x_k: Array1::zeros(1),
+            f_k: Dual::new(1),
+            r: Array1::zeros(1),
+            d: Array1::zeros(1),
+            bd: Array1::zeros(1),
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+            f_hess_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective + Gradient + HessianVector,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient + HessianVector,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        self.f_hess_calls.set(0);
+        let line_search_evals = (self.line_search.f_evals(), self.line_search.f_grad_evals());
+        let diff_evals = problem.f_evals();
+        self.set_up_parameters(x0, problem);
+
+        let mut alpha_1: f64;
+        let mut step: StepValues;
+        let mut alpha_k: f64;
+
+        let mut solution = Solution::new(x0.len());
+
+        // whether p_k is the steepest descent direction
+        let mut steepest = false;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.re.is_finite() || !self.f_k.du.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if self.f_k.du.dot(&self.f_k.du).sqrt() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            if !steepest {
+                self.newton_step(problem);
+            }
+
+            alpha_1 = 1.0;
+            step = StepValues{x_k: &self.x_k, f_k: &self.f_k, p_k: &self.p_k, alpha_1};
+            alpha_k = match self.line_search.find_alpha(step, problem) {
+                Ok(alpha) => alpha,
+                Err(error) if steepest => {
+                    solution.status = Status::LineSearchFailure(error);
+                    break;
+                },
+                Err(_) => {
+                    // try the steepest descent direction instead of the newton
+                    // step
+                    self.p_k = -&self.f_k.du;
+                    steepest = true;
+                    continue;
+                },
+            };
+            steepest = false;
+            // do the operation x_new = x_k + alpha*p_k
+            self.x_k += &(alpha_k*&self.p_k);
+            self.eval_func(problem);
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.re,
+                grad_norm: self.f_k.du.dot(&self.f_k.du).sqrt(),
+                step_length: alpha_k,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k.re;
+        solution.grad_norm = self.f_k.du.dot(&self.f_k.du).sqrt();
+        // Note that grad evaluations counts both actual grad evaluations
+        // (multivariate) and diff evaluations (univariate) which may not be
+        // what one expects since diff may evaluate the object function only once
+        // while grad will evaluate it multiple times
+        solution.f_evals = self.f_calls.get() + self.line_search.f_evals() - line_search_evals.0
+                            + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + self.line_search.f_grad_evals() - line_search_evals.1;
+        solution.f_hess_evals = self.f_hess_calls.get();
+        solution
+    }
+
+    // Line search Newton-CG step obtained from "Numerical Optimization"
+    // (Algorithm 7.1). The newton equations B*p = -grad are solved
+    // approximately with conjugate gradient iterations that stop when the
+    // residual is small enough or when a direction of negative curvature is
+    // found.
+    fn newton_step<P>(&mut self, problem: &mut P)
+    where
+        P: Objective + Gradient + HessianVector,
+    {
+        let grad_norm = self.f_k.du.dot(&self.f_k.du).sqrt();
+        let eps = f64::min(0.5, grad_norm.sqrt())*grad_norm;
+
+        // z_0 = 0, r_0 = grad, d_0 = -r_0
+        self.p_k.fill(0.0);
+        self.r.assign(&self.f_k.du);
+        self.d.assign(&self.f_k.du);
+        self.d *= -1.0;
+        let mut rr = self.r.dot(&self.r);
+
+        let mut j: u32 = 0;
+        while j < self.j_max {
+            self.f_hess_calls.set(self.f_hess_calls.get() + 1);
+            self.f_grad_calls.set(self.f_grad_calls.get() + problem.hess_vec_grad_evals());
+            problem.hess_vec(&self.x_k, &self.d, &mut self.bd);
+            let dbd = self.d.dot(&self.bd);
+
+            if dbd <= 0.0 {
+                // negative curvature, the steepest descent direction is used
+                // when it is found in the first iteration
+                if j == 0 {
+                    self.p_k.assign(&self.d);
+                }
+                break;
+            }
+
+            let alpha = rr/dbd;
+            self.p_k.scaled_add(alpha, &self.d);
+            self.r.scaled_add(alpha, &self.bd);
+
+            let rr_new = self.r.dot(&self.r);
+            if rr_new.sqrt() < eps {
+                break;
+            }
+            let beta = rr_new/rr;
+            rr = rr_new;
+            self.d *= beta;
+            self.d -= &self.r;
+
+            j += 1;
+        }
+
+        // safeguard in case the approximated hessian-vector products didn't
+        // give a descent direction
+        if self.p_k.dot(&self.f_k.du) >= 0.0 || !self.p_k.iter().all(|p| p.is_finite()) {
+            self.p_k.assign(&self.f_k.du);
+            self.p_k *= -1.0;
+        }
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: Objective + Gradient + HessianVector,
+    {
+        let mut new_x_k = Array::zeros(x0.raw_dim());
+        new_x_k.assign(x0);
+        self.x_k = new_x_k;
+        self.f_k = Dual::new(x0.len());
+        self.eval_func(problem);
+        self.p_k = -&self.f_k.du;
+        self.r = Array::zeros(x0.raw_dim());
+        self.d = Array::zeros(x0.raw_dim());
+        self.bd = Array::zeros(x0.raw_dim());
+    }
+
+    fn eval_func<P>(&mut self, problem: &mut P)
+    where
+        P: Objective + Gradient + HessianVector,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(&self.x_k);
+        self.f_k.re = problem.eval_real();
+        problem.grad(&mut self.f_k.du);
+    }
+}
+
+
+impl Default for NewtonCG {
+    fn default() -> NewtonCG {
+        NewtonCG::new()
+    }
+}
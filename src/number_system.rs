@@ -20,3 +20,12 @@ pub use hyperdual::HyperDual;
 mod hyperdual_scalar;
 mod hyperdual_scalar_math;
 pub use hyperdual_scalar::HyperDualScalar;
+
+
+mod real;
+pub use real::Real;
+
+
+mod tape;
+mod tape_math;
+pub use tape::{Tape, Var, Adjoints};
@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 
+use std::f64::consts::LN_10;
 use crate::number_system::Dual;
 
 impl Dual {
@@ -27,4 +28,150 @@ impl Dual {
             du: &self.du * m * self.re.powi(n-1),
         }
     }
+
+    /// Square root.
+    pub fn sqrt(&self) -> Dual {
+        let x = self.re;
+        let s = x.sqrt();
+        self.chain(s, 0.5/s)
+    }
+
+    /// Exponential function.
+    pub fn exp(&self) -> Dual {
+        let x = self.re;
+        let e = x.exp();
+        self.chain(e, e)
+    }
+
+    /// Natural logarithm.
+    pub fn ln(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.ln(), 1.0/x)
+    }
+
+    /// Base 10 logarithm.
+    pub fn log10(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.log10(), 1.0/(x*LN_10))
+    }
+
+    /// Raises the number to a real power.
+    pub fn powf(&self, n: f64) -> Dual {
+        let x = self.re;
+        self.chain(x.powf(n), n*x.powf(n - 1.0))
+    }
+
+    /// Tangent function.
+    pub fn tan(&self) -> Dual {
+        let x = self.re;
+        let t = x.tan();
+        self.chain(t, 1.0 + t.powi(2))
+    }
+
+    /// Arcsine function.
+    pub fn asin(&self) -> Dual {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.asin(), 1.0/d.sqrt())
+    }
+
+    /// Arccosine function.
+    pub fn acos(&self) -> Dual {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.acos(), -1.0/d.sqrt())
+    }
+
+    /// Arctangent function.
+    pub fn atan(&self) -> Dual {
+        let x = self.re;
+        let d = 1.0 + x.powi(2);
+        self.chain(x.atan(), 1.0/d)
+    }
+
+    /// Hyperbolic sine function.
+    pub fn sinh(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.sinh(), x.cosh())
+    }
+
+    /// Hyperbolic cosine function.
+    pub fn cosh(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.cosh(), x.sinh())
+    }
+
+    /// Hyperbolic tangent function.
+    pub fn tanh(&self) -> Dual {
+        let x = self.re;
+        let t = x.tanh();
+        self.chain(t, 1.0 - t.powi(2))
+    }
+
+    /// Absolute value. The derivative at zero is taken from the sign of zero.
+    pub fn abs(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.abs(), x.signum())
+    }
+
+    /// Reciprocal (1/x).
+    pub fn recip(&self) -> Dual {
+        let x = self.re;
+        self.chain(x.recip(), -1.0/x.powi(2))
+    }
+
+    /// Cube root.
+    pub fn cbrt(&self) -> Dual {
+        let x = self.re;
+        let c = x.cbrt();
+        self.chain(c, 1.0/(3.0*c.powi(2)))
+    }
+
+    /// Raises the number to a power given by another number (`self^other`). The
+    /// base must be positive.
+    pub fn pow(&self, other: &Dual) -> Dual {
+        let a = self.re;
+        let b = other.re;
+        let g = a.powf(b);
+        let ln_a = a.ln();
+        self.chain2(other, g, b*a.powf(b - 1.0), g*ln_a)
+    }
+
+    /// Four quadrant arctangent of `self` (y) and `other` (x).
+    pub fn atan2(&self, other: &Dual) -> Dual {
+        let a = self.re;
+        let b = other.re;
+        let r2 = a.powi(2) + b.powi(2);
+        self.chain2(other, a.atan2(b), b/r2, -a/r2)
+    }
+
+    /// Length of the hypotenuse of a right-angle triangle with legs `self`
+    /// and `other`.
+    pub fn hypot(&self, other: &Dual) -> Dual {
+        let a = self.re;
+        let b = other.re;
+        let h = a.hypot(b);
+        self.chain2(other, h, a/h, b/h)
+    }
+}
+
+
+impl Dual {
+    // Applies the chain rule for a function with value f and derivative df
+    // at the real part of the number.
+    fn chain(&self, f: f64, df: f64) -> Dual {
+        Dual {
+            re: f,
+            du: &self.du * df,
+        }
+    }
+
+    // Applies the chain rule for a function g(self, other) with value g and
+    // partial derivatives g_a and g_b.
+    fn chain2(&self, other: &Dual, g: f64, g_a: f64, g_b: f64) -> Dual {
+        Dual {
+            re: g,
+            du: &self.du * g_a + &other.du * g_b,
+        }
+    }
 }
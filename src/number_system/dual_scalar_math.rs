@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 
+use std::f64::consts::LN_10;
 use crate::number_system::DualScalar;
 
 
@@ -30,4 +31,170 @@ impl DualScalar {
             du: self.du * m * self.re.powi(n-1),
         }
     }
+
+    /// Square root.
+    #[inline(always)]
+    pub fn sqrt(&self) -> DualScalar {
+        let x = self.re;
+        let s = x.sqrt();
+        self.chain(s, 0.5/s)
+    }
+
+    /// Exponential function.
+    #[inline(always)]
+    pub fn exp(&self) -> DualScalar {
+        let x = self.re;
+        let e = x.exp();
+        self.chain(e, e)
+    }
+
+    /// Natural logarithm.
+    #[inline(always)]
+    pub fn ln(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.ln(), 1.0/x)
+    }
+
+    /// Base 10 logarithm.
+    #[inline(always)]
+    pub fn log10(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.log10(), 1.0/(x*LN_10))
+    }
+
+    /// Raises the number to a real power.
+    #[inline(always)]
+    pub fn powf(&self, n: f64) -> DualScalar {
+        let x = self.re;
+        self.chain(x.powf(n), n*x.powf(n - 1.0))
+    }
+
+    /// Tangent function.
+    #[inline(always)]
+    pub fn tan(&self) -> DualScalar {
+        let x = self.re;
+        let t = x.tan();
+        self.chain(t, 1.0 + t.powi(2))
+    }
+
+    /// Arcsine function.
+    #[inline(always)]
+    pub fn asin(&self) -> DualScalar {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.asin(), 1.0/d.sqrt())
+    }
+
+    /// Arccosine function.
+    #[inline(always)]
+    pub fn acos(&self) -> DualScalar {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.acos(), -1.0/d.sqrt())
+    }
+
+    /// Arctangent function.
+    #[inline(always)]
+    pub fn atan(&self) -> DualScalar {
+        let x = self.re;
+        let d = 1.0 + x.powi(2);
+        self.chain(x.atan(), 1.0/d)
+    }
+
+    /// Hyperbolic sine function.
+    #[inline(always)]
+    pub fn sinh(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.sinh(), x.cosh())
+    }
+
+    /// Hyperbolic cosine function.
+    #[inline(always)]
+    pub fn cosh(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.cosh(), x.sinh())
+    }
+
+    /// Hyperbolic tangent function.
+    #[inline(always)]
+    pub fn tanh(&self) -> DualScalar {
+        let x = self.re;
+        let t = x.tanh();
+        self.chain(t, 1.0 - t.powi(2))
+    }
+
+    /// Absolute value. The derivative at zero is taken from the sign of zero.
+    #[inline(always)]
+    pub fn abs(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.abs(), x.signum())
+    }
+
+    /// Reciprocal (1/x).
+    #[inline(always)]
+    pub fn recip(&self) -> DualScalar {
+        let x = self.re;
+        self.chain(x.recip(), -1.0/x.powi(2))
+    }
+
+    /// Cube root.
+    #[inline(always)]
+    pub fn cbrt(&self) -> DualScalar {
+        let x = self.re;
+        let c = x.cbrt();
+        self.chain(c, 1.0/(3.0*c.powi(2)))
+    }
+
+    /// Raises the number to a power given by another number (`self^other`). The
+    /// base must be positive.
+    #[inline(always)]
+    pub fn pow(&self, other: &DualScalar) -> DualScalar {
+        let a = self.re;
+        let b = other.re;
+        let g = a.powf(b);
+        let ln_a = a.ln();
+        self.chain2(other, g, b*a.powf(b - 1.0), g*ln_a)
+    }
+
+    /// Four quadrant arctangent of `self` (y) and `other` (x).
+    #[inline(always)]
+    pub fn atan2(&self, other: &DualScalar) -> DualScalar {
+        let a = self.re;
+        let b = other.re;
+        let r2 = a.powi(2) + b.powi(2);
+        self.chain2(other, a.atan2(b), b/r2, -a/r2)
+    }
+
+    /// Length of the hypotenuse of a right-angle triangle with legs `self`
+    /// and `other`.
+    #[inline(always)]
+    pub fn hypot(&self, other: &DualScalar) -> DualScalar {
+        let a = self.re;
+        let b = other.re;
+        let h = a.hypot(b);
+        self.chain2(other, h, a/h, b/h)
+    }
+}
+
+
+impl DualScalar {
+    // Applies the chain rule for a function with value f and derivative df
+    // at the real part of the number.
+    #[inline(always)]
+    fn chain(&self, f: f64, df: f64) -> DualScalar {
+        DualScalar {
+            re: f,
+            du: self.du * df,
+        }
+    }
+
+    // Applies the chain rule for a function g(self, other) with value g and
+    // partial derivatives g_a and g_b.
+    #[inline(always)]
+    fn chain2(&self, other: &DualScalar, g: f64, g_a: f64, g_b: f64) -> DualScalar {
+        DualScalar {
+            re: g,
+            du: g_a * self.du + g_b * other.du,
+        }
+    }
 }
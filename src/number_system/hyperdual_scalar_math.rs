@@ -3,6 +3,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 
+use std::f64::consts::LN_10;
 use crate::number_system::HyperDualScalar as HDual;
 
 
@@ -38,4 +39,163 @@ impl HDual {
         }
     }
 
+    /// Square root.
+    pub fn sqrt(&self) -> HDual {
+        let x = self.re;
+        let s = x.sqrt();
+        self.chain(s, 0.5/s, -0.25/(s*x))
+    }
+
+    /// Exponential function.
+    pub fn exp(&self) -> HDual {
+        let x = self.re;
+        let e = x.exp();
+        self.chain(e, e, e)
+    }
+
+    /// Natural logarithm.
+    pub fn ln(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.ln(), 1.0/x, -1.0/x.powi(2))
+    }
+
+    /// Base 10 logarithm.
+    pub fn log10(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.log10(), 1.0/(x*LN_10), -1.0/(x.powi(2)*LN_10))
+    }
+
+    /// Raises the number to a real power.
+    pub fn powf(&self, n: f64) -> HDual {
+        let x = self.re;
+        self.chain(x.powf(n), n*x.powf(n - 1.0), n*(n - 1.0)*x.powf(n - 2.0))
+    }
+
+    /// Tangent function.
+    pub fn tan(&self) -> HDual {
+        let x = self.re;
+        let t = x.tan();
+        self.chain(t, 1.0 + t.powi(2), 2.0*t*(1.0 + t.powi(2)))
+    }
+
+    /// Arcsine function.
+    pub fn asin(&self) -> HDual {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.asin(), 1.0/d.sqrt(), x/d.powf(1.5))
+    }
+
+    /// Arccosine function.
+    pub fn acos(&self) -> HDual {
+        let x = self.re;
+        let d = 1.0 - x.powi(2);
+        self.chain(x.acos(), -1.0/d.sqrt(), -x/d.powf(1.5))
+    }
+
+    /// Arctangent function.
+    pub fn atan(&self) -> HDual {
+        let x = self.re;
+        let d = 1.0 + x.powi(2);
+        self.chain(x.atan(), 1.0/d, -2.0*x/d.powi(2))
+    }
+
+    /// Hyperbolic sine function.
+    pub fn sinh(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.sinh(), x.cosh(), x.sinh())
+    }
+
+    /// Hyperbolic cosine function.
+    pub fn cosh(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.cosh(), x.sinh(), x.cosh())
+    }
+
+    /// Hyperbolic tangent function.
+    pub fn tanh(&self) -> HDual {
+        let x = self.re;
+        let t = x.tanh();
+        self.chain(t, 1.0 - t.powi(2), -2.0*t*(1.0 - t.powi(2)))
+    }
+
+    /// Absolute value. The derivative at zero is taken from the sign of zero.
+    pub fn abs(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.abs(), x.signum(), 0.0)
+    }
+
+    /// Reciprocal (1/x).
+    pub fn recip(&self) -> HDual {
+        let x = self.re;
+        self.chain(x.recip(), -1.0/x.powi(2), 2.0/x.powi(3))
+    }
+
+    /// Cube root.
+    pub fn cbrt(&self) -> HDual {
+        let x = self.re;
+        let c = x.cbrt();
+        self.chain(c, 1.0/(3.0*c.powi(2)), -2.0/(9.0*c.powi(5)))
+    }
+
+    /// Raises the number to a power given by another number (`self^other`). The
+    /// base must be positive.
+    pub fn pow(&self, other: &HDual) -> HDual {
+        let a = self.re;
+        let b = other.re;
+        let g = a.powf(b);
+        let ln_a = a.ln();
+        self.chain2(other, g, [b*a.powf(b - 1.0), g*ln_a],
+                    [b*(b - 1.0)*a.powf(b - 2.0),
+                     a.powf(b - 1.0)*(1.0 + b*ln_a),
+                     g*ln_a.powi(2)])
+    }
+
+    /// Four quadrant arctangent of `self` (y) and `other` (x).
+    pub fn atan2(&self, other: &HDual) -> HDual {
+        let a = self.re;
+        let b = other.re;
+        let r2 = a.powi(2) + b.powi(2);
+        let r4 = r2.powi(2);
+        self.chain2(other, a.atan2(b), [b/r2, -a/r2],
+                    [-2.0*a*b/r4, (a.powi(2) - b.powi(2))/r4, 2.0*a*b/r4])
+    }
+
+    /// Length of the hypotenuse of a right-angle triangle with legs `self`
+    /// and `other`.
+    pub fn hypot(&self, other: &HDual) -> HDual {
+        let a = self.re;
+        let b = other.re;
+        let h = a.hypot(b);
+        self.chain2(other, h, [a/h, b/h],
+                    [b.powi(2)/h.powi(3), -a*b/h.powi(3), a.powi(2)/h.powi(3)])
+    }
+}
+
+
+impl HDual {
+    // Applies the chain rule for a function with value f, first derivative df
+    // and second derivative d2f at the real part of the number.
+    fn chain(&self, f: f64, df: f64, d2f: f64) -> HDual {
+        HDual {
+            re: f,
+            e1: df * self.e1,
+            e2: df * self.e2,
+            e1e2: df * self.e1e2 + d2f * self.e1 * self.e2,
+        }
+    }
+
+    // Applies the chain rule for a function g(self, other) with value g, first
+    // partial derivatives dg = [g_a, g_b] and second partial derivatives
+    // d2g = [g_aa, g_ab, g_bb].
+    fn chain2(&self, other: &HDual, g: f64, dg: [f64; 2], d2g: [f64; 3]) -> HDual {
+        HDual {
+            re: g,
+            e1: dg[0] * self.e1 + dg[1] * other.e1,
+            e2: dg[0] * self.e2 + dg[1] * other.e2,
+            e1e2: dg[0] * self.e1e2 + dg[1] * other.e1e2
+                + d2g[0] * self.e1 * self.e2
+                + d2g[1] * (self.e1 * other.e2 + other.e1 * self.e2)
+                + d2g[2] * other.e1 * other.e2,
+        }
+    }
 }
@@ -0,0 +1,694 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use std::ops::{Add, Sub, Neg, Mul, Div};
+use crate::number_system::{DualScalar, Dual, HyperDualScalar, HyperDual, Var};
+
+
+/// Common interface of the number systems.
+///
+/// This trait is implemented by `f64` and by all the dual and hyperdual number
+/// types, so an objective function written once as a generic function can be
+/// evaluated with real numbers, gradients (dual numbers) or hessians
+/// (hyperdual numbers).
+///
+/// Constants must be on the right side of the operations (`x*2.0` instead of
+/// `2.0*x`) since the trait can only require operations where the number type
+/// is on the left side.
+///
+/// # Example
+/// ``` rust
+///use optimization::number_system::{Real, DualScalar};
+///
+///fn objective<T: Real>(x: &[T]) -> T {
+///    let u1 = x[0].cos()*x[1].sin() - 0.05;
+///    let u2 = x[1].sin() - 0.2;
+///    u1.powi(2) + u2.powi(2)
+///}
+///
+///let real = objective(&[1.0, 1.0]);
+///let dual = objective(&[DualScalar{re: 1.0, du: 1.0}, DualScalar{re: 1.0, du: 0.0}]);
+///assert_eq!(real, dual.re);
+/// ```
+pub trait Real:
+    Clone
+    + Add<Output=Self>
+    + Sub<Output=Self>
+    + Mul<Output=Self>
+    + Div<Output=Self>
+    + Neg<Output=Self>
+    + Add<f64, Output=Self>
+    + Sub<f64, Output=Self>
+    + Mul<f64, Output=Self>
+    + Div<f64, Output=Self>
+{
+    /// Returns the real part of the number.
+    fn re(&self) -> f64;
+
+    /// Returns a constant (all derivatives equal to zero) with the same shape
+    /// as `self`. This is needed by the number types that carry gradient
+    /// arrays.
+    fn constant(&self, value: f64) -> Self;
+
+    /// Sine function.
+    fn sin(&self) -> Self;
+
+    /// Cosine function.
+    fn cos(&self) -> Self;
+
+    /// Raises the number to an integer power.
+    fn powi(&self, n: i32) -> Self;
+
+    /// Square root.
+    fn sqrt(&self) -> Self;
+
+    /// Exponential function.
+    fn exp(&self) -> Self;
+
+    /// Natural logarithm.
+    fn ln(&self) -> Self;
+
+    /// Base 10 logarithm.
+    fn log10(&self) -> Self;
+
+    /// Raises the number to a real power.
+    fn powf(&self, n: f64) -> Self;
+
+    /// Tangent function.
+    fn tan(&self) -> Self;
+
+    /// Arcsine function.
+    fn asin(&self) -> Self;
+
+    /// Arccosine function.
+    fn acos(&self) -> Self;
+
+    /// Arctangent function.
+    fn atan(&self) -> Self;
+
+    /// Hyperbolic sine function.
+    fn sinh(&self) -> Self;
+
+    /// Hyperbolic cosine function.
+    fn cosh(&self) -> Self;
+
+    /// Hyperbolic tangent function.
+    fn tanh(&self) -> Self;
+
+    /// Absolute value. The derivative at zero is taken from the sign of zero.
+    fn abs(&self) -> Self;
+
+    /// Reciprocal (1/x).
+    fn recip(&self) -> Self;
+
+    /// Cube root.
+    fn cbrt(&self) -> Self;
+
+    /// Raises the number to a power given by another number (`self^other`). The
+    /// base must be positive.
+    fn pow(&self, other: &Self) -> Self;
+
+    /// Four quadrant arctangent of `self` (y) and `other` (x).
+    fn atan2(&self, other: &Self) -> Self;
+
+    /// Length of the hypotenuse of a right-angle triangle with legs `self` and `other`.
+    fn hypot(&self, other: &Self) -> Self;
+}
+
+
+impl Real for f64 {
+    fn re(&self) -> f64 {
+        *self
+    }
+
+    fn constant(&self, value: f64) -> f64 {
+        value
+    }
+
+    fn sin(&self) -> f64 {
+        f64::sin(*self)
+    }
+
+    fn cos(&self) -> f64 {
+        f64::cos(*self)
+    }
+
+    fn powi(&self, n: i32) -> f64 {
+        f64::powi(*self, n)
+    }
+
+    fn sqrt(&self) -> f64 {
+        f64::sqrt(*self)
+    }
+
+    fn exp(&self) -> f64 {
+        f64::exp(*self)
+    }
+
+    fn ln(&self) -> f64 {
+        f64::ln(*self)
+    }
+
+    fn log10(&self) -> f64 {
+        f64::log10(*self)
+    }
+
+    fn powf(&self, n: f64) -> f64 {
+        f64::powf(*self, n)
+    }
+
+    fn tan(&self) -> f64 {
+        f64::tan(*self)
+    }
+
+    fn asin(&self) -> f64 {
+        f64::asin(*self)
+    }
+
+    fn acos(&self) -> f64 {
+        f64::acos(*self)
+    }
+
+    fn atan(&self) -> f64 {
+        f64::atan(*self)
+    }
+
+    fn sinh(&self) -> f64 {
+        f64::sinh(*self)
+    }
+
+    fn cosh(&self) -> f64 {
+        f64::cosh(*self)
+    }
+
+    fn tanh(&self) -> f64 {
+        f64::tanh(*self)
+    }
+
+    fn abs(&self) -> f64 {
+        f64::abs(*self)
+    }
+
+    fn recip(&self) -> f64 {
+        f64::recip(*self)
+    }
+
+    fn cbrt(&self) -> f64 {
+        f64::cbrt(*self)
+    }
+
+    fn pow(&self, other: &f64) -> f64 {
+        f64::powf(*self, *other)
+    }
+
+    fn atan2(&self, other: &f64) -> f64 {
+        f64::atan2(*self, *other)
+    }
+
+    fn hypot(&self, other: &f64) -> f64 {
+        f64::hypot(*self, *other)
+    }
+}
+
+
+impl Real for DualScalar {
+    fn re(&self) -> f64 {
+        self.re
+    }
+
+    fn constant(&self, value: f64) -> DualScalar {
+        DualScalar{re: value, du: 0.0}
+    }
+
+    fn sin(&self) -> DualScalar {
+        DualScalar::sin(self)
+    }
+
+    fn cos(&self) -> DualScalar {
+        DualScalar::cos(self)
+    }
+
+    fn powi(&self, n: i32) -> DualScalar {
+        DualScalar::powi(self, n)
+    }
+
+    fn sqrt(&self) -> DualScalar {
+        DualScalar::sqrt(self)
+    }
+
+    fn exp(&self) -> DualScalar {
+        DualScalar::exp(self)
+    }
+
+    fn ln(&self) -> DualScalar {
+        DualScalar::ln(self)
+    }
+
+    fn log10(&self) -> DualScalar {
+        DualScalar::log10(self)
+    }
+
+    fn powf(&self, n: f64) -> DualScalar {
+        DualScalar::powf(self, n)
+    }
+
+    fn tan(&self) -> DualScalar {
+        DualScalar::tan(self)
+    }
+
+    fn asin(&self) -> DualScalar {
+        DualScalar::asin(self)
+    }
+
+    fn acos(&self) -> DualScalar {
+        DualScalar::acos(self)
+    }
+
+    fn atan(&self) -> DualScalar {
+        DualScalar::atan(self)
+    }
+
+    fn sinh(&self) -> DualScalar {
+        DualScalar::sinh(self)
+    }
+
+    fn cosh(&self) -> DualScalar {
+        DualScalar::cosh(self)
+    }
+
+    fn tanh(&self) -> DualScalar {
+        DualScalar::tanh(self)
+    }
+
+    fn abs(&self) -> DualScalar {
+        DualScalar::abs(self)
+    }
+
+    fn recip(&self) -> DualScalar {
+        DualScalar::recip(self)
+    }
+
+    fn cbrt(&self) -> DualScalar {
+        DualScalar::cbrt(self)
+    }
+
+    fn pow(&self, other: &DualScalar) -> DualScalar {
+        DualScalar::pow(self, other)
+    }
+
+    fn atan2(&self, other: &DualScalar) -> DualScalar {
+        DualScalar::atan2(self, other)
+    }
+
+    fn hypot(&self, other: &DualScalar) -> DualScalar {
+        DualScalar::hypot(self, other)
+    }
+}
+
+
+impl Real for Dual {
+    fn re(&self) -> f64 {
+        self.re
+    }
+
+    fn constant(&self, value: f64) -> Dual {
+        let mut constant = Dual::new(self.du.len());
+        constant.re = value;
+        constant
+    }
+
+    fn sin(&self) -> Dual {
+        Dual::sin(self)
+    }
+
+    fn cos(&self) -> Dual {
+        Dual::cos(self)
+    }
+
+    fn powi(&self, n: i32) -> Dual {
+        Dual::powi(self, n)
+    }
+
+    fn sqrt(&self) -> Dual {
+        Dual::sqrt(self)
+    }
+
+    fn exp(&self) -> Dual {
+        Dual::exp(self)
+    }
+
+    fn ln(&self) -> Dual {
+        Dual::ln(self)
+    }
+
+    fn log10(&self) -> Dual {
+        Dual::log10(self)
+    }
+
+    fn powf(&self, n: f64) -> Dual {
+        Dual::powf(self, n)
+    }
+
+    fn tan(&self) -> Dual {
+        Dual::tan(self)
+    }
+
+    fn asin(&self) -> Dual {
+        Dual::asin(self)
+    }
+
+    fn acos(&self) -> Dual {
+        Dual::acos(self)
+    }
+
+    fn atan(&self) -> Dual {
+        Dual::atan(self)
+    }
+
+    fn sinh(&self) -> Dual {
+        Dual::sinh(self)
+    }
+
+    fn cosh(&self) -> Dual {
+        Dual::cosh(self)
+    }
+
+    fn tanh(&self) -> Dual {
+        Dual::tanh(self)
+    }
+
+    fn abs(&self) -> Dual {
+        Dual::abs(self)
+    }
+
+    fn recip(&self) -> Dual {
+        Dual::recip(self)
+    }
+
+    fn cbrt(&self) -> Dual {
+        Dual::cbrt(self)
+    }
+
+    fn pow(&self, other: &Dual) -> Dual {
+        Dual::pow(self, other)
+    }
+
+    fn atan2(&self, other: &Dual) -> Dual {
+        Dual::atan2(self, other)
+    }
+
+    fn hypot(&self, other: &Dual) -> Dual {
+        Dual::hypot(self, other)
+    }
+}
+
+
+impl Real for HyperDualScalar {
+    fn re(&self) -> f64 {
+        self.re
+    }
+
+    fn constant(&self, value: f64) -> HyperDualScalar {
+        HyperDualScalar{re: value, e1: 0.0, e2: 0.0, e1e2: 0.0}
+    }
+
+    fn sin(&self) -> HyperDualScalar {
+        HyperDualScalar::sin(self)
+    }
+
+    fn cos(&self) -> HyperDualScalar {
+        HyperDualScalar::cos(self)
+    }
+
+    fn powi(&self, n: i32) -> HyperDualScalar {
+        HyperDualScalar::powi(self, n)
+    }
+
+    fn sqrt(&self) -> HyperDualScalar {
+        HyperDualScalar::sqrt(self)
+    }
+
+    fn exp(&self) -> HyperDualScalar {
+        HyperDualScalar::exp(self)
+    }
+
+    fn ln(&self) -> HyperDualScalar {
+        HyperDualScalar::ln(self)
+    }
+
+    fn log10(&self) -> HyperDualScalar {
+        HyperDualScalar::log10(self)
+    }
+
+    fn powf(&self, n: f64) -> HyperDualScalar {
+        HyperDualScalar::powf(self, n)
+    }
+
+    fn tan(&self) -> HyperDualScalar {
+        HyperDualScalar::tan(self)
+    }
+
+    fn asin(&self) -> HyperDualScalar {
+        HyperDualScalar::asin(self)
+    }
+
+    fn acos(&self) -> HyperDualScalar {
+        HyperDualScalar::acos(self)
+    }
+
+    fn atan(&self) -> HyperDualScalar {
+        HyperDualScalar::atan(self)
+    }
+
+    fn sinh(&self) -> HyperDualScalar {
+        HyperDualScalar::sinh(self)
+    }
+
+    fn cosh(&self) -> HyperDualScalar {
+        HyperDualScalar::cosh(self)
+    }
+
+    fn tanh(&self) -> HyperDualScalar {
+        HyperDualScalar::tanh(self)
+    }
+
+    fn abs(&self) -> HyperDualScalar {
+        HyperDualScalar::abs(self)
+    }
+
+    fn recip(&self) -> HyperDualScalar {
+        HyperDualScalar::recip(self)
+    }
+
+    fn cbrt(&self) -> HyperDualScalar {
+        HyperDualScalar::cbrt(self)
+    }
+
+    fn pow(&self, other: &HyperDualScalar) -> HyperDualScalar {
+        HyperDualScalar::pow(self, other)
+    }
+
+    fn atan2(&self, other: &HyperDualScalar) -> HyperDualScalar {
+        HyperDualScalar::atan2(self, other)
+    }
+
+    fn hypot(&self, other: &HyperDualScalar) -> HyperDualScalar {
+        HyperDualScalar::hypot(self, other)
+    }
+}
+
+
+impl Real for HyperDual {
+    fn re(&self) -> f64 {
+        self.real
+    }
+
+    fn constant(&self, value: f64) -> HyperDual {
+        let mut constant = HyperDual::new(self.grad.len());
+        constant.real = value;
+        constant
+    }
+
+    fn sin(&self) -> HyperDual {
+        HyperDual::sin(self)
+    }
+
+    fn cos(&self) -> HyperDual {
+        HyperDual::cos(self)
+    }
+
+    fn powi(&self, n: i32) -> HyperDual {
+        HyperDual::powi(self, n)
+    }
+
+    fn sqrt(&self) -> HyperDual {
+        HyperDual::sqrt(self)
+    }
+
+    fn exp(&self) -> HyperDual {
+        HyperDual::exp(self)
+    }
+
+    fn ln(&self) -> HyperDual {
+        HyperDual::ln(self)
+    }
+
+    fn log10(&self) -> HyperDual {
+        HyperDual::log10(self)
+    }
+
+    fn powf(&self, n: f64) -> HyperDual {
+        HyperDual::powf(self, n)
+    }
+
+    fn tan(&self) -> HyperDual {
+        HyperDual::tan(self)
+    }
+
+    fn asin(&self) -> HyperDual {
+        HyperDual::asin(self)
+    }
+
+    fn acos(&self) -> HyperDual {
+        HyperDual::acos(self)
+    }
+
+    fn atan(&self) -> HyperDual {
+        HyperDual::atan(self)
+    }
+
+    fn sinh(&self) -> HyperDual {
+        HyperDual::sinh(self)
+    }
+
+    fn cosh(&self) -> HyperDual {
+        HyperDual::cosh(self)
+    }
+
+    fn tanh(&self) -> HyperDual {
+        HyperDual::tanh(self)
+    }
+
+    fn abs(&self) -> HyperDual {
+        HyperDual::abs(self)
+    }
+
+    fn recip(&self) -> HyperDual {
+        HyperDual::recip(self)
+    }
+
+    fn cbrt(&self) -> HyperDual {
+        HyperDual::cbrt(self)
+    }
+
+    fn pow(&self, other: &HyperDual) -> HyperDual {
+        HyperDual::pow(self, other)
+    }
+
+    fn atan2(&self, other: &HyperDual) -> HyperDual {
+        HyperDual::atan2(self, other)
+    }
+
+    fn hypot(&self, other: &HyperDual) -> HyperDual {
+        HyperDual::hypot(self, other)
+    }
+}
+
+
+impl<'t, T: Real + Copy> Real for Var<'t, T> {
+    fn re(&self) -> f64 {
+        self.value().re()
+    }
+
+    // Constants are recorded in the tape as variables whose derivatives are
+    // never used
+    fn constant(&self, value: f64) -> Var<'t, T> {
+        self.unary(self.value().constant(value), self.value().constant(0.0))
+    }
+
+    fn sin(&self) -> Var<'t, T> {
+        Var::sin(self)
+    }
+
+    fn cos(&self) -> Var<'t, T> {
+        Var::cos(self)
+    }
+
+    fn powi(&self, n: i32) -> Var<'t, T> {
+        Var::powi(self, n)
+    }
+
+    fn sqrt(&self) -> Var<'t, T> {
+        Var::sqrt(self)
+    }
+
+    fn exp(&self) -> Var<'t, T> {
+        Var::exp(self)
+    }
+
+    fn ln(&self) -> Var<'t, T> {
+        Var::ln(self)
+    }
+
+    fn log10(&self) -> Var<'t, T> {
+        Var::log10(self)
+    }
+
+    fn powf(&self, n: f64) -> Var<'t, T> {
+        Var::powf(self, n)
+    }
+
+    fn tan(&self) -> Var<'t, T> {
+        Var::tan(self)
+    }
+
+    fn asin(&self) -> Var<'t, T> {
+        Var::asin(self)
+    }
+
+    fn acos(&self) -> Var<'t, T> {
+        Var::acos(self)
+    }
+
+    fn atan(&self) -> Var<'t, T> {
+        Var::atan(self)
+    }
+
+    fn sinh(&self) -> Var<'t, T> {
+        Var::sinh(self)
+    }
+
+    fn cosh(&self) -> Var<'t, T> {
+        Var::cosh(self)
+    }
+
+    fn tanh(&self) -> Var<'t, T> {
+        Var::tanh(self)
+    }
+
+    fn abs(&self) -> Var<'t, T> {
+        Var::abs(self)
+    }
+
+    fn recip(&self) -> Var<'t, T> {
+        Var::recip(self)
+    }
+
+    fn cbrt(&self) -> Var<'t, T> {
+        Var::cbrt(self)
+    }
+
+    fn pow(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        Var::pow(self, other)
+    }
+
+    fn atan2(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        Var::atan2(self, other)
+    }
+
+    fn hypot(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        Var::hypot(self, other)
+    }
+}
@@ -0,0 +1,320 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use std::cell::RefCell;
+use std::ops::{Add, Sub, Neg, Mul, Div};
+use crate::number_system::Real;
+
+
+// Operation recorded in the tape. Each node has at most two parents and the
+// weights are the partial derivatives of the node with respect of its parents.
+// Variables (leaf nodes) point to themselves with zero weights.
+#[derive(Debug, Clone)]
+struct Node<T> {
+    parents: [usize; 2],
+    weights: [T; 2],
+}
+
+
+/// Tape (Wengert list) used by reverse mode automatic differentiation.
+///
+/// Every operation done with the variables ([`Var`]) of a tape is recorded in
+/// it, so the gradient of the final result with respect of all the variables
+/// is obtained with a single backward pass through the tape
+/// ([`Var::grad`]). The cost of the gradient is then a small multiple of the
+/// cost of evaluating the function, independently of the number of variables.
+///
+/// The tape is generic over the number type `T` of the values. With `f64` the
+/// backward pass gives the gradient. With [`DualScalar`](crate::number_system::DualScalar)
+/// values (forward over reverse mode) the variables can be seeded with a
+/// direction `v` in their dual part, the real part of the adjoints is the
+/// gradient and the dual part is the hessian-vector product `H*v`.
+///
+/// # Example
+/// ``` rust
+///use optimization::number_system::{Tape, DualScalar};
+///
+///// gradient
+///let tape = Tape::new();
+///let x = tape.var(1.0);
+///let y = tape.var(2.0);
+///let f = x*y + x.sin();
+///let grad = f.grad();
+///assert_eq!(grad.wrt(&x), 2.0 + 1.0_f64.cos());
+///assert_eq!(grad.wrt(&y), 1.0);
+///
+///// hessian-vector product with v = [1, 0]
+///let tape = Tape::new();
+///let x = tape.var(DualScalar{re: 1.0, du: 1.0});
+///let y = tape.var(DualScalar{re: 2.0, du: 0.0});
+///let f = x*y + x.sin();
+///let grad = f.grad();
+///assert_eq!(grad.wrt(&x).du, -1.0_f64.sin());
+///assert_eq!(grad.wrt(&y).du, 1.0);
+/// ```
+#[derive(Debug)]
+pub struct Tape<T = f64> {
+    nodes: RefCell<Vec<Node<T>>>,
+}
+
+
+impl<T: Real + Copy> Tape<T> {
+    pub fn new() -> Tape<T> {
+        Tape {
+            nodes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Creates a new variable with the given value.
+    pub fn var(&self, value: T) -> Var<'_, T> {
+        let zero = value.constant(0.0);
+        let index = self.push([usize::MAX; 2], [zero, zero]);
+        Var {
+            tape: self,
+            index,
+            value,
+        }
+    }
+
+    /// Creates one variable for each of the given values.
+    pub fn vars(&self, values: &[T]) -> Vec<Var<'_, T>> {
+        values.iter().map(|value| self.var(*value)).collect()
+    }
+
+    /// Number of nodes (variables and operations) recorded in the tape.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    /// Returns `true` if nothing has been recorded in the tape.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    /// Removes all the nodes of the tape so it can be reused without
+    /// allocating memory again.
+    pub fn clear(&mut self) {
+        self.nodes.borrow_mut().clear();
+    }
+
+    // Records a node in the tape and returns its index. Leaf nodes are given
+    // usize::MAX as parents and they are replaced by the node index.
+    fn push(&self, parents: [usize; 2], weights: [T; 2]) -> usize {
+        let mut nodes = self.nodes.borrow_mut();
+        let index = nodes.len();
+        let parents = if parents[0] == usize::MAX { [index; 2] } else { parents };
+        nodes.push(Node { parents, weights });
+        index
+    }
+
+    // Backward pass: propagates the adjoints from the node `index` to all the
+    // nodes recorded before it.
+    fn backward(&self, index: usize, one: T) -> Vec<T> {
+        let nodes = self.nodes.borrow();
+        let zero = one.constant(0.0);
+        let mut adjoints = vec![zero; index + 1];
+        adjoints[index] = one;
+        for i in (0..=index).rev() {
+            let node = &nodes[i];
+            let adjoint = adjoints[i];
+            for k in 0..2 {
+                let parent = node.parents[k];
+                if parent != i {
+                    adjoints[parent] = adjoints[parent] + node.weights[k]*adjoint;
+                }
+            }
+        }
+        adjoints
+    }
+}
+
+
+impl<T: Real + Copy> Default for Tape<T> {
+    fn default() -> Tape<T> {
+        Tape::new()
+    }
+}
+
+
+/// Variable of a [`Tape`].
+///
+/// The variables are cheap to copy (they contain a reference to the tape, the
+/// index of the node and its value) and they support the same operations and
+/// math functions as the dual number types. The variables also implement
+/// [`Real`] so generic objective functions can be evaluated with them.
+///
+/// Constants must be on the right side of the operations with `f64` when the
+/// function is generic over [`Real`], but `2.0*x` is also supported for `Var`.
+#[derive(Debug, Clone, Copy)]
+pub struct Var<'t, T = f64> {
+    tape: &'t Tape<T>,
+    index: usize,
+    value: T,
+}
+
+
+impl<'t, T: Real + Copy> Var<'t, T> {
+    /// Value of the variable.
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Calculates the derivatives of this variable with respect of all the
+    /// variables recorded before it in the tape (backward pass).
+    pub fn grad(&self) -> Adjoints<T> {
+        Adjoints {
+            adjoints: self.tape.backward(self.index, self.value.constant(1.0)),
+        }
+    }
+
+    // Records the result of an operation with one argument. `d` is the
+    // derivative of the operation with respect of `self`.
+    pub(crate) fn unary(&self, value: T, d: T) -> Var<'t, T> {
+        let index = self.tape.push([self.index; 2], [d, value.constant(0.0)]);
+        Var {
+            tape: self.tape,
+            index,
+            value,
+        }
+    }
+
+    // Records the result of an operation with two arguments. `d_a` and `d_b`
+    // are the partial derivatives with respect of `self` and `other`.
+    pub(crate) fn binary(&self, other: &Var<'t, T>, value: T, d_a: T, d_b: T) -> Var<'t, T> {
+        debug_assert!(std::ptr::eq(self.tape, other.tape),
+                      "variables from different tapes");
+        let index = self.tape.push([self.index, other.index], [d_a, d_b]);
+        Var {
+            tape: self.tape,
+            index,
+            value,
+        }
+    }
+}
+
+
+/// Derivatives obtained from the backward pass through a [`Tape`].
+#[derive(Debug, Clone)]
+pub struct Adjoints<T = f64> {
+    adjoints: Vec<T>,
+}
+
+
+impl<T: Real + Copy> Adjoints<T> {
+    /// Derivative with respect of the variable `var`. Variables created after
+    /// the differentiated variable have zero derivative.
+    pub fn wrt(&self, var: &Var<T>) -> T {
+        match self.adjoints.get(var.index) {
+            Some(adjoint) => *adjoint,
+            None => var.value.constant(0.0),
+        }
+    }
+
+    /// Derivatives with respect of each of the variables in `vars`.
+    pub fn wrt_all(&self, vars: &[Var<T>]) -> Vec<T> {
+        vars.iter().map(|var| self.wrt(var)).collect()
+    }
+}
+
+
+impl<'t, T: Real + Copy> Add<Var<'t, T>> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn add(self, other: Var<'t, T>) -> Var<'t, T> {
+        let one = self.value.constant(1.0);
+        self.binary(&other, self.value + other.value, one, one)
+    }
+}
+
+impl<'t, T: Real + Copy> Add<f64> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn add(self, other: f64) -> Var<'t, T> {
+        self.unary(self.value + other, self.value.constant(1.0))
+    }
+}
+
+impl<'t, T: Real + Copy> Add<Var<'t, T>> for f64 {
+    type Output = Var<'t, T>;
+    fn add(self, other: Var<'t, T>) -> Var<'t, T> {
+        other + self
+    }
+}
+
+
+impl<'t, T: Real + Copy> Sub<Var<'t, T>> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn sub(self, other: Var<'t, T>) -> Var<'t, T> {
+        let one = self.value.constant(1.0);
+        self.binary(&other, self.value - other.value, one, -one)
+    }
+}
+
+impl<'t, T: Real + Copy> Sub<f64> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn sub(self, other: f64) -> Var<'t, T> {
+        self.unary(self.value - other, self.value.constant(1.0))
+    }
+}
+
+impl<'t, T: Real + Copy> Sub<Var<'t, T>> for f64 {
+    type Output = Var<'t, T>;
+    fn sub(self, other: Var<'t, T>) -> Var<'t, T> {
+        other.unary(-other.value + self, other.value.constant(-1.0))
+    }
+}
+
+
+impl<'t, T: Real + Copy> Mul<Var<'t, T>> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn mul(self, other: Var<'t, T>) -> Var<'t, T> {
+        self.binary(&other, self.value*other.value, other.value, self.value)
+    }
+}
+
+impl<'t, T: Real + Copy> Mul<f64> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn mul(self, other: f64) -> Var<'t, T> {
+        self.unary(self.value*other, self.value.constant(other))
+    }
+}
+
+impl<'t, T: Real + Copy> Mul<Var<'t, T>> for f64 {
+    type Output = Var<'t, T>;
+    fn mul(self, other: Var<'t, T>) -> Var<'t, T> {
+        other*self
+    }
+}
+
+
+impl<'t, T: Real + Copy> Div<Var<'t, T>> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn div(self, other: Var<'t, T>) -> Var<'t, T> {
+        let inv = other.value.recip();
+        let value = self.value*inv;
+        self.binary(&other, value, inv, -value*inv)
+    }
+}
+
+impl<'t, T: Real + Copy> Div<f64> for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn div(self, other: f64) -> Var<'t, T> {
+        self.unary(self.value/other, self.value.constant(1.0/other))
+    }
+}
+
+impl<'t, T: Real + Copy> Div<Var<'t, T>> for f64 {
+    type Output = Var<'t, T>;
+    fn div(self, other: Var<'t, T>) -> Var<'t, T> {
+        let inv = other.value.recip();
+        other.unary(inv*self, -inv*inv*self)
+    }
+}
+
+
+impl<'t, T: Real + Copy> Neg for Var<'t, T> {
+    type Output = Var<'t, T>;
+    fn neg(self) -> Var<'t, T> {
+        self.unary(-self.value, self.value.constant(-1.0))
+    }
+}
@@ -0,0 +1,143 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use std::f64::consts::LN_10;
+use crate::number_system::{Real, Var};
+
+
+// The derivatives are written with the operations of the `Real` trait so they
+// are also differentiated when the tape values are dual numbers.
+impl<'t, T: Real + Copy> Var<'t, T> {
+    pub fn sin(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.sin(), x.cos())
+    }
+
+    pub fn cos(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.cos(), -x.sin())
+    }
+
+    pub fn powi(&self, n: i32) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.powi(n), x.powi(n - 1)*f64::from(n))
+    }
+
+    /// Square root.
+    pub fn sqrt(&self) -> Var<'t, T> {
+        let s = self.value().sqrt();
+        self.unary(s, s.recip()*0.5)
+    }
+
+    /// Exponential function.
+    pub fn exp(&self) -> Var<'t, T> {
+        let e = self.value().exp();
+        self.unary(e, e)
+    }
+
+    /// Natural logarithm.
+    pub fn ln(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.ln(), x.recip())
+    }
+
+    /// Base 10 logarithm.
+    pub fn log10(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.log10(), x.recip()/LN_10)
+    }
+
+    /// Raises the number to a real power.
+    pub fn powf(&self, n: f64) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.powf(n), x.powf(n - 1.0)*n)
+    }
+
+    /// Tangent function.
+    pub fn tan(&self) -> Var<'t, T> {
+        let t = self.value().tan();
+        self.unary(t, t.powi(2) + 1.0)
+    }
+
+    /// Arcsine function.
+    pub fn asin(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.asin(), (-x.powi(2) + 1.0).sqrt().recip())
+    }
+
+    /// Arccosine function.
+    pub fn acos(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.acos(), -(-x.powi(2) + 1.0).sqrt().recip())
+    }
+
+    /// Arctangent function.
+    pub fn atan(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.atan(), (x.powi(2) + 1.0).recip())
+    }
+
+    /// Hyperbolic sine function.
+    pub fn sinh(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.sinh(), x.cosh())
+    }
+
+    /// Hyperbolic cosine function.
+    pub fn cosh(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.cosh(), x.sinh())
+    }
+
+    /// Hyperbolic tangent function.
+    pub fn tanh(&self) -> Var<'t, T> {
+        let t = self.value().tanh();
+        self.unary(t, -t.powi(2) + 1.0)
+    }
+
+    /// Absolute value. The derivative at zero is taken from the sign of zero.
+    pub fn abs(&self) -> Var<'t, T> {
+        let x = self.value();
+        self.unary(x.abs(), x.constant(x.re().signum()))
+    }
+
+    /// Reciprocal (1/x).
+    pub fn recip(&self) -> Var<'t, T> {
+        let r = self.value().recip();
+        self.unary(r, -r.powi(2))
+    }
+
+    /// Cube root.
+    pub fn cbrt(&self) -> Var<'t, T> {
+        let c = self.value().cbrt();
+        self.unary(c, (c.powi(2)*3.0).recip())
+    }
+
+    /// Raises the number to a power given by another number (`self^other`). The
+    /// base must be positive.
+    pub fn pow(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        let a = self.value();
+        let b = other.value();
+        let g = a.pow(&b);
+        self.binary(other, g, b*a.pow(&(b - 1.0)), g*a.ln())
+    }
+
+    /// Four quadrant arctangent of `self` (y) and `other` (x).
+    pub fn atan2(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        let a = self.value();
+        let b = other.value();
+        let r2 = a.powi(2) + b.powi(2);
+        self.binary(other, a.atan2(&b), b/r2, -a/r2)
+    }
+
+    /// Length of the hypotenuse of a right-angle triangle with legs `self`
+    /// and `other`.
+    pub fn hypot(&self, other: &Var<'t, T>) -> Var<'t, T> {
+        let a = self.value();
+        let b = other.value();
+        let h = a.hypot(&b);
+        self.binary(other, h, a/h, b/h)
+    }
+}
@@ -0,0 +1,99 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+//! Observers of the minimization progress.
+//!
+//! Every solver has a `minimize_with_observer` method that calls an
+//! [`Observer`] at the end of each iteration. The observer receives the
+//! current state of the solver and decides whether the minimization should
+//! continue or stop. Any closure `FnMut(&Iteration) -> Control` is an observer.
+//!
+//! # Example
+//! ``` rust
+//!use optimization::observer::{Iteration, Control};
+//!
+//!let mut history = Vec::new();
+//!let mut observer = |iteration: &Iteration| {
+//!    history.push(iteration.f_k);
+//!    if iteration.k < 100 {
+//!        Control::Continue
+//!    }
+//!    else {
+//!        Control::Stop
+//!    }
+//!};
+//!// the observer is then passed to a solver:
+//!// solver.minimize_with_observer(&x0, &mut problem, &mut observer);
+//! ```
+
+
+use ndarray::Array1;
+
+
+/// State of the solver at the end of an iteration.
+#[derive(Debug)]
+pub struct Iteration<'a> {
+    /// Iteration number.
+    pub k: u32,
+
+    /// Current position.
+    pub x_k: &'a Array1<f64>,
+
+    /// Objective function value at the current position.
+    pub f_k: f64,
+
+    /// Norm of the gradient at the current position (NaN for derivative-free
+    /// methods).
+    pub grad_norm: f64,
+
+    /// Step length used in this iteration. For line search methods this is the
+    /// `alpha` found by the line search, for trust region methods this is the
+    /// norm of the step taken (zero when the step was rejected) and for
+    /// derivative-free methods it is the norm of the change of position.
+    pub step_length: f64,
+
+    /// Current trust region radius (only for trust region methods).
+    pub trust_radius: Option<f64>,
+}
+
+
+/// Decision taken by an observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Control {
+    /// Keep iterating.
+    Continue,
+    /// Stop the minimization. The solver returns a solution with
+    /// `Status::UserAbort`.
+    Stop,
+}
+
+
+/// Observer of the minimization progress.
+pub trait Observer {
+    /// Method called by the solver at the end of each iteration.
+    fn observe(&mut self, iteration: &Iteration) -> Control;
+}
+
+
+impl<F> Observer for F
+where
+    F: FnMut(&Iteration) -> Control,
+{
+    fn observe(&mut self, iteration: &Iteration) -> Control {
+        self(iteration)
+    }
+}
+
+
+/// Observer that never stops the minimization. It is used by the `minimize`
+/// method of the solvers.
+pub struct NoObserver;
+
+
+impl Observer for NoObserver {
+    fn observe(&mut self, _iteration: &Iteration) -> Control {
+        Control::Continue
+    }
+}
@@ -0,0 +1,197 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use std::cell::Cell;
+use crate::problem::Objective;
+use crate::scalar::Brent;
+
+
+// Powell's conjugate direction method. The function is minimized along each
+// direction of a direction set with Brent's method and after each iteration
+// the direction of largest decrease is replaced by the total displacement of
+// the iteration (the same variant as scipy's `fmin_powell`). It only needs the
+// `Objective` trait.
+pub struct Powell {
+    // max number of iterations
+    pub i_max: u32,
+
+    // relative tolerance of the line minimizations
+    pub xtol: f64,
+
+    // the solver stops when the relative decrease of the function in one
+    // iteration is smaller than ftol
+    pub ftol: f64,
+
+    // initial direction set given as the rows of the matrix. The coordinate
+    // directions are used by default.
+    pub initial_directions: Option<Array2<f64>>,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // current function evaluation
+    f_k: f64,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+}
+
+
+impl Powell {
+    pub fn new() -> Powell {
+        Powell {
+            i_max: 1000,
+            xtol: 1e-6,
+            ftol: 1e-10,
+            initial_directions: None,
+            x_k: Array1::zeros(1),
+            f_k: 0.0,
+            f_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        let n = x0.len();
+        let mut directions: Vec<Array1<f64>> = match &self.initial_directions {
+            Some(directions) => {
+                assert_eq!(directions.dim(), (n, n), "initial directions have wrong dimension");
+                directions.outer_iter().map(|d| d.to_owned()).collect()
+            },
+            None => Array2::<f64>::eye(n).outer_iter().map(|d| d.to_owned()).collect(),
+        };
+        self.x_k = x0.clone();
+        self.f_k = self.eval(x0, problem);
+
+        let mut x_old: Array1<f64> = Array::zeros(n);
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.f_k.is_finite() {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            let f_old = self.f_k;
+            x_old.assign(&self.x_k);
+
+            // direction with the largest decrease of the function
+            let mut i_big = 0;
+            let mut delta = 0.0;
+            for (i, direction) in directions.iter().enumerate() {
+                let f_prev = self.f_k;
+                self.line_minimization(direction, problem);
+                if f_prev - self.f_k > delta {
+                    delta = f_prev - self.f_k;
+                    i_big = i;
+                }
+            }
+
+            let step = &self.x_k - &x_old;
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k,
+                grad_norm: f64::NAN,
+                step_length: step.dot(&step).sqrt(),
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            if 2.0*(f_old - self.f_k) <= self.ftol*(f_old.abs() + self.f_k.abs()) + 1e-20 {
+                solution.status = Status::FTolerance;
+                break;
+            }
+
+            // The displacement of the iteration replaces the direction of
+            // largest decrease when the extrapolated point x_k + step shows that
+            // the new direction is worth it (Numerical Recipes, 10.7)
+            let f_ext = self.eval(&(&self.x_k + &step), problem);
+            if f_ext < f_old {
+                let t = 2.0*(f_old - 2.0*self.f_k + f_ext)*(f_old - self.f_k - delta).powi(2)
+                        - delta*(f_old - f_ext).powi(2);
+                if t < 0.0 {
+                    self.line_minimization(&step, problem);
+                    directions.remove(i_big);
+                    directions.push(step);
+                }
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = self.f_k;
+        solution.f_evals = self.f_calls.get();
+        solution
+    }
+
+    fn eval<P>(&self, x: &Array1<f64>, problem: &mut P) -> f64
+    where
+        P: Objective,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.update_x(x);
+        problem.eval_real()
+    }
+
+    // Minimizes the function along the direction and moves x_k to the minimum
+    fn line_minimization<P>(&mut self, direction: &Array1<f64>, problem: &mut P)
+    where
+        P: Objective,
+    {
+        if direction.iter().all(|d| *d == 0.0) {
+            return
+        }
+        let x_k = self.x_k.clone();
+        let f_k = self.f_k;
+        let mut phi = |t: f64| {
+            if t == 0.0 {
+                f_k
+            }
+            else {
+                self.eval(&(&x_k + &(t*direction)), problem)
+            }
+        };
+        let mut brent = Brent::new();
+        brent.xtol = self.xtol;
+        let sol = brent.minimize(&mut phi, 0.0, 1.0);
+        if sol.f < self.f_k {
+            self.x_k = &x_k + &(sol.x*direction);
+            self.f_k = sol.f;
+        }
+    }
+}
+
+
+impl Default for Powell {
+    fn default() -> Powell {
+        Powell::new()
+    }
+}
+
@@ -6,7 +6,13 @@
 //! Traits to be used by the objective function.
 //!
 //! This collection of traits help to interface an arbitrary objective function
-//! with the optimization algorithms used in this crate.
+//! with the optimization algorithms used in this crate. Objective functions
+//! that can be written as a closure can use [`FnProblem`] instead of
+//! implementing the traits by hand (or [`TapeProblem`] for reverse mode
+//! differentiation). Objective functions that can only be evaluated with real
+//! numbers can get their derivatives with finite differences through
+//! [`FiniteDiff`] and [`FiniteHessian`]. Hand written derivatives can be
+//! verified with [`DerivativeChecker`].
 //!
 //! # Example
 //! ``` rust
@@ -107,6 +113,25 @@
 
 use ndarray::{Array1, Array2};
 
+
+mod fn_problem;
+pub use fn_problem::FnProblem;
+
+mod tape_problem;
+pub use tape_problem::TapeProblem;
+
+mod fn_residuals;
+pub use fn_residuals::FnResiduals;
+
+mod fn_constraints;
+pub use fn_constraints::FnConstraints;
+
+mod finite_diff;
+pub use finite_diff::{FiniteDiff, FiniteHessian, Difference, DualStep, DualObjective};
+
+mod check;
+pub use check::{DerivativeChecker, DerivativeCheck};
+
 /// Objective function evaluation and update of variable values.
 ///
 /// This trait is used as an interface of the objective function with the
@@ -151,6 +176,14 @@ pub trait Gradient {
     /// This method is useful to get the derivative with respect of `alpha` when
     /// `x + alpha*p` is evaluated
     fn diff(&mut self) -> f64;
+
+    /// Total number of evaluations of the objective function done by `grad`
+    /// and `diff`. Problems that approximate the derivatives with the
+    /// objective function (e.g. [`FiniteDiff`]) return their count so the
+    /// solvers can add it to `Solution::f_evals`. It is zero by default.
+    fn f_evals(&self) -> u32 {
+        0
+    }
 }
 
 
@@ -163,4 +196,157 @@ pub trait Hessian {
     /// Method that actually calculates the hessian matrix of the objective
     /// function.
     fn hess(&mut self, output: &mut Array2<f64>);
+
+    /// Total number of gradient evaluations done by `hess`. Problems that
+    /// approximate the hessian with the gradient (e.g. [`FiniteHessian`])
+    /// return their count so the solvers can add it to
+    /// `Solution::f_grad_evals`. It is zero by default.
+    fn grad_evals(&self) -> u32 {
+        0
+    }
+}
+
+
+/// Hessian-vector product of the objective function.
+///
+/// Some minimization algorithms only use the hessian through products `H*v`,
+/// so the hessian matrix doesn't need to be formed. The default implementation
+/// approximates the product with central differences of the gradient:
+///
+/// `H*v ≈ (grad(x + h*v) - grad(x - h*v))/(2h)`
+///
+/// which needs two gradient evaluations. Problems that can calculate the
+/// product exactly (e.g. with hyperdual numbers or forward over reverse
+/// differentiation) should override `hess_vec`.
+pub trait HessianVector: Objective + Gradient {
+    /// Calculates the product of the hessian at `x` and the vector `v` and
+    /// writes it into the `output` array. The internal variables of the problem
+    /// are left at `x`.
+    fn hess_vec(&mut self, x: &Array1<f64>, v: &Array1<f64>, output: &mut Array1<f64>) {
+        let v_norm = v.dot(v).sqrt();
+        if v_norm == 0.0 {
+            output.fill(0.0);
+            self.update_x(x);
+            return
+        }
+        // step size that balances the truncation and round-off errors of the
+        // central differences
+        let h = f64::EPSILON.cbrt()*(1.0 + x.dot(x).sqrt())/v_norm;
+        let mut grad_minus = Array1::zeros(x.raw_dim());
+
+        self.update_x(&(x + &(h*v)));
+        self.grad(output);
+        self.update_x(&(x - &(h*v)));
+        self.grad(&mut grad_minus);
+        *output -= &grad_minus;
+        *output /= 2.0*h;
+        self.update_x(x);
+    }
+
+    /// Number of gradient evaluations done by each call to `hess_vec`, the
+    /// solvers add them to `Solution::f_grad_evals`. It is two for the
+    /// default implementation, problems that override `hess_vec` without
+    /// calling `grad` should return zero.
+    fn hess_vec_grad_evals(&self) -> u32 {
+        2
+    }
+}
+
+
+/// Residuals and jacobian of a least-squares problem.
+///
+/// Least-squares problems minimize `f(x) = 0.5*sum(r_i(x)^2)` where `r_i` are
+/// the residuals. The least-squares solvers use the residual vector and its
+/// jacobian directly instead of the scalar objective function.
+pub trait LeastSquares {
+    /// Number of residuals.
+    fn num_residuals(&self) -> usize;
+
+    /// Calculates the residuals at `x` and writes them into the `output`
+    /// array.
+    fn residuals(&mut self, x: &Array1<f64>, output: &mut Array1<f64>);
+
+    /// Calculates the jacobian of the residuals at `x` and writes it into the
+    /// `output` matrix. The element `[i, j]` is the derivative of the residual
+    /// `i` with respect of the variable `j`.
+    fn jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>);
+}
+
+
+/// Equality and inequality constraints.
+///
+/// Constrained solvers minimize an objective function subject to the equality
+/// constraints `c(x) = 0` and the inequality constraints `g(x) <= 0`. Both are
+/// vectors of functions and the solvers use their values and jacobians.
+pub trait Constraints {
+    /// Number of equality constraints.
+    fn num_equality(&self) -> usize;
+
+    /// Number of inequality constraints.
+    fn num_inequality(&self) -> usize;
+
+    /// Calculates the equality constraints at `x` and writes them into the
+    /// `output` array.
+    fn equality(&mut self, x: &Array1<f64>, output: &mut Array1<f64>);
+
+    /// Calculates the jacobian of the equality constraints at `x` and writes
+    /// it into the `output` matrix. The element `[i, j]` is the derivative of
+    /// the constraint `i` with respect of the variable `j`.
+    fn equality_jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>);
+
+    /// Calculates the inequality constraints at `x` and writes them into the
+    /// `output` array.
+    fn inequality(&mut self, x: &Array1<f64>, output: &mut Array1<f64>);
+
+    /// Calculates the jacobian of the inequality constraints at `x` and writes
+    /// it into the `output` matrix.
+    fn inequality_jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>);
+
+    /// Calculates the sum of the hessians of the equality constraints at `x`
+    /// weighted by `y` (`Σ y_i*hess(c_i)`) and writes it into the `output`
+    /// matrix. It is used by the solvers that need the hessian of the
+    /// lagrangian. The default implementation uses central differences of
+    /// `equality_jacobian`.
+    fn equality_hessian(&mut self, x: &Array1<f64>, y: &Array1<f64>, output: &mut Array2<f64>) {
+        let m = self.num_equality();
+        weighted_hessian(x, y, m, output, |x, jac| self.equality_jacobian(x, jac));
+    }
+
+    /// Calculates the sum of the hessians of the inequality constraints at
+    /// `x` weighted by `y` (`Σ y_i*hess(g_i)`) and writes it into the `output`
+    /// matrix. The default implementation uses central differences of
+    /// `inequality_jacobian`.
+    fn inequality_hessian(&mut self, x: &Array1<f64>, y: &Array1<f64>, output: &mut Array2<f64>) {
+        let m = self.num_inequality();
+        weighted_hessian(x, y, m, output, |x, jac| self.inequality_jacobian(x, jac));
+    }
+}
+
+
+// Central differences of J^T*y, where the jacobian J of m constraints is
+// calculated by the closure. The result is symmetrized.
+fn weighted_hessian<F>(x: &Array1<f64>, y: &Array1<f64>, m: usize, output: &mut Array2<f64>, mut jacobian: F)
+where
+    F: FnMut(&Array1<f64>, &mut Array2<f64>),
+{
+    let n = x.len();
+    output.fill(0.0);
+    if y.iter().all(|y_i| *y_i == 0.0) {
+        return
+    }
+    let mut jac_plus = Array2::zeros((m, n));
+    let mut jac_minus = Array2::zeros((m, n));
+    let mut x_h = x.clone();
+    for j in 0..n {
+        let h = f64::EPSILON.cbrt()*x[j].abs().max(1.0);
+        x_h[j] = x[j] + h;
+        jacobian(&x_h, &mut jac_plus);
+        x_h[j] = x[j] - h;
+        jacobian(&x_h, &mut jac_minus);
+        x_h[j] = x[j];
+        let column = (&jac_plus - &jac_minus).t().dot(y)/(2.0*h);
+        output.column_mut(j).assign(&column);
+    }
+    let symmetric = 0.5*(&*output + &output.t());
+    output.assign(&symmetric);
 }
@@ -0,0 +1,232 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use std::fmt;
+use ndarray::{Array1, Array2, Array, Dimension, Ix1, Ix2, arr1};
+use crate::problem::{Objective, Gradient, Hessian, Difference};
+
+
+/// Verification of the derivatives of a problem.
+///
+/// The derivatives calculated by the problem (`Gradient::grad`,
+/// `Gradient::diff` and `Hessian::hess`) are compared with finite differences
+/// of `Objective::eval_real` (or of `Gradient::grad` for the hessian) at a
+/// given position. The error of each component is
+/// `|actual - expected|/max(|expected|, 1)`, so it is a relative error for
+/// large derivatives and an absolute error for small ones.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::{DerivativeChecker, FnProblem};
+///use optimization::number_system::DualScalar;
+///use ndarray::arr1;
+///
+///let mut problem = FnProblem::new(2, |x: &[DualScalar]| x[0].sin()*x[1].powi(3));
+///let check = DerivativeChecker::new().gradient(&mut problem, &arr1(&[0.3, 1.2]));
+///assert!(check.passed(), "{}", check);
+/// ```
+pub struct DerivativeChecker {
+    // max error of a component to pass the check
+    pub tolerance: f64,
+
+    // difference formula
+    pub difference: Difference,
+
+    // relative step, the default step of the formula is used when it is None
+    pub step: Option<f64>,
+}
+
+
+impl DerivativeChecker {
+    pub fn new() -> DerivativeChecker {
+        DerivativeChecker {
+            tolerance: 1e-6,
+            difference: Difference::Central,
+            step: None,
+        }
+    }
+
+    /// Checks `Gradient::grad` at `x`.
+    pub fn gradient<P>(&self, problem: &mut P, x: &Array1<f64>) -> DerivativeCheck<Ix1>
+    where
+        P: Objective + Gradient,
+    {
+        let n = x.len();
+        let mut actual: Array1<f64> = Array::zeros(n);
+        problem.update_x(x);
+        problem.eval_real();
+        problem.grad(&mut actual);
+
+        let step = self.step();
+        let f_x = problem.eval_real();
+        let mut expected: Array1<f64> = Array::zeros(n);
+        let mut x_h = x.clone();
+        for i in 0..n {
+            x_h[i] = x[i] + step*x[i].abs().max(1.0);
+            let h = x_h[i] - x[i];
+            problem.update_x(&x_h);
+            let f_plus = problem.eval_real();
+            expected[i] = match self.difference {
+                Difference::Forward => (f_plus - f_x)/h,
+                Difference::Central => {
+                    x_h[i] = x[i] - h;
+                    problem.update_x(&x_h);
+                    (f_plus - problem.eval_real())/(2.0*h)
+                },
+            };
+            x_h[i] = x[i];
+        }
+        problem.update_x(x);
+        DerivativeCheck::new(actual, expected, self.tolerance)
+    }
+
+    /// Checks `Gradient::diff` at `x` along the direction `p` (the problem is
+    /// moved with `Objective::move_step(x, p, 0)`). The report has a single
+    /// component.
+    pub fn diff<P>(&self, problem: &mut P, x: &Array1<f64>, p: &Array1<f64>) -> DerivativeCheck<Ix1>
+    where
+        P: Objective + Gradient,
+    {
+        problem.move_step(x, p, 0.0);
+        let actual = problem.diff();
+
+        let p_norm = p.dot(p).sqrt();
+        let h = if p_norm > 0.0 { self.step()*(1.0 + x.dot(x).sqrt())/p_norm } else { 1.0 };
+        let mut eval_at = |alpha: f64| {
+            problem.update_x(&(x + &(alpha*p)));
+            problem.eval_real()
+        };
+        let expected = match self.difference {
+            Difference::Forward => (eval_at(h) - eval_at(0.0))/h,
+            Difference::Central => (eval_at(h) - eval_at(-h))/(2.0*h),
+        };
+        problem.update_x(x);
+        DerivativeCheck::new(arr1(&[actual]), arr1(&[expected]), self.tolerance)
+    }
+
+    /// Checks `Hessian::hess` at `x` with differences of `Gradient::grad`
+    /// (which should be checked first). The symmetry of the hessian is not
+    /// enforced, so an asymmetric hessian fails the check.
+    pub fn hessian<P>(&self, problem: &mut P, x: &Array1<f64>) -> DerivativeCheck<Ix2>
+    where
+        P: Objective + Gradient + Hessian,
+    {
+        let n = x.len();
+        let mut actual: Array2<f64> = Array::zeros((n, n));
+        problem.update_x(x);
+        problem.eval_real();
+        problem.hess(&mut actual);
+
+        let step = self.step();
+        let mut g_x: Array1<f64> = Array::zeros(n);
+        let mut g_plus: Array1<f64> = Array::zeros(n);
+        let mut g_minus: Array1<f64> = Array::zeros(n);
+        problem.update_x(x);
+        problem.grad(&mut g_x);
+        let mut expected: Array2<f64> = Array::zeros((n, n));
+        let mut x_h = x.clone();
+        for j in 0..n {
+            x_h[j] = x[j] + step*x[j].abs().max(1.0);
+            let h = x_h[j] - x[j];
+            problem.update_x(&x_h);
+            problem.grad(&mut g_plus);
+            let column = match self.difference {
+                Difference::Forward => (&g_plus - &g_x)/h,
+                Difference::Central => {
+                    x_h[j] = x[j] - h;
+                    problem.update_x(&x_h);
+                    problem.grad(&mut g_minus);
+                    (&g_plus - &g_minus)/(2.0*h)
+                },
+            };
+            expected.column_mut(j).assign(&column);
+            x_h[j] = x[j];
+        }
+        problem.update_x(x);
+        DerivativeCheck::new(actual, expected, self.tolerance)
+    }
+
+    fn step(&self) -> f64 {
+        self.step.unwrap_or_else(|| self.difference.default_step())
+    }
+}
+
+
+impl Default for DerivativeChecker {
+    fn default() -> DerivativeChecker {
+        DerivativeChecker::new()
+    }
+}
+
+
+/// Report of a derivative check.
+///
+/// The arrays have the shape of the derivative: `Ix1` for the gradient and
+/// `Ix2` for the hessian.
+#[derive(Debug, Clone)]
+pub struct DerivativeCheck<D: Dimension> {
+    /// Derivatives calculated by the problem.
+    pub actual: Array<f64, D>,
+
+    /// Derivatives calculated with finite differences.
+    pub expected: Array<f64, D>,
+
+    /// Error of each component.
+    pub errors: Array<f64, D>,
+
+    /// Largest error of all the components (NaN when any derivative is not
+    /// finite).
+    pub max_error: f64,
+
+    /// Max error of a component to pass the check.
+    pub tolerance: f64,
+}
+
+
+impl<D: Dimension> DerivativeCheck<D> {
+    fn new(actual: Array<f64, D>, expected: Array<f64, D>, tolerance: f64) -> DerivativeCheck<D> {
+        let mut errors = &actual - &expected;
+        errors.zip_mut_with(&expected, |e, x| *e = e.abs()/x.abs().max(1.0));
+        let max_error = errors.iter().fold(0.0, |acc: f64, e| {
+            if e.is_nan() || acc.is_nan() { f64::NAN } else { acc.max(*e) }
+        });
+        DerivativeCheck {
+            actual,
+            expected,
+            errors,
+            max_error,
+            tolerance,
+        }
+    }
+
+    /// Returns `true` when the error of every component is within the
+    /// tolerance.
+    pub fn passed(&self) -> bool {
+        self.max_error <= self.tolerance
+    }
+
+    /// Indices of the components whose error is larger than the tolerance (or
+    /// not finite).
+    pub fn offending(&self) -> Vec<D::Pattern> {
+        self.errors.indexed_iter()
+            .filter(|(_, e)| e.is_nan() || **e > self.tolerance)
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+
+impl<D: Dimension> fmt::Display for DerivativeCheck<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "max error {:e} (tolerance {:e})", self.max_error, self.tolerance)?;
+        let components = self.errors.indexed_iter().zip(self.actual.iter().zip(self.expected.iter()));
+        for ((index, e), (actual, expected)) in components {
+            if e.is_nan() || *e > self.tolerance {
+                writeln!(f, "  {:?}: actual {:e}, expected {:e}, error {:e}", index, actual, expected, e)?;
+            }
+        }
+        Ok(())
+    }
+}
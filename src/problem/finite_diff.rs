@@ -0,0 +1,516 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::problem::{Objective, Gradient, Hessian, HessianVector};
+use crate::number_system::DualScalar;
+
+
+/// Finite difference formula.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difference {
+    /// `(f(x + h) - f(x))/h`. It needs one evaluation per variable (the value
+    /// at `x` is reused when it is known) and its error is of order `h`.
+    Forward,
+    /// `(f(x + h) - f(x - h))/(2h)`. It needs two evaluations per variable
+    /// and its error is of order `h^2`.
+    Central,
+}
+
+
+impl Difference {
+    // Relative step that balances the truncation and round-off errors
+    pub(super) fn default_step(&self) -> f64 {
+        match self {
+            Difference::Forward => f64::EPSILON.sqrt(),
+            Difference::Central => f64::EPSILON.cbrt(),
+        }
+    }
+}
+
+
+/// Gradient approximated with finite differences.
+///
+/// This adapter implements `Gradient` for any problem that only implements
+/// `Objective`, so it can be used with all the gradient based solvers. The
+/// step of each variable is `step*max(|x_i|, 1)`, where the relative step is
+/// chosen from the difference formula when it is not given. The evaluations of
+/// the objective function used by the differences are counted and added to
+/// `Solution::f_evals` by the solvers.
+///
+/// Problems that can also be evaluated with `DualScalar` numbers should use
+/// [`DualStep`] (or [`FnProblem`](crate::problem::FnProblem) for closures)
+/// instead, which gives the derivatives exactly.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::{FiniteDiff, Objective};
+///use optimization::BFGS;
+///use ndarray::{Array1, arr1};
+///
+///// objective function that can only be evaluated with f64 values
+///struct Problem {
+///    x: Array1<f64>,
+///}
+///
+///impl Objective for Problem {
+///    fn eval(&mut self) {}
+///
+///    fn eval_real(&mut self) -> f64 {
+///        (self.x[0] - 1.0).powi(2) + 10.0*(self.x[1] - self.x[0]).powi(2)
+///    }
+///
+///    fn update_x(&mut self, x: &Array1<f64>) {
+///        self.x.assign(x);
+///    }
+///
+///    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+///        self.x.assign(&(x + &(alpha*p)));
+///    }
+///}
+///
+///let mut problem = FiniteDiff::new(Problem{x: Array1::zeros(2)});
+///let sol = BFGS::new().minimize(&arr1(&[0.0, 0.0]), &mut problem);
+///assert!(sol.success);
+///assert!((sol.x[1] - 1.0).abs() < 1e-5);
+/// ```
+pub struct FiniteDiff<P> {
+    // difference formula
+    pub difference: Difference,
+
+    // relative step, the default step of the formula is used when it is None
+    pub step: Option<f64>,
+
+    // wrapped problem
+    problem: P,
+
+    // current position
+    x: Array1<f64>,
+
+    // direction of the last call to move_step
+    p: Array1<f64>,
+
+    // objective function value at x when it is known
+    value: Option<f64>,
+
+    // evaluations done by the differences
+    f_calls: u32,
+}
+
+
+impl<P: Objective> FiniteDiff<P> {
+    /// Wraps `problem` using central differences.
+    pub fn new(problem: P) -> FiniteDiff<P> {
+        FiniteDiff {
+            difference: Difference::Central,
+            step: None,
+            problem,
+            x: Array1::zeros(0),
+            p: Array1::zeros(0),
+            value: None,
+            f_calls: 0,
+        }
+    }
+
+    /// Returns the wrapped problem.
+    pub fn inner(&self) -> &P {
+        &self.problem
+    }
+
+    /// Consumes the adapter and returns the wrapped problem.
+    pub fn into_inner(self) -> P {
+        self.problem
+    }
+
+    fn eval_at(&mut self, x: &Array1<f64>) -> f64 {
+        self.f_calls += 1;
+        self.problem.update_x(x);
+        self.problem.eval_real()
+    }
+
+    fn step(&self) -> f64 {
+        self.step.unwrap_or_else(|| self.difference.default_step())
+    }
+}
+
+
+impl<P: Objective> Objective for FiniteDiff<P> {
+    fn eval(&mut self) {
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        let value = self.problem.eval_real();
+        self.value = Some(value);
+        value
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x = x.clone();
+        self.value = None;
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x = x + &(alpha*p);
+        self.p = p.clone();
+        self.value = None;
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Objective> Gradient for FiniteDiff<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        let x = self.x.clone();
+        let step = self.step();
+        let f_x = match (self.difference, self.value) {
+            (Difference::Forward, Some(value)) => value,
+            (Difference::Forward, None) => self.eval_at(&x),
+            (Difference::Central, _) => 0.0,
+        };
+        let mut x_h = x.clone();
+        for i in 0..x.len() {
+            x_h[i] = x[i] + step*x[i].abs().max(1.0);
+            // the step that is actually represented in floating point
+            let h = x_h[i] - x[i];
+            output[i] = match self.difference {
+                Difference::Forward => (self.eval_at(&x_h) - f_x)/h,
+                Difference::Central => {
+                    let f_plus = self.eval_at(&x_h);
+                    x_h[i] = x[i] - h;
+                    (f_plus - self.eval_at(&x_h))/(2.0*h)
+                },
+            };
+            x_h[i] = x[i];
+        }
+        self.problem.update_x(&x);
+    }
+
+    // Derivative along the direction of the last call to move_step
+    fn diff(&mut self) -> f64 {
+        let p_norm = self.p.dot(&self.p).sqrt();
+        if p_norm == 0.0 {
+            return 0.0
+        }
+        let x = self.x.clone();
+        let p = self.p.clone();
+        let h = self.step()*(1.0 + x.dot(&x).sqrt())/p_norm;
+        let diff = match self.difference {
+            Difference::Forward => {
+                let f_x = match self.value {
+                    Some(value) => value,
+                    None => self.eval_at(&x),
+                };
+                (self.eval_at(&(&x + &(h*&p))) - f_x)/h
+            },
+            Difference::Central => {
+                let f_plus = self.eval_at(&(&x + &(h*&p)));
+                (f_plus - self.eval_at(&(&x - &(h*&p))))/(2.0*h)
+            },
+        };
+        self.problem.update_x(&x);
+        diff
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.f_calls
+    }
+}
+
+impl<P: Objective> HessianVector for FiniteDiff<P> {}
+
+
+/// Objective function that can also be evaluated with `DualScalar` numbers.
+pub trait DualObjective: Objective {
+    /// Evaluates the objective function at `x`, propagating the dual parts.
+    fn eval_dual(&mut self, x: &[DualScalar]) -> DualScalar;
+}
+
+
+/// Gradient calculated with dual numbers, the analog of the complex step
+/// method.
+///
+/// The complex step method gets the derivative from `Im(f(x + ih))/h`; with
+/// dual numbers the derivative is the dual part of `f(x + ε)`, so it is exact
+/// and there is no step to choose. This adapter implements `Gradient` for any
+/// problem that implements [`DualObjective`]: the gradient needs one dual
+/// evaluation per variable and `diff` needs one evaluation. The evaluations
+/// are counted and added to `Solution::f_evals` by the solvers.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::{DualStep, DualObjective, Objective};
+///use optimization::number_system::DualScalar;
+///use optimization::BFGS;
+///use ndarray::{Array1, arr1};
+///
+///struct Problem {
+///    x: Array1<f64>,
+///}
+///
+///impl Objective for Problem {
+///    fn eval(&mut self) {}
+///
+///    fn eval_real(&mut self) -> f64 {
+///        (self.x[0] - 1.0).powi(2) + 10.0*(self.x[1] - self.x[0]).powi(2)
+///    }
+///
+///    fn update_x(&mut self, x: &Array1<f64>) {
+///        self.x.assign(x);
+///    }
+///
+///    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+///        self.x.assign(&(x + &(alpha*p)));
+///    }
+///}
+///
+///impl DualObjective for Problem {
+///    fn eval_dual(&mut self, x: &[DualScalar]) -> DualScalar {
+///        (x[0] - 1.0).powi(2) + (x[1] - x[0]).powi(2)*10.0
+///    }
+///}
+///
+///let mut problem = DualStep::new(Problem{x: Array1::zeros(2)});
+///let sol = BFGS::new().minimize(&arr1(&[0.0, 0.0]), &mut problem);
+///assert!(sol.success);
+///assert!((sol.x[1] - 1.0).abs() < 1e-6);
+/// ```
+pub struct DualStep<P> {
+    // wrapped problem
+    problem: P,
+
+    // current position
+    x: Array1<f64>,
+
+    // direction of the last call to move_step
+    p: Array1<f64>,
+
+    // dual evaluations done by the derivatives
+    f_calls: u32,
+}
+
+
+impl<P: DualObjective> DualStep<P> {
+    pub fn new(problem: P) -> DualStep<P> {
+        DualStep {
+            problem,
+            x: Array1::zeros(0),
+            p: Array1::zeros(0),
+            f_calls: 0,
+        }
+    }
+
+    /// Returns the wrapped problem.
+    pub fn inner(&self) -> &P {
+        &self.problem
+    }
+
+    /// Consumes the adapter and returns the wrapped problem.
+    pub fn into_inner(self) -> P {
+        self.problem
+    }
+
+    // Derivative at x along p
+    fn eval_du(&mut self, x: &Array1<f64>, p: &Array1<f64>) -> f64 {
+        self.f_calls += 1;
+        let x_d: Vec<DualScalar> = x.iter().zip(p.iter()).map(|(x, p)| DualScalar{re: *x, du: *p}).collect();
+        self.problem.eval_dual(&x_d).du
+    }
+}
+
+
+impl<P: DualObjective> Objective for DualStep<P> {
+    fn eval(&mut self) {
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x = x.clone();
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x = x + &(alpha*p);
+        self.p = p.clone();
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: DualObjective> Gradient for DualStep<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        let x = self.x.clone();
+        let mut e_i: Array1<f64> = Array::zeros(x.len());
+        for i in 0..x.len() {
+            e_i[i] = 1.0;
+            output[i] = self.eval_du(&x, &e_i);
+            e_i[i] = 0.0;
+        }
+    }
+
+    // Derivative along the direction of the last call to move_step
+    fn diff(&mut self) -> f64 {
+        let x = self.x.clone();
+        let p = self.p.clone();
+        self.eval_du(&x, &p)
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.f_calls
+    }
+}
+
+impl<P: DualObjective> HessianVector for DualStep<P> {}
+
+
+/// Hessian approximated with finite differences of the gradient.
+///
+/// This adapter implements `Hessian` for any problem with `Gradient` (which
+/// can be a [`FiniteDiff`] problem), so it can be used with the solvers that
+/// need the hessian matrix. Each column of the hessian needs one (forward) or
+/// two (central) gradient evaluations and the result is symmetrized.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::{FnProblem, FiniteHessian};
+///use optimization::number_system::DualScalar;
+///use optimization::TrustNCG;
+///use ndarray::arr1;
+///
+///let problem = FnProblem::new(2, |x: &[DualScalar]| {
+///    (x[0] - 1.0).powi(2) + (x[1] - x[0]).powi(2)*10.0
+///});
+///let mut problem = FiniteHessian::new(problem);
+///let sol = TrustNCG::new().minimize(&arr1(&[0.0, 0.0]), &mut problem);
+///assert!(sol.success);
+/// ```
+pub struct FiniteHessian<P> {
+    // difference formula
+    pub difference: Difference,
+
+    // relative step, the default step of the formula is used when it is None
+    pub step: Option<f64>,
+
+    // wrapped problem
+    problem: P,
+
+    // current position
+    x: Array1<f64>,
+
+    // gradient evaluations done by the differences
+    grad_calls: u32,
+}
+
+
+impl<P: Objective + Gradient> FiniteHessian<P> {
+    /// Wraps `problem` using central differences.
+    pub fn new(problem: P) -> FiniteHessian<P> {
+        FiniteHessian {
+            difference: Difference::Central,
+            step: None,
+            problem,
+            x: Array1::zeros(0),
+            grad_calls: 0,
+        }
+    }
+
+    /// Returns the wrapped problem.
+    pub fn inner(&self) -> &P {
+        &self.problem
+    }
+
+    /// Consumes the adapter and returns the wrapped problem.
+    pub fn into_inner(self) -> P {
+        self.problem
+    }
+
+    fn grad_at(&mut self, x: &Array1<f64>, output: &mut Array1<f64>) {
+        self.grad_calls += 1;
+        self.problem.update_x(x);
+        self.problem.grad(output);
+    }
+}
+
+
+impl<P: Objective + Gradient> Objective for FiniteHessian<P> {
+    fn eval(&mut self) {
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x = x.clone();
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x = x + &(alpha*p);
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Objective + Gradient> Gradient for FiniteHessian<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.problem.grad(output);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.problem.diff()
+    }
+
+    fn f_evals(&self) -> u32 {
+        self.problem.f_evals()
+    }
+}
+
+
+impl<P: Objective + Gradient> Hessian for FiniteHessian<P> {
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        let x = self.x.clone();
+        let n = x.len();
+        let step = self.step.unwrap_or_else(|| self.difference.default_step());
+        let mut g_x: Array1<f64> = Array::zeros(n);
+        let mut g_plus: Array1<f64> = Array::zeros(n);
+        let mut g_minus: Array1<f64> = Array::zeros(n);
+        if self.difference == Difference::Forward {
+            self.grad_at(&x, &mut g_x);
+        }
+        let mut x_h = x.clone();
+        for j in 0..n {
+            x_h[j] = x[j] + step*x[j].abs().max(1.0);
+            let h = x_h[j] - x[j];
+            self.grad_at(&x_h, &mut g_plus);
+            let column = match self.difference {
+                Difference::Forward => (&g_plus - &g_x)/h,
+                Difference::Central => {
+                    x_h[j] = x[j] - h;
+                    self.grad_at(&x_h, &mut g_minus);
+                    (&g_plus - &g_minus)/(2.0*h)
+                },
+            };
+            output.column_mut(j).assign(&column);
+            x_h[j] = x[j];
+        }
+        // the differences are not exactly symmetric
+        let symmetric = 0.5*(&*output + &output.t());
+        output.assign(&symmetric);
+        self.problem.update_x(&x);
+    }
+
+    fn grad_evals(&self) -> u32 {
+        self.grad_calls
+    }
+}
+
+impl<P: Objective + Gradient> HessianVector for FiniteHessian<P> {}
@@ -0,0 +1,133 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2};
+use crate::number_system::DualScalar;
+use crate::problem::Constraints;
+
+
+/// Constraints defined by closures.
+///
+/// The closure `equality` returns the equality constraints `c(x) = 0` and
+/// `inequality` returns the inequality constraints `g(x) <= 0`. The jacobians
+/// are calculated with dual numbers, one evaluation of the closure per
+/// variable. The number of constraints is obtained by evaluating the closures
+/// once when the constraints are created.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::{FnConstraints, Constraints};
+///use optimization::number_system::DualScalar;
+///
+///// point on the unit circle and above the line y = x
+///let constraints = FnConstraints::new(
+///    2,
+///    |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+///    |x: &[DualScalar]| vec![x[0] - x[1]],
+///);
+///assert_eq!(constraints.num_equality(), 1);
+///assert_eq!(constraints.num_inequality(), 1);
+/// ```
+pub struct FnConstraints<E, G> {
+    // function that evaluates the equality constraints
+    equality: E,
+
+    // function that evaluates the inequality constraints
+    inequality: G,
+
+    // variables of the constraint functions
+    x: Vec<DualScalar>,
+
+    // number of equality constraints
+    m_eq: usize,
+
+    // number of inequality constraints
+    m_ineq: usize,
+}
+
+
+impl<E, G> FnConstraints<E, G>
+where
+    E: Fn(&[DualScalar]) -> Vec<DualScalar>,
+    G: Fn(&[DualScalar]) -> Vec<DualScalar>,
+{
+    /// Creates the constraints of a problem with `n` variables.
+    pub fn new(n: usize, equality: E, inequality: G) -> FnConstraints<E, G> {
+        let x = vec![DualScalar::new(); n];
+        let m_eq = equality(&x).len();
+        let m_ineq = inequality(&x).len();
+        FnConstraints {
+            equality,
+            inequality,
+            x,
+            m_eq,
+            m_ineq,
+        }
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].du = 0.0;
+        }
+    }
+}
+
+
+// Fills the jacobian of func at the current variables, one column per variable
+fn jacobian<F>(func: &F, x: &mut [DualScalar], output: &mut Array2<f64>)
+where
+    F: Fn(&[DualScalar]) -> Vec<DualScalar>,
+{
+    for j in 0..x.len() {
+        x[j].du = 1.0;
+        let c = func(x);
+        for i in 0..c.len() {
+            output[[i, j]] = c[i].du;
+        }
+        x[j].du = 0.0;
+    }
+}
+
+
+impl<E, G> Constraints for FnConstraints<E, G>
+where
+    E: Fn(&[DualScalar]) -> Vec<DualScalar>,
+    G: Fn(&[DualScalar]) -> Vec<DualScalar>,
+{
+    fn num_equality(&self) -> usize {
+        self.m_eq
+    }
+
+    fn num_inequality(&self) -> usize {
+        self.m_ineq
+    }
+
+    fn equality(&mut self, x: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        let c = (self.equality)(&self.x);
+        for i in 0..self.m_eq {
+            output[i] = c[i].re;
+        }
+    }
+
+    fn equality_jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>) {
+        self.update_x(x);
+        jacobian(&self.equality, &mut self.x, output);
+    }
+
+    fn inequality(&mut self, x: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        let g = (self.inequality)(&self.x);
+        for i in 0..self.m_ineq {
+            output[i] = g[i].re;
+        }
+    }
+
+    fn inequality_jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>) {
+        self.update_x(x);
+        jacobian(&self.inequality, &mut self.x, output);
+    }
+}
@@ -0,0 +1,349 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::number_system::{DualScalar, HyperDualScalar, HyperDual};
+use crate::problem::{Objective, Gradient, Hessian, HessianVector};
+
+
+mod sealed {
+    // Number types that can be used as variables of a `FnProblem`.
+    pub trait Variables: Sized {
+        fn variables(n: usize) -> Vec<Self>;
+    }
+}
+
+
+impl sealed::Variables for DualScalar {
+    fn variables(n: usize) -> Vec<DualScalar> {
+        vec![DualScalar::new(); n]
+    }
+}
+
+impl sealed::Variables for HyperDualScalar {
+    fn variables(n: usize) -> Vec<HyperDualScalar> {
+        vec![HyperDualScalar::new(); n]
+    }
+}
+
+impl sealed::Variables for HyperDual {
+    fn variables(n: usize) -> Vec<HyperDual> {
+        vec![HyperDual::new(n); n]
+    }
+}
+
+
+/// Problem defined by a closure.
+///
+/// This adapter wraps a function `Fn(&[T]) -> T` and implements the problem
+/// traits for it, so the variables don't need to be stored and seeded by hand.
+/// The number type `T` decides which traits are implemented:
+///
+/// * `DualScalar`: `Objective` and `Gradient`. The gradient needs `n`
+///   evaluations of the function. The hessian-vector product is approximated
+///   with differences of the gradient.
+/// * `HyperDualScalar`: `Objective`, `Gradient` and `Hessian`. The hessian
+///   needs `n(n+1)/2` evaluations of the function and the hessian-vector
+///   product needs `n` evaluations.
+/// * `HyperDual`: `Objective`, `Gradient` and `Hessian`. The gradient and the
+///   hessian are obtained with a single evaluation of the function, however
+///   each operation carries a dense gradient and hessian.
+///
+/// All of them implement `HessianVector`.
+///
+/// # Example
+/// ``` rust
+///use optimization::number_system::DualScalar;
+///use optimization::problem::{FnProblem, Objective, Gradient};
+///use optimization::BFGS;
+///use ndarray::arr1;
+///
+///let mut problem = FnProblem::new(3, |x: &[DualScalar]| {
+///    let u1 = x[0].cos()*x[1].sin() - 0.05;
+///    let u2 = x[1].sin() - 0.2;
+///    let u3 = x[2].powi(2) - 2.56;
+///    u1.powi(2) + u2.powi(2) + u3.powi(2)
+///});
+///
+///let x0 = arr1(&[1.0, 1.0, 1.0]);
+///let sol = BFGS::new().minimize(&x0, &mut problem);
+///assert!(sol.success);
+/// ```
+pub struct FnProblem<T, F> {
+    // function that evaluates the objective
+    func: F,
+
+    // variables of the objective function
+    x: Vec<T>,
+
+    // last evaluation of the objective function
+    value: T,
+
+    // direction of the last call to move_step
+    p: Array1<f64>,
+}
+
+
+impl<T, F> FnProblem<T, F>
+where
+    T: sealed::Variables,
+    F: Fn(&[T]) -> T,
+{
+    /// Creates a problem with `n` variables whose objective function is `func`.
+    pub fn new(n: usize, func: F) -> FnProblem<T, F> {
+        let x = T::variables(n);
+        let value = func(&x);
+        FnProblem {
+            func,
+            x,
+            value,
+            p: Array::zeros(n),
+        }
+    }
+
+    /// Returns the last evaluation of the objective function.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+
+impl<F> Objective for FnProblem<DualScalar, F>
+where
+    F: Fn(&[DualScalar]) -> DualScalar,
+{
+    fn eval(&mut self) {
+        self.value = (self.func)(&self.x);
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].du = 0.0;
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = DualScalar{re: alpha, du: 1.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+impl<F> Gradient for FnProblem<DualScalar, F>
+where
+    F: Fn(&[DualScalar]) -> DualScalar,
+{
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        for x_i in self.x.iter_mut() {
+            x_i.du = 0.0;
+        }
+        for i in 0..self.x.len() {
+            self.x[i].du = 1.0;
+            self.eval();
+            output[i] = self.value.du;
+            self.x[i].du = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.du
+    }
+}
+
+impl<F> HessianVector for FnProblem<DualScalar, F>
+where
+    F: Fn(&[DualScalar]) -> DualScalar,
+{}
+
+
+impl<F> Objective for FnProblem<HyperDualScalar, F>
+where
+    F: Fn(&[HyperDualScalar]) -> HyperDualScalar,
+{
+    fn eval(&mut self) {
+        self.value = (self.func)(&self.x);
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i] = HyperDualScalar{re: x[i], e1: 0.0, e2: 0.0, e1e2: 0.0};
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = HyperDualScalar{re: alpha, e1: 1.0, e2: 1.0, e1e2: 0.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+impl<F> Gradient for FnProblem<HyperDualScalar, F>
+where
+    F: Fn(&[HyperDualScalar]) -> HyperDualScalar,
+{
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.clear_dual_parts();
+        for i in 0..self.x.len() {
+            self.x[i].e1 = 1.0;
+            self.eval();
+            output[i] = self.value.e1;
+            self.x[i].e1 = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.e1
+    }
+}
+
+impl<F> Hessian for FnProblem<HyperDualScalar, F>
+where
+    F: Fn(&[HyperDualScalar]) -> HyperDualScalar,
+{
+    // The hessian is a symmetric matrix so only its upper triangle is evaluated
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        self.clear_dual_parts();
+        for i in 0..self.x.len() {
+            for j in i..self.x.len() {
+                self.x[i].e1 = 1.0;
+                self.x[j].e2 = 1.0;
+                self.eval();
+                output[[i, j]] = self.value.e1e2;
+                output[[j, i]] = self.value.e1e2;
+                self.x[i].e1 = 0.0;
+                self.x[j].e2 = 0.0;
+            }
+        }
+    }
+}
+
+impl<F> HessianVector for FnProblem<HyperDualScalar, F>
+where
+    F: Fn(&[HyperDualScalar]) -> HyperDualScalar,
+{
+    // The direction v is seeded in the first dual part of all the variables so
+    // each evaluation gives one component of H*v
+    fn hess_vec(&mut self, x: &Array1<f64>, v: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        for j in 0..self.x.len() {
+            self.x[j].e1 = v[j];
+        }
+        for i in 0..self.x.len() {
+            self.x[i].e2 = 1.0;
+            self.eval();
+            output[i] = self.value.e1e2;
+            self.x[i].e2 = 0.0;
+        }
+        self.clear_dual_parts();
+    }
+
+    fn hess_vec_grad_evals(&self) -> u32 {
+        0
+    }
+}
+
+impl<F> FnProblem<HyperDualScalar, F> {
+    // removes the dual parts left by a previous call to move_step
+    fn clear_dual_parts(&mut self) {
+        for x_i in self.x.iter_mut() {
+            x_i.e1 = 0.0;
+            x_i.e2 = 0.0;
+            x_i.e1e2 = 0.0;
+        }
+    }
+}
+
+
+impl<F> Objective for FnProblem<HyperDual, F>
+where
+    F: Fn(&[HyperDual]) -> HyperDual,
+{
+    fn eval(&mut self) {
+        self.value = (self.func)(&self.x);
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.real
+    }
+
+    // Each variable is seeded with its unit vector so the gradient and hessian
+    // of the function are obtained in a single evaluation
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].real = x[i];
+            self.x[i].grad.fill(0.0);
+            self.x[i].grad[i] = 1.0;
+            self.x[i].hess.fill(0.0);
+        }
+    }
+
+    // The derivative with respect of alpha is obtained from the gradient
+    // (chain rule) so only the direction p needs to be stored
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        for i in 0..self.x.len() {
+            self.x[i].real = x[i] + alpha*p[i];
+            self.x[i].grad.fill(0.0);
+            self.x[i].grad[i] = 1.0;
+            self.x[i].hess.fill(0.0);
+        }
+        self.p.assign(p);
+    }
+}
+
+impl<F> Gradient for FnProblem<HyperDual, F>
+where
+    F: Fn(&[HyperDual]) -> HyperDual,
+{
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.eval();
+        output.assign(&self.value.grad);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.grad.dot(&self.p)
+    }
+}
+
+impl<F> Hessian for FnProblem<HyperDual, F>
+where
+    F: Fn(&[HyperDual]) -> HyperDual,
+{
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        self.eval();
+        output.assign(&self.value.hess);
+    }
+}
+
+impl<F> HessianVector for FnProblem<HyperDual, F>
+where
+    F: Fn(&[HyperDual]) -> HyperDual,
+{
+    fn hess_vec(&mut self, x: &Array1<f64>, v: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        self.eval();
+        output.assign(&self.value.hess.dot(v));
+    }
+
+    fn hess_vec_grad_evals(&self) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,97 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2};
+use crate::number_system::DualScalar;
+use crate::problem::LeastSquares;
+
+
+/// Least-squares problem defined by a closure that returns the residuals.
+///
+/// The jacobian is calculated with dual numbers, one column (one evaluation of
+/// the closure) per variable.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::FnResiduals;
+///use optimization::number_system::DualScalar;
+///use optimization::LevenbergMarquardt;
+///use ndarray::arr1;
+///
+///let mut problem = FnResiduals::new(3, 3, |x: &[DualScalar]| {
+///    vec![
+///        x[0].cos()*x[1].sin() - 0.05,
+///        x[1].sin() - 0.2,
+///        x[2].powi(2) - 2.56,
+///    ]
+///});
+///
+///let x0 = arr1(&[1.0, 1.0, 1.0]);
+///let sol = LevenbergMarquardt::new().minimize(&x0, &mut problem);
+///assert!(sol.success);
+/// ```
+pub struct FnResiduals<F> {
+    // function that evaluates the residuals
+    func: F,
+
+    // variables of the residual functions
+    x: Vec<DualScalar>,
+
+    // number of residuals
+    m: usize,
+}
+
+
+impl<F> FnResiduals<F>
+where
+    F: Fn(&[DualScalar]) -> Vec<DualScalar>,
+{
+    /// Creates a problem with `n` variables and `m` residuals calculated by
+    /// `func`.
+    pub fn new(n: usize, m: usize, func: F) -> FnResiduals<F> {
+        FnResiduals {
+            func,
+            x: vec![DualScalar::new(); n],
+            m,
+        }
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].du = 0.0;
+        }
+    }
+}
+
+
+impl<F> LeastSquares for FnResiduals<F>
+where
+    F: Fn(&[DualScalar]) -> Vec<DualScalar>,
+{
+    fn num_residuals(&self) -> usize {
+        self.m
+    }
+
+    fn residuals(&mut self, x: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        let r = (self.func)(&self.x);
+        for i in 0..self.m {
+            output[i] = r[i].re;
+        }
+    }
+
+    fn jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>) {
+        self.update_x(x);
+        for j in 0..self.x.len() {
+            self.x[j].du = 1.0;
+            let r = (self.func)(&self.x);
+            for i in 0..self.m {
+                output[[i, j]] = r[i].du;
+            }
+            self.x[j].du = 0.0;
+        }
+    }
+}
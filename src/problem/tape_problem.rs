@@ -0,0 +1,166 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array};
+use crate::number_system::{Tape, Var, DualScalar};
+use crate::problem::{Objective, Gradient, HessianVector};
+
+
+/// Problem defined by a closure and differentiated in reverse mode.
+///
+/// This adapter is similar to [`FnProblem`](crate::problem::FnProblem) but the
+/// function is evaluated with the variables of a [`Tape`], so the full
+/// gradient is obtained with a single evaluation of the function followed by a
+/// backward pass through the tape. This is the best option for problems with
+/// many variables.
+///
+/// The values recorded in the tape are `DualScalar` numbers, so the
+/// hessian-vector product is also exact: the variables are seeded with the
+/// direction `v` in their dual part and the dual part of the adjoints is `H*v`
+/// (forward over reverse mode). It costs a single evaluation of the function
+/// too. The gradient leaves the dual parts at zero.
+///
+/// # Example
+/// ``` rust
+///use optimization::problem::TapeProblem;
+///use optimization::BFGS;
+///use ndarray::arr1;
+///
+///let mut problem = TapeProblem::new(3, |x| {
+///    let u1 = x[0].cos()*x[1].sin() - 0.05;
+///    let u2 = x[1].sin() - 0.2;
+///    let u3 = x[2].powi(2) - 2.56;
+///    u1.powi(2) + u2.powi(2) + u3.powi(2)
+///});
+///
+///let x0 = arr1(&[1.0, 1.0, 1.0]);
+///let sol = BFGS::new().minimize(&x0, &mut problem);
+///assert!(sol.success);
+/// ```
+pub struct TapeProblem<F> {
+    // function that evaluates the objective
+    func: F,
+
+    // tape reused by every evaluation
+    tape: Tape<DualScalar>,
+
+    // current position
+    x: Array1<f64>,
+
+    // direction of the last call to move_step
+    p: Array1<f64>,
+
+    // last evaluation of the objective function
+    value: f64,
+}
+
+
+impl<F> TapeProblem<F>
+where
+    F: for<'t> Fn(&[Var<'t, DualScalar>]) -> Var<'t, DualScalar>,
+{
+    /// Creates a problem with `n` variables whose objective function is `func`.
+    pub fn new(n: usize, func: F) -> TapeProblem<F> {
+        TapeProblem {
+            func,
+            tape: Tape::new(),
+            x: Array::zeros(n),
+            p: Array::zeros(n),
+            value: f64::NAN,
+        }
+    }
+
+    /// Returns the last evaluation of the objective function.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // Records the function at the current position with the variables seeded
+    // with the direction `v` and runs the backward pass. The real part of the
+    // adjoints is the gradient and the dual part is `H*v`.
+    fn eval_adjoints(&mut self, v: &Array1<f64>) -> Vec<DualScalar> {
+        self.tape.clear();
+        let values: Vec<DualScalar> = self.x.iter().zip(v.iter())
+            .map(|(x_i, v_i)| DualScalar{re: *x_i, du: *v_i})
+            .collect();
+        let x = self.tape.vars(&values);
+        let f = (self.func)(&x);
+        self.value = f.value().re;
+        f.grad().wrt_all(&x)
+    }
+
+    // Evaluates the function at the current position and writes its gradient
+    // into `output`.
+    fn eval_grad(&mut self, output: &mut Array1<f64>) {
+        let adjoints = self.eval_adjoints(&Array::zeros(self.x.len()));
+        for (i, adjoint) in adjoints.iter().enumerate() {
+            output[i] = adjoint.re;
+        }
+    }
+}
+
+
+impl<F> Objective for TapeProblem<F>
+where
+    F: for<'t> Fn(&[Var<'t, DualScalar>]) -> Var<'t, DualScalar>,
+{
+    fn eval(&mut self) {
+        self.tape.clear();
+        let values: Vec<DualScalar> = self.x.iter().map(|x_i| DualScalar{re: *x_i, du: 0.0}).collect();
+        let x = self.tape.vars(&values);
+        self.value = (self.func)(&x).value().re;
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+        self.p.fill(0.0);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+        self.p.assign(p);
+    }
+}
+
+
+impl<F> Gradient for TapeProblem<F>
+where
+    F: for<'t> Fn(&[Var<'t, DualScalar>]) -> Var<'t, DualScalar>,
+{
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.eval_grad(output);
+    }
+
+    // The derivative with respect of alpha is obtained from the gradient
+    // (chain rule)
+    fn diff(&mut self) -> f64 {
+        let mut grad = Array::zeros(self.x.len());
+        self.eval_grad(&mut grad);
+        grad.dot(&self.p)
+    }
+}
+
+
+impl<F> HessianVector for TapeProblem<F>
+where
+    F: for<'t> Fn(&[Var<'t, DualScalar>]) -> Var<'t, DualScalar>,
+{
+    fn hess_vec(&mut self, x: &Array1<f64>, v: &Array1<f64>, output: &mut Array1<f64>) {
+        self.update_x(x);
+        let adjoints = self.eval_adjoints(v);
+        for (i, adjoint) in adjoints.iter().enumerate() {
+            output[i] = adjoint.du;
+        }
+    }
+
+    fn hess_vec_grad_evals(&self) -> u32 {
+        0
+    }
+}
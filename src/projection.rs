@@ -0,0 +1,170 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Projections onto simple sets.
+//!
+//! The [`SPG`](crate::SPG) method minimizes a function over any set that
+//! implements the [`Projection`] trait, which only needs to find the closest
+//! point of the set to a given point. This module has the projections of the
+//! common sets whose projection is cheap: boxes ([`Bounds`]), balls
+//! ([`Ball`]), simplices ([`Simplex`]) and unit quaternions
+//! ([`UnitQuaternions`]).
+//!
+//! # Example
+//! ``` rust
+//!use optimization::projection::{Projection, Ball};
+//!use ndarray::arr1;
+//!
+//!let ball = Ball::new(arr1(&[0.0, 0.0]), 1.0);
+//!let mut x = arr1(&[3.0, 4.0]);
+//!ball.project(&mut x);
+//!assert!((x[0] - 0.6).abs() < 1e-12);
+//!assert!((x[1] - 0.8).abs() < 1e-12);
+//! ```
+
+
+use ndarray::Array1;
+
+
+/// Euclidean projection onto a closed set.
+pub trait Projection {
+    /// Replaces `x` with the closest point of the set to `x`.
+    fn project(&self, x: &mut Array1<f64>);
+}
+
+
+/// Box `lower <= x <= upper`. The bounds can be infinite.
+pub struct Bounds {
+    // lower bounds of the variables
+    pub lower: Array1<f64>,
+
+    // upper bounds of the variables
+    pub upper: Array1<f64>,
+}
+
+
+impl Bounds {
+    pub fn new(lower: Array1<f64>, upper: Array1<f64>) -> Bounds {
+        assert_eq!(lower.len(), upper.len(), "bounds have different dimensions");
+        Bounds {
+            lower,
+            upper,
+        }
+    }
+}
+
+
+impl Projection for Bounds {
+    fn project(&self, x: &mut Array1<f64>) {
+        for i in 0..x.len() {
+            x[i] = x[i].min(self.upper[i]).max(self.lower[i]);
+        }
+    }
+}
+
+
+/// Ball `|x - center| <= radius`.
+pub struct Ball {
+    // center of the ball
+    pub center: Array1<f64>,
+
+    // radius of the ball
+    pub radius: f64,
+}
+
+
+impl Ball {
+    pub fn new(center: Array1<f64>, radius: f64) -> Ball {
+        Ball {
+            center,
+            radius,
+        }
+    }
+}
+
+
+impl Projection for Ball {
+    fn project(&self, x: &mut Array1<f64>) {
+        let d = &*x - &self.center;
+        let norm = d.dot(&d).sqrt();
+        if norm > self.radius {
+            x.assign(&(&self.center + &(self.radius/norm*&d)));
+        }
+    }
+}
+
+
+/// Simplex `x >= 0, sum(x) = total` (the probability simplex when the total
+/// is one).
+pub struct Simplex {
+    // sum of the components
+    pub total: f64,
+}
+
+
+impl Simplex {
+    pub fn new(total: f64) -> Simplex {
+        Simplex {
+            total,
+        }
+    }
+}
+
+
+impl Projection for Simplex {
+    fn project(&self, x: &mut Array1<f64>) {
+        // the projection is max(x - tau, 0), the shift tau is found from the
+        // sorted components
+        let mut sorted = x.to_vec();
+        sorted.sort_unstable_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let mut sum = 0.0;
+        let mut tau = 0.0;
+        for (i, x_i) in sorted.iter().enumerate() {
+            sum += x_i;
+            let t = (sum - self.total)/(i + 1) as f64;
+            if *x_i > t {
+                tau = t;
+            }
+        }
+        x.mapv_inplace(|x_i| (x_i - tau).max(0.0));
+    }
+}
+
+
+/// Unit quaternions: each group of four consecutive variables `q0, q1, q2,
+/// q3` (the components of an [`HDQuaternion`](crate::geometry::HDQuaternion))
+/// is kept on the unit sphere. Note that the sphere is not convex, the
+/// projection is only unique away from the origin (where the identity
+/// rotation is used).
+pub struct UnitQuaternions;
+
+
+impl UnitQuaternions {
+    pub fn new() -> UnitQuaternions {
+        UnitQuaternions
+    }
+}
+
+
+impl Default for UnitQuaternions {
+    fn default() -> UnitQuaternions {
+        UnitQuaternions::new()
+    }
+}
+
+
+impl Projection for UnitQuaternions {
+    fn project(&self, x: &mut Array1<f64>) {
+        assert_eq!(x.len() % 4, 0, "the variables are not a set of quaternions");
+        for mut q in x.exact_chunks_mut(4) {
+            let norm = q.dot(&q).sqrt();
+            if norm > 0.0 {
+                q /= norm;
+            }
+            else {
+                q.assign(&Array1::from(vec![1.0, 0.0, 0.0, 0.0]));
+            }
+        }
+    }
+}
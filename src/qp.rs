@@ -0,0 +1,417 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+//! Dense quadratic programming.
+//!
+//! A [`QuadraticProgram`] is the problem
+//!
+//! `min 1/2*x^T*Q*x + c^T*x  s.t.  A_eq*x = b_eq,  A_ineq*x <= b_ineq`
+//!
+//! where `Q` is a symmetric positive semidefinite matrix (`Q = 0` gives a
+//! linear program). It is solved with the primal active-set method of
+//! [`ActiveSet`] ("Numerical Optimization", chapter 16). The programs are
+//! meant to be small (e.g. the subproblems of the [`SQP`](crate::SQP) method
+//! or least-distance placements), all the matrices are dense.
+//!
+//! The solution reports whether the constraints are infeasible or the
+//! objective function is unbounded below, and contains the working set of
+//! active inequality constraints, which can be given back to the solver to
+//! warm start a similar program.
+//!
+//! # Example
+//! ``` rust
+//!use optimization::qp::{QuadraticProgram, ActiveSet, QPStatus};
+//!use ndarray::{arr1, arr2, Array2};
+//!
+//!// point of the triangle x0 + x1 <= 1, x >= 0 closest to (1, 3)
+//!let program = QuadraticProgram::new(Array2::eye(2), arr1(&[-1.0, -3.0]))
+//!    .with_inequality(arr2(&[[1.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]), arr1(&[1.0, 0.0, 0.0]));
+//!let sol = ActiveSet::new().solve(&program);
+//!assert_eq!(sol.status, QPStatus::Optimal);
+//!assert!((sol.x[0] - 0.0).abs() < 1e-10);
+//!assert!((sol.x[1] - 1.0).abs() < 1e-10);
+//!assert_eq!(sol.working_set.len(), 2);
+//! ```
+
+
+use ndarray::{Array1, Array2, Array, ArrayView1, s};
+use crate::linalg;
+
+
+/// Quadratic program `min 1/2*x^T*Q*x + c^T*x` subject to `A_eq*x = b_eq`
+/// and `A_ineq*x <= b_ineq`.
+#[derive(Debug, Clone)]
+pub struct QuadraticProgram {
+    /// Symmetric positive semidefinite matrix of the quadratic term.
+    pub q: Array2<f64>,
+
+    /// Linear term.
+    pub c: Array1<f64>,
+
+    /// Matrix of the equality constraints (one row per constraint).
+    pub a_eq: Array2<f64>,
+
+    /// Right hand side of the equality constraints.
+    pub b_eq: Array1<f64>,
+
+    /// Matrix of the inequality constraints (one row per constraint).
+    pub a_ineq: Array2<f64>,
+
+    /// Right hand side of the inequality constraints.
+    pub b_ineq: Array1<f64>,
+}
+
+
+impl QuadraticProgram {
+    /// Creates the program without constraints.
+    pub fn new(q: Array2<f64>, c: Array1<f64>) -> QuadraticProgram {
+        let n = c.len();
+        QuadraticProgram {
+            q,
+            c,
+            a_eq: Array::zeros((0, n)),
+            b_eq: Array::zeros(0),
+            a_ineq: Array::zeros((0, n)),
+            b_ineq: Array::zeros(0),
+        }
+    }
+
+    /// Sets the equality constraints `a*x = b`.
+    pub fn with_equality(mut self, a: Array2<f64>, b: Array1<f64>) -> QuadraticProgram {
+        self.a_eq = a;
+        self.b_eq = b;
+        self
+    }
+
+    /// Sets the inequality constraints `a*x <= b`.
+    pub fn with_inequality(mut self, a: Array2<f64>, b: Array1<f64>) -> QuadraticProgram {
+        self.a_ineq = a;
+        self.b_ineq = b;
+        self
+    }
+
+    /// Value of the objective function at `x`.
+    pub fn eval(&self, x: &Array1<f64>) -> f64 {
+        0.5*x.dot(&self.q.dot(x)) + self.c.dot(x)
+    }
+
+    // Rows of the equality constraints followed by the rows of the
+    // inequality constraints in the working set
+    fn working_rows<'a>(&'a self, working_set: &'a [usize]) -> impl Iterator<Item = ArrayView1<'a, f64>> {
+        self.a_eq.rows().into_iter().chain(working_set.iter().map(move |i| self.a_ineq.row(*i)))
+    }
+}
+
+
+/// Termination reason of the [`ActiveSet`] method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QPStatus {
+    /// The KKT conditions are satisfied, `x` is a minimum.
+    Optimal,
+    /// There is no point that satisfies the constraints. `x` is the point
+    /// that minimizes the max violation of the constraints.
+    Infeasible,
+    /// The objective function decreases without bound along a direction of
+    /// zero curvature that doesn't hit any constraint.
+    Unbounded,
+    /// The maximum number of iterations was reached.
+    MaxIterations,
+}
+
+
+/// Result of the [`ActiveSet`] method.
+#[derive(Debug, Clone)]
+pub struct QPSolution {
+    /// Solution of the program (last point of the iterations).
+    pub x: Array1<f64>,
+
+    /// Whether the program was solved (`status` is `Optimal`).
+    pub success: bool,
+
+    /// Reason why the solver stopped.
+    pub status: QPStatus,
+
+    /// Objective function value at `x`.
+    pub f: f64,
+
+    /// Lagrange multipliers of the equality constraints.
+    pub equality_multipliers: Array1<f64>,
+
+    /// Lagrange multipliers of the inequality constraints (they are zero for
+    /// the constraints that are not in the working set).
+    pub inequality_multipliers: Array1<f64>,
+
+    /// Indices of the inequality constraints in the working set at `x`.
+    pub working_set: Vec<usize>,
+
+    /// Number of iterations, including the ones needed to find a feasible
+    /// point.
+    pub iter_num: u32,
+}
+
+
+/// Primal active-set method for quadratic programs.
+///
+/// The method keeps a working set of inequality constraints that are treated
+/// as equalities. Each iteration solves the KKT system of the working set:
+/// the step either stops at a new constraint (which is added to the working
+/// set) or reaches the minimum on the working set, where the constraint with
+/// the most negative multiplier is removed. When the KKT system is singular
+/// (`Q` semidefinite) its least-squares residual gives a direction of zero
+/// curvature along which the objective function decreases, which is followed
+/// until a constraint blocks it or detected as unbounded.
+///
+/// A feasible starting point is found first by solving the linear program
+/// `min t s.t. A_eq*x = b_eq, A_ineq*x - t <= b_ineq` with the same
+/// iterations. The program is infeasible when the optimal `t` is positive.
+pub struct ActiveSet {
+    // max number of iterations of each phase
+    pub i_max: u32,
+
+    // feasibility and optimality tolerance
+    pub tol: f64,
+}
+
+
+impl ActiveSet {
+    pub fn new() -> ActiveSet {
+        ActiveSet {
+            i_max: 1000,
+            tol: 1e-9,
+        }
+    }
+
+    /// Solves the program starting from the origin with an empty working set.
+    pub fn solve(&self, program: &QuadraticProgram) -> QPSolution {
+        self.solve_from(program, &Array::zeros(program.c.len()), &[])
+    }
+
+    /// Solves the program starting from `x0` and the working set of a
+    /// previous solution (warm start).
+    ///
+    /// The point that satisfies the equality constraints and the constraints
+    /// of the working set closest to `x0` is used as starting point when it
+    /// satisfies the other inequality constraints. Otherwise the working set
+    /// is discarded and a feasible point is searched from `x0`. Solving a
+    /// sequence of similar programs (e.g. the subproblems of SQP) usually
+    /// takes few iterations when the working set of the previous program is
+    /// given.
+    pub fn solve_from(&self, program: &QuadraticProgram, x0: &Array1<f64>, working_set: &[usize]) -> QPSolution {
+        let n = x0.len();
+        let m_eq = program.b_eq.len();
+        let m_ineq = program.b_ineq.len();
+
+        if !working_set.is_empty() {
+            let mut working_set = working_set.to_vec();
+            working_set.sort_unstable();
+            working_set.dedup();
+            working_set.retain(|i| *i < m_ineq);
+            let mut a: Array2<f64> = Array::zeros((m_eq + working_set.len(), n));
+            let mut b: Array1<f64> = Array::zeros(m_eq + working_set.len());
+            for (i, row) in program.working_rows(&working_set).enumerate() {
+                a.row_mut(i).assign(&row);
+            }
+            b.slice_mut(s![..m_eq]).assign(&program.b_eq);
+            for (j, i) in working_set.iter().enumerate() {
+                b[m_eq + j] = program.b_ineq[*i];
+            }
+            // the rows of the working set must be linearly independent
+            if linalg::rank(&a) == a.nrows() {
+                let x = x0 + &linalg::lstsq(&a, &(&b - &a.dot(x0)));
+                if max_abs(&(a.dot(&x) - &b)) <= self.tol*(1.0 + max_abs(&b))
+                    && self.violation(program, &x) <= self.tol*(1.0 + max_abs(&program.b_ineq)) {
+                    return self.active_set(program, x, working_set, 0)
+                }
+            }
+        }
+
+        let mut solution = QPSolution {
+            x: x0.clone(),
+            success: false,
+            status: QPStatus::Infeasible,
+            f: f64::NAN,
+            equality_multipliers: Array::zeros(m_eq),
+            inequality_multipliers: Array::zeros(m_ineq),
+            working_set: Vec::new(),
+            iter_num: 0,
+        };
+
+        // closest point to x0 that satisfies the equality constraints
+        let x = x0 + &linalg::lstsq(&program.a_eq, &(&program.b_eq - &program.a_eq.dot(x0)));
+        if max_abs(&(program.a_eq.dot(&x) - &program.b_eq)) > self.tol*(1.0 + max_abs(&program.b_eq)) {
+            solution.f = program.eval(&x);
+            solution.x = x;
+            return solution
+        }
+
+        // linear program that minimizes the max violation t of the
+        // inequality constraints, the last inequality is t >= 0
+        let violation = self.violation(program, &x);
+        if violation <= self.tol*(1.0 + max_abs(&program.b_ineq)) {
+            return self.active_set(program, x, Vec::new(), 0)
+        }
+        let mut phase_1 = QuadraticProgram::new(Array::zeros((n + 1, n + 1)), Array::zeros(n + 1));
+        phase_1.c[n] = 1.0;
+        phase_1.a_eq = Array::zeros((m_eq, n + 1));
+        phase_1.a_eq.slice_mut(s![.., ..n]).assign(&program.a_eq);
+        phase_1.b_eq = program.b_eq.clone();
+        phase_1.a_ineq = Array::zeros((m_ineq + 1, n + 1));
+        phase_1.a_ineq.slice_mut(s![..m_ineq, ..n]).assign(&program.a_ineq);
+        phase_1.a_ineq.slice_mut(s![.., n]).fill(-1.0);
+        phase_1.b_ineq = Array::zeros(m_ineq + 1);
+        phase_1.b_ineq.slice_mut(s![..m_ineq]).assign(&program.b_ineq);
+        let mut x_t = Array::zeros(n + 1);
+        x_t.slice_mut(s![..n]).assign(&x);
+        x_t[n] = violation;
+
+        let feasible = self.active_set(&phase_1, x_t, Vec::new(), 0);
+        let x = feasible.x.slice(s![..n]).to_owned();
+        // phase 1 didn't finish or the min violation is positive
+        if feasible.status != QPStatus::Optimal || feasible.x[n] > self.tol*(1.0 + max_abs(&program.b_ineq)) {
+            if feasible.status == QPStatus::MaxIterations {
+                solution.status = QPStatus::MaxIterations;
+            }
+            solution.f = program.eval(&x);
+            solution.x = x;
+            solution.iter_num = feasible.iter_num;
+            return solution
+        }
+        self.active_set(program, x, Vec::new(), feasible.iter_num)
+    }
+
+    // Max violation of the inequality constraints at x
+    fn violation(&self, program: &QuadraticProgram, x: &Array1<f64>) -> f64 {
+        (program.a_ineq.dot(x) - &program.b_ineq).fold(0.0, |acc: f64, v| acc.max(*v))
+    }
+
+    // Active-set iterations from the feasible point x
+    fn active_set(&self,
+                  program: &QuadraticProgram,
+                  mut x: Array1<f64>,
+                  mut working_set: Vec<usize>,
+                  iter_num: u32) -> QPSolution
+    {
+        let n = x.len();
+        let m_eq = program.b_eq.len();
+        let mut status = QPStatus::MaxIterations;
+        let mut multipliers: Array1<f64> = Array::zeros(m_eq);
+        // the last step reached the minimum of the working set, the next step
+        // would only be rounding noise (which can be large compared with x
+        // when the gradient is large)
+        let mut at_minimum = false;
+
+        let mut k: u32 = 0;
+        while k < self.i_max {
+            k += 1;
+            let g = program.q.dot(&x) + &program.c;
+
+            // KKT system of the working set: Q*p + A_w^T*y = -g, A_w*p = 0
+            let m = m_eq + working_set.len();
+            let mut kkt: Array2<f64> = Array::zeros((n + m, n + m));
+            kkt.slice_mut(s![..n, ..n]).assign(&program.q);
+            for (i, row) in program.working_rows(&working_set).enumerate() {
+                kkt.slice_mut(s![n + i, ..n]).assign(&row);
+                kkt.slice_mut(s![..n, n + i]).assign(&row);
+            }
+            let mut rhs: Array1<f64> = Array::zeros(n + m);
+            rhs.slice_mut(s![..n]).assign(&(-&g));
+            let (z, _) = linalg::lstsq_rank(&kkt, &rhs, 1e-12);
+            let residual = &rhs - &kkt.dot(&z);
+
+            // inconsistent system: the objective decreases without bound
+            // along the residual (Q*p = 0 and A_w*p = 0)
+            let curvature_free = max_abs(&residual) > self.tol*(1.0 + max_abs(&g));
+            let p = if curvature_free {
+                residual.slice(s![..n]).to_owned()
+            }
+            else {
+                z.slice(s![..n]).to_owned()
+            };
+
+            if !curvature_free && (at_minimum || max_abs(&p) <= self.tol*(1.0 + max_abs(&x))) {
+                multipliers = z.slice(s![n..]).to_owned();
+                // the constraint with the most negative multiplier leaves
+                // the working set
+                let mut leaving = None;
+                let mut min_multiplier = -self.tol;
+                for (j, y) in multipliers.iter().skip(m_eq).enumerate() {
+                    if *y < min_multiplier {
+                        min_multiplier = *y;
+                        leaving = Some(j);
+                    }
+                }
+                match leaving {
+                    Some(j) => {
+                        working_set.remove(j);
+                        at_minimum = false;
+                        continue;
+                    },
+                    None => {
+                        status = QPStatus::Optimal;
+                        break;
+                    },
+                }
+            }
+
+            // longest step that keeps the inequality constraints satisfied
+            let mut alpha = if curvature_free { f64::INFINITY } else { 1.0 };
+            let mut blocking = None;
+            let p_norm = max_abs(&p);
+            for i in 0..program.b_ineq.len() {
+                if working_set.contains(&i) {
+                    continue;
+                }
+                let row = program.a_ineq.row(i);
+                let slope = row.dot(&p);
+                let row_norm = row.iter().fold(0.0, |acc: f64, a| acc.max(a.abs()));
+                if slope > f64::EPSILON.sqrt()*p_norm*row_norm {
+                    let alpha_i = (program.b_ineq[i] - row.dot(&x)).max(0.0)/slope;
+                    if alpha_i < alpha {
+                        alpha = alpha_i;
+                        blocking = Some(i);
+                    }
+                }
+            }
+            if alpha.is_infinite() {
+                status = QPStatus::Unbounded;
+                break;
+            }
+            x += &(alpha*&p);
+            match blocking {
+                Some(i) => working_set.push(i),
+                None => at_minimum = true,
+            }
+        }
+
+        let mut inequality_multipliers: Array1<f64> = Array::zeros(program.b_ineq.len());
+        if status == QPStatus::Optimal {
+            for (j, i) in working_set.iter().enumerate() {
+                inequality_multipliers[*i] = multipliers[m_eq + j];
+            }
+        }
+        QPSolution {
+            f: program.eval(&x),
+            x,
+            success: status == QPStatus::Optimal,
+            status,
+            equality_multipliers: multipliers.slice(s![..m_eq]).to_owned(),
+            inequality_multipliers,
+            working_set,
+            iter_num: iter_num + k,
+        }
+    }
+}
+
+
+impl Default for ActiveSet {
+    fn default() -> ActiveSet {
+        ActiveSet::new()
+    }
+}
+
+
+fn max_abs(x: &Array1<f64>) -> f64 {
+    x.iter().fold(0.0, |acc: f64, x_i| acc.max(x_i.abs()))
+}
@@ -0,0 +1,186 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+//! Solvers of systems of nonlinear equations `F(x) = 0`.
+//!
+//! The equations are given with the [`LeastSquares`] trait (the residuals are
+//! the values of the equations), so a closure written with dual numbers can be
+//! used through [`FnResiduals`](crate::problem::FnResiduals), which calculates
+//! the jacobian with forward differentiation.
+//!
+//! Solving the equations directly instead of minimizing the sum of their
+//! squares keeps the information about the rank of the jacobian. Every solver
+//! returns a [`RootSolution`] with the norm of the residuals and the rank of
+//! the jacobian at the solution, which tells if the system is over or under
+//! determined (e.g. redundant or missing geometric constraints).
+//!
+//! * [`Newton`]: Newton's method damped with a backtracking line search.
+//! * [`Hybrid`]: Powell's hybrid method, the newton step and the steepest
+//!   descent direction of `0.5*|F|^2` are combined with the dogleg method
+//!   inside a trust region.
+//! * [`Broyden`]: Quasi-newton method that updates an approximation of the
+//!   jacobian with Broyden's rank one formula, so the jacobian is only
+//!   calculated at the start (and when the approximation stops working).
+//!
+//! Non square systems are solved in the least-squares sense: the steps are
+//! calculated with a QR factorization with column pivoting.
+//!
+//! # Example
+//! ``` rust
+//!use optimization::root::Hybrid;
+//!use optimization::problem::FnResiduals;
+//!use optimization::number_system::DualScalar;
+//!use ndarray::arr1;
+//!
+//!// point at distance 5 of the origin and distance 5 of (8, 0)
+//!let mut problem = FnResiduals::new(2, 2, |x: &[DualScalar]| {
+//!    vec![
+//!        x[0].powi(2) + x[1].powi(2) - 25.0,
+//!        (x[0] - 8.0).powi(2) + x[1].powi(2) - 25.0,
+//!    ]
+//!});
+//!
+//!let sol = Hybrid::new().solve(&arr1(&[1.0, 1.0]), &mut problem);
+//!assert!(sol.success);
+//!assert!((sol.x[0] - 4.0).abs() < 1e-8);
+//!assert!((sol.x[1] - 3.0).abs() < 1e-8);
+//!assert!(!sol.is_overdetermined() && !sol.is_underdetermined());
+//! ```
+
+
+use ndarray::{Array1, Array2};
+use std::cell::Cell;
+use crate::Status;
+use crate::linalg;
+use crate::line_search::LineSearchError;
+use crate::problem::LeastSquares;
+
+
+mod newton;
+pub use newton::Newton;
+
+mod hybrid;
+pub use hybrid::Hybrid;
+
+mod broyden;
+pub use broyden::Broyden;
+
+
+/// Result returned by the solvers of systems of equations.
+#[derive(Debug, Clone)]
+pub struct RootSolution {
+    /// Solution of the system (last position of the solver).
+    pub x: Array1<f64>,
+
+    /// Whether the solver found a root. This is `true` only when `status` is a
+    /// successful termination.
+    pub success: bool,
+
+    /// Reason why the solver stopped.
+    pub status: Status,
+
+    /// Total number of iterations.
+    pub iter_num: u32,
+
+    /// Number of evaluations of the equations.
+    pub f_evals: u32,
+
+    /// Number of jacobian evaluations.
+    pub f_jac_evals: u32,
+
+    /// Values of the equations at `x`.
+    pub residuals: Array1<f64>,
+
+    /// Euclidean norm of the residuals at `x`.
+    pub residual_norm: f64,
+
+    /// Jacobian of the equations at `x`.
+    pub jacobian: Array2<f64>,
+
+    /// Numerical rank of the jacobian at `x`.
+    pub rank: usize,
+}
+
+
+impl RootSolution {
+    pub(crate) fn new(x: Array1<f64>,
+                      residuals: Array1<f64>,
+                      jacobian: Array2<f64>,
+                      status: Status,
+                      iter_num: u32) -> RootSolution {
+        RootSolution {
+            x,
+            success: status.is_success(),
+            status,
+            iter_num,
+            f_evals: 0,
+            f_jac_evals: 0,
+            residual_norm: residuals.dot(&residuals).sqrt(),
+            residuals,
+            rank: linalg::rank(&jacobian),
+            jacobian,
+        }
+    }
+
+    /// Returns `true` when the rank of the jacobian is smaller than the number
+    /// of equations, i.e. some equations are redundant (or inconsistent when
+    /// the residuals are not zero).
+    pub fn is_overdetermined(&self) -> bool {
+        self.rank < self.residuals.len()
+    }
+
+    /// Returns `true` when the rank of the jacobian is smaller than the number
+    /// of variables, i.e. the solution can move along some directions without
+    /// changing the equations, so it is not unique.
+    pub fn is_underdetermined(&self) -> bool {
+        self.rank < self.x.len()
+    }
+}
+
+
+// Backtracking line search on the merit function 0.5*|F(x + alpha*p)|^2 with
+// the jacobian (or its approximation) j. The steps are reduced with quadratic
+// interpolation until the Armijo condition is satisfied. Returns the step
+// length and the residuals at the new point are written into r_new.
+fn line_search<P>(problem: &mut P,
+                  x: &Array1<f64>,
+                  p: &Array1<f64>,
+                  r: &Array1<f64>,
+                  j: &Array2<f64>,
+                  r_new: &mut Array1<f64>,
+                  f_calls: &Cell<u32>) -> Result<f64, LineSearchError>
+where
+    P: LeastSquares,
+{
+    const C1: f64 = 1e-4;
+    const I_MAX: u32 = 50;
+    let phi_0 = 0.5*r.dot(r);
+    let dphi_0 = r.dot(&j.dot(p));
+    if dphi_0 >= 0.0 {
+        return Err(LineSearchError::NotDescentDirection)
+    }
+
+    let mut alpha = 1.0;
+    for _ in 0..I_MAX {
+        f_calls.set(f_calls.get() + 1);
+        problem.residuals(&(x + &(alpha*p)), r_new);
+        let phi = 0.5*r_new.dot(r_new);
+        if phi <= phi_0 + C1*alpha*dphi_0 {
+            return Ok(alpha)
+        }
+        let alpha_new = if phi.is_finite() {
+            // minimum of the quadratic interpolation of phi
+            -dphi_0*alpha.powi(2)/(2.0*(phi - phi_0 - dphi_0*alpha))
+        }
+        else {
+            0.5*alpha
+        };
+        alpha = alpha_new.max(0.1*alpha).min(0.5*alpha);
+        if alpha <= f64::EPSILON {
+            return Err(LineSearchError::IntervalCollapsed)
+        }
+    }
+    Err(LineSearchError::MaxIterations)
+}
@@ -0,0 +1,175 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array, Axis};
+use crate::Status;
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::linalg;
+use std::cell::Cell;
+use crate::problem::LeastSquares;
+use super::{RootSolution, line_search};
+
+
+/// Broyden's quasi-newton method.
+///
+/// The jacobian is calculated at the starting point and then updated with
+/// Broyden's rank one formula `B += (y - B*s)*s^T/(s^T*s)`, where `s` is the
+/// step and `y` the change of the residuals. The steps are damped with a
+/// backtracking line search as in [`Newton`](super::Newton). When the line
+/// search fails with the approximation, the exact jacobian is calculated again
+/// before giving up. It needs fewer jacobian evaluations than Newton's method,
+/// which is useful when the jacobian is expensive, but it converges
+/// superlinearly instead of quadratically.
+pub struct Broyden {
+    // max number of iterations
+    pub i_max: u32,
+
+    // the solver stops when the norm of the residuals is smaller than ftol
+    pub ftol: f64,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // residuals at the current position
+    r_k: Array1<f64>,
+
+    // approximation of the jacobian at the current position
+    b_k: Array2<f64>,
+
+    // holder for residual calls
+    f_calls: Cell<u32>,
+
+    // holder for jacobian calls
+    f_jac_calls: Cell<u32>,
+}
+
+
+impl Broyden {
+    pub fn new() -> Broyden {
+        Broyden {
+            i_max: 200,
+            ftol: 1e-10,
+            x_k: Array1::zeros(1),
+            r_k: Array1::zeros(1),
+            b_k: Array2::zeros((1, 1)),
+            f_calls: Cell::new(0),
+            f_jac_calls: Cell::new(0),
+        }
+    }
+
+    pub fn solve<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> RootSolution
+    where
+        P: LeastSquares,
+    {
+        self.solve_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn solve_with_observer<P, O>(&mut self,
+                                     x0: &Array1<f64>,
+                                     problem: &mut P,
+                                     observer: &mut O) -> RootSolution
+    where
+        P: LeastSquares,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_jac_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let mut r_new: Array1<f64> = Array::zeros(self.r_k.len());
+        // whether b_k is the exact jacobian at x_k
+        let mut exact = true;
+        let mut status = Status::MaxIterations;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.r_k.iter().chain(self.b_k.iter()).all(|v| v.is_finite()) {
+                status = Status::NonFinite;
+                break;
+            }
+            if self.r_k.dot(&self.r_k).sqrt() <= self.ftol {
+                status = Status::ResidualTolerance;
+                break;
+            }
+
+            let p_k = linalg::lstsq(&self.b_k, &(-&self.r_k));
+            let alpha = match line_search(problem, &self.x_k, &p_k, &self.r_k, &self.b_k,
+                                          &mut r_new, &self.f_calls) {
+                Ok(alpha) => alpha,
+                Err(error) if exact => {
+                    status = Status::LineSearchFailure(error);
+                    break;
+                },
+                Err(_) => {
+                    // restart the approximation with the exact jacobian
+                    self.eval_jacobian(problem);
+                    exact = true;
+                    continue;
+                },
+            };
+            exact = false;
+
+            let s_k = alpha*&p_k;
+            let y_k = &r_new - &self.r_k;
+            self.x_k += &s_k;
+            self.r_k.assign(&r_new);
+            let u = (&y_k - &self.b_k.dot(&s_k))/s_k.dot(&s_k);
+            self.b_k += &(u.insert_axis(Axis(1)).dot(&s_k.view().insert_axis(Axis(0))));
+
+            let g_k = self.b_k.t().dot(&self.r_k);
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: 0.5*self.r_k.dot(&self.r_k),
+                grad_norm: g_k.dot(&g_k).sqrt(),
+                step_length: s_k.dot(&s_k).sqrt(),
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        // the rank is reported with the exact jacobian
+        if !exact {
+            self.eval_jacobian(problem);
+        }
+        let mut solution = RootSolution::new(self.x_k.clone(), self.r_k.clone(),
+                                             self.b_k.clone(), status, k);
+        solution.f_evals = self.f_calls.get();
+        solution.f_jac_evals = self.f_jac_calls.get();
+        solution
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        let m = problem.num_residuals();
+        self.x_k = x0.clone();
+        self.r_k = Array::zeros(m);
+        self.b_k = Array::zeros((m, x0.len()));
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&self.x_k, &mut self.r_k);
+        self.eval_jacobian(problem);
+    }
+
+    fn eval_jacobian<P>(&mut self, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        self.f_jac_calls.set(self.f_jac_calls.get() + 1);
+        problem.jacobian(&self.x_k, &mut self.b_k);
+    }
+}
+
+
+impl Default for Broyden {
+    fn default() -> Broyden {
+        Broyden::new()
+    }
+}
@@ -0,0 +1,200 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::Status;
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::trust_region::{self, TrustRegion};
+use crate::linalg;
+use std::cell::Cell;
+use crate::problem::LeastSquares;
+use super::RootSolution;
+
+
+/// Powell's hybrid method.
+///
+/// The step combines the newton step with the steepest descent direction of
+/// `0.5*|F|^2` using the dogleg method inside a trust region (the method of
+/// MINPACK's `hybrj`, with the exact jacobian at every accepted step). Far from
+/// the root it behaves like steepest descent, which makes it more robust than
+/// Newton's method when the starting point is bad or the jacobian is nearly
+/// singular.
+pub struct Hybrid {
+    // max number of iterations
+    pub i_max: u32,
+
+    // the solver stops when the norm of the residuals is smaller than ftol
+    pub ftol: f64,
+
+    // initial trust region radius
+    pub delta_0: f64,
+
+    // max trust region radius
+    pub delta_max: f64,
+
+    // min trust region radius, the solver stops when the radius is smaller
+    pub delta_min: f64,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // residuals at the current position
+    r_k: Array1<f64>,
+
+    // jacobian at the current position
+    j_k: Array2<f64>,
+
+    // gradient of 0.5*|F|^2, g = J^T*r
+    g_k: Array1<f64>,
+
+    // holder for residual calls
+    f_calls: Cell<u32>,
+
+    // holder for jacobian calls
+    f_jac_calls: Cell<u32>,
+}
+
+
+impl Hybrid {
+    pub fn new() -> Hybrid {
+        Hybrid {
+            i_max: 1000,
+            ftol: 1e-10,
+            delta_0: 1.0,
+            delta_max: 100.0,
+            delta_min: 1e-12,
+            x_k: Array1::zeros(1),
+            r_k: Array1::zeros(1),
+            j_k: Array2::zeros((1, 1)),
+            g_k: Array1::zeros(1),
+            f_calls: Cell::new(0),
+            f_jac_calls: Cell::new(0),
+        }
+    }
+
+    pub fn solve<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> RootSolution
+    where
+        P: LeastSquares,
+    {
+        self.solve_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn solve_with_observer<P, O>(&mut self,
+                                     x0: &Array1<f64>,
+                                     problem: &mut P,
+                                     observer: &mut O) -> RootSolution
+    where
+        P: LeastSquares,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_jac_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let mut region = TrustRegion::new(self.delta_0, self.delta_max);
+
+        let mut x_new: Array1<f64> = Array::zeros(x0.raw_dim());
+        let mut r_new: Array1<f64> = Array::zeros(self.r_k.len());
+        // newton step, it only changes when a step is accepted
+        let mut p_n: Array1<f64> = Array::zeros(x0.raw_dim());
+        let mut update_newton = true;
+        let mut status = Status::MaxIterations;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.r_k.iter().chain(self.g_k.iter()).all(|v| v.is_finite()) {
+                status = Status::NonFinite;
+                break;
+            }
+            let f_k = 0.5*self.r_k.dot(&self.r_k);
+            if (2.0*f_k).sqrt() <= self.ftol {
+                status = Status::ResidualTolerance;
+                break;
+            }
+            if region.delta < self.delta_min {
+                status = Status::TrustRadiusCollapsed;
+                break;
+            }
+
+            if update_newton {
+                p_n = linalg::lstsq(&self.j_k, &(-&self.r_k));
+            }
+            let p_k = trust_region::dogleg(&self.j_k, &self.g_k, &p_n, region.delta);
+
+            x_new.assign(&(&self.x_k + &p_k));
+            self.f_calls.set(self.f_calls.get() + 1);
+            problem.residuals(&x_new, &mut r_new);
+            let f_new = 0.5*r_new.dot(&r_new);
+
+            // predicted reduction of the linear model of F
+            let jp = self.j_k.dot(&p_k);
+            let predicted = -self.g_k.dot(&p_k) - 0.5*jp.dot(&jp);
+            let mut rho = (f_k - f_new)/predicted;
+            if !f_new.is_finite() || predicted <= 0.0 {
+                rho = f64::NEG_INFINITY;
+            }
+
+            let mut step_length = p_k.dot(&p_k).sqrt();
+            update_newton = region.update(rho, step_length);
+            if update_newton {
+                self.x_k.assign(&x_new);
+                self.r_k.assign(&r_new);
+                self.eval_jacobian(problem);
+            }
+            else {
+                step_length = 0.0;
+            }
+
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: 0.5*self.r_k.dot(&self.r_k),
+                grad_norm: self.g_k.dot(&self.g_k).sqrt(),
+                step_length,
+                trust_radius: Some(region.delta),
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        let mut solution = RootSolution::new(self.x_k.clone(), self.r_k.clone(),
+                                             self.j_k.clone(), status, k);
+        solution.f_evals = self.f_calls.get();
+        solution.f_jac_evals = self.f_jac_calls.get();
+        solution
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        let m = problem.num_residuals();
+        self.x_k = x0.clone();
+        self.r_k = Array::zeros(m);
+        self.j_k = Array::zeros((m, x0.len()));
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&self.x_k, &mut self.r_k);
+        self.eval_jacobian(problem);
+    }
+
+    fn eval_jacobian<P>(&mut self, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        self.f_jac_calls.set(self.f_jac_calls.get() + 1);
+        problem.jacobian(&self.x_k, &mut self.j_k);
+        self.g_k = self.j_k.t().dot(&self.r_k);
+    }
+}
+
+
+impl Default for Hybrid {
+    fn default() -> Hybrid {
+        Hybrid::new()
+    }
+}
@@ -0,0 +1,154 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array};
+use crate::Status;
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::linalg;
+use std::cell::Cell;
+use crate::problem::LeastSquares;
+use super::{RootSolution, line_search};
+
+
+/// Damped Newton's method.
+///
+/// The newton step `J*p = -F` is calculated at each iteration with the exact
+/// jacobian and its length is reduced with a backtracking line search on
+/// `0.5*|F|^2` when the full step doesn't decrease the residuals enough. It
+/// converges quadratically near a regular root.
+pub struct Newton {
+    // max number of iterations
+    pub i_max: u32,
+
+    // the solver stops when the norm of the residuals is smaller than ftol
+    pub ftol: f64,
+
+    // current position
+    x_k: Array1<f64>,
+
+    // residuals at the current position
+    r_k: Array1<f64>,
+
+    // jacobian at the current position
+    j_k: Array2<f64>,
+
+    // holder for residual calls
+    f_calls: Cell<u32>,
+
+    // holder for jacobian calls
+    f_jac_calls: Cell<u32>,
+}
+
+
+impl Newton {
+    pub fn new() -> Newton {
+        Newton {
+            i_max: 100,
+            ftol: 1e-10,
+            x_k: Array1::zeros(1),
+            r_k: Array1::zeros(1),
+            j_k: Array2::zeros((1, 1)),
+            f_calls: Cell::new(0),
+            f_jac_calls: Cell::new(0),
+        }
+    }
+
+    pub fn solve<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> RootSolution
+    where
+        P: LeastSquares,
+    {
+        self.solve_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn solve_with_observer<P, O>(&mut self,
+                                     x0: &Array1<f64>,
+                                     problem: &mut P,
+                                     observer: &mut O) -> RootSolution
+    where
+        P: LeastSquares,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_jac_calls.set(0);
+        self.set_up_parameters(x0, problem);
+        let mut r_new: Array1<f64> = Array::zeros(self.r_k.len());
+        let mut status = Status::MaxIterations;
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !self.r_k.iter().chain(self.j_k.iter()).all(|v| v.is_finite()) {
+                status = Status::NonFinite;
+                break;
+            }
+            if self.r_k.dot(&self.r_k).sqrt() <= self.ftol {
+                status = Status::ResidualTolerance;
+                break;
+            }
+
+            let p_k = linalg::lstsq(&self.j_k, &(-&self.r_k));
+            let alpha = match line_search(problem, &self.x_k, &p_k, &self.r_k, &self.j_k,
+                                          &mut r_new, &self.f_calls) {
+                Ok(alpha) => alpha,
+                Err(error) => {
+                    status = Status::LineSearchFailure(error);
+                    break;
+                },
+            };
+            self.x_k.scaled_add(alpha, &p_k);
+            self.r_k.assign(&r_new);
+            self.eval_jacobian(problem);
+
+            let g_k = self.j_k.t().dot(&self.r_k);
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: 0.5*self.r_k.dot(&self.r_k),
+                grad_norm: g_k.dot(&g_k).sqrt(),
+                step_length: alpha*p_k.dot(&p_k).sqrt(),
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        let mut solution = RootSolution::new(self.x_k.clone(), self.r_k.clone(),
+                                             self.j_k.clone(), status, k);
+        solution.f_evals = self.f_calls.get();
+        solution.f_jac_evals = self.f_jac_calls.get();
+        solution
+    }
+
+    fn set_up_parameters<P>(&mut self, x0: &Array1<f64>, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        let m = problem.num_residuals();
+        self.x_k = x0.clone();
+        self.r_k = Array::zeros(m);
+        self.j_k = Array::zeros((m, x0.len()));
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.residuals(&self.x_k, &mut self.r_k);
+        self.eval_jacobian(problem);
+    }
+
+    fn eval_jacobian<P>(&mut self, problem: &mut P)
+    where
+        P: LeastSquares,
+    {
+        self.f_jac_calls.set(self.f_jac_calls.get() + 1);
+        problem.jacobian(&self.x_k, &mut self.j_k);
+    }
+}
+
+
+impl Default for Newton {
+    fn default() -> Newton {
+        Newton::new()
+    }
+}
@@ -0,0 +1,580 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+//! Minimization of functions of one variable.
+//!
+//! The minimum is first bracketed with [`bracket`], which searches downhill
+//! from two initial points, and the bracket is then reduced with golden
+//! section search ([`GoldenSection`]) or Brent's method ([`Brent`]). Brent's
+//! method can also use the derivative of the function when it is written with
+//! `DualScalar` numbers (`Brent::minimize_dual`).
+//!
+//! # Example
+//! ``` rust
+//!use optimization::scalar::Brent;
+//!use optimization::number_system::DualScalar;
+//!
+//!// minimum at x = 2
+//!let sol = Brent::new().minimize(|x: f64| (x - 2.0).powi(2) + 1.0, 0.0, 1.0);
+//!assert!(sol.success);
+//!assert!((sol.x - 2.0).abs() < 1e-6);
+//!
+//!let sol = Brent::new().minimize_dual(|x: DualScalar| x.cos(), 2.0, 3.0);
+//!assert!((sol.x - std::f64::consts::PI).abs() < 1e-6);
+//! ```
+
+
+use crate::Status;
+use crate::number_system::DualScalar;
+
+
+/// Golden ratio.
+const GOLD: f64 = 1.618_033_988_749_895;
+
+/// Fraction of the interval used by the golden section steps (2 - GOLD).
+const CGOLD: f64 = 0.381_966_011_250_105_1;
+
+
+/// Three points `a`, `b` and `c` that bracket a minimum: `b` is between `a`
+/// and `c`, and `f(b)` is smaller than `f(a)` and `f(c)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bracket {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub fa: f64,
+    pub fb: f64,
+    pub fc: f64,
+}
+
+
+/// Result of a scalar minimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarSolution {
+    /// Position of the minimum.
+    pub x: f64,
+
+    /// Function value at `x`.
+    pub f: f64,
+
+    /// Whether the minimum was found with the requested tolerance.
+    pub success: bool,
+
+    /// Reason why the solver stopped.
+    pub status: Status,
+
+    /// Number of iterations.
+    pub iter_num: u32,
+
+    /// Number of function evaluations (including the bracket search).
+    pub f_evals: u32,
+
+    /// Number of derivative evaluations.
+    pub f_grad_evals: u32,
+}
+
+
+/// Searches downhill from `a` and `b` until the minimum is bracketed. The
+/// steps grow with the golden ratio and parabolic extrapolation (Numerical
+/// Recipes, `mnbrak`). Returns `None` when no bracket is found (e.g. the
+/// function decreases forever or is not finite).
+pub fn bracket<F>(mut f: F, a: f64, b: f64) -> Option<Bracket>
+where
+    F: FnMut(f64) -> f64,
+{
+    const GROW_LIMIT: f64 = 110.0;
+    const I_MAX: u32 = 100;
+    let (mut a, mut b) = (a, b);
+    let mut fa = f(a);
+    let mut fb = f(b);
+    if fb > fa {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+    let mut c = b + GOLD*(b - a);
+    let mut fc = f(c);
+
+    let mut i: u32 = 0;
+    while fc < fb {
+        if i >= I_MAX {
+            return None
+        }
+        // minimum of the parabola through a, b and c
+        let r = (b - a)*(fb - fc);
+        let q = (b - c)*(fb - fa);
+        let denom = 2.0*(q - r).abs().max(1e-21).copysign(q - r);
+        let mut u = b - ((b - c)*q - (b - a)*r)/denom;
+        let u_lim = b + GROW_LIMIT*(c - b);
+        let mut fu;
+        if (b - u)*(u - c) > 0.0 {
+            fu = f(u);
+            if fu < fc {
+                return Some(Bracket{a: b, b: u, c, fa: fb, fb: fu, fc})
+            }
+            else if fu > fb {
+                return Some(Bracket{a, b, c: u, fa, fb, fc: fu})
+            }
+            u = c + GOLD*(c - b);
+            fu = f(u);
+        }
+        else if (c - u)*(u - u_lim) > 0.0 {
+            fu = f(u);
+            if fu < fc {
+                b = c;
+                fb = fc;
+                c = u;
+                fc = fu;
+                u = c + GOLD*(c - b);
+                fu = f(u);
+            }
+        }
+        else if (u - u_lim)*(u_lim - c) >= 0.0 {
+            u = u_lim;
+            fu = f(u);
+        }
+        else {
+            u = c + GOLD*(c - b);
+            fu = f(u);
+        }
+        a = b;
+        fa = fb;
+        b = c;
+        fb = fc;
+        c = u;
+        fc = fu;
+        i += 1;
+    }
+    if fa.is_nan() || fb.is_nan() || fc.is_nan() || fb > fa {
+        return None
+    }
+    Some(Bracket{a, b, c, fa, fb, fc})
+}
+
+
+/// Golden section search.
+///
+/// The bracket is reduced by a constant factor of 0.618 at each iteration.
+/// It is slower than Brent's method but it doesn't assume that the function
+/// is smooth.
+pub struct GoldenSection {
+    // max number of iterations
+    pub i_max: u32,
+
+    // relative tolerance of the position of the minimum
+    pub xtol: f64,
+}
+
+
+impl GoldenSection {
+    pub fn new() -> GoldenSection {
+        GoldenSection {
+            i_max: 500,
+            xtol: 1e-8,
+        }
+    }
+
+    /// Minimizes `f` starting with the bracket search from `a` and `b`.
+    pub fn minimize<F>(&self, mut f: F, a: f64, b: f64) -> ScalarSolution
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let mut f_evals = 0;
+        let bracket = bracket(|x| {f_evals += 1; f(x)}, a, b);
+        let mut sol = match bracket {
+            Some(bracket) => self.minimize_bracket(&mut f, &bracket),
+            None => return bracket_failure(&mut f, b, f_evals),
+        };
+        sol.f_evals += f_evals;
+        sol
+    }
+
+    /// Minimizes `f` inside a known bracket.
+    pub fn minimize_bracket<F>(&self, mut f: F, bracket: &Bracket) -> ScalarSolution
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let Bracket{a, b, c, fb, ..} = *bracket;
+        // x1 and x2 are the inner points, with x1 < x2 when a < c
+        let (mut x0, mut x3) = (a, c);
+        let (mut x1, mut x2, mut f1, mut f2);
+        let mut f_evals = 1;
+        if (c - b).abs() > (b - a).abs() {
+            x1 = b;
+            f1 = fb;
+            x2 = b + CGOLD*(c - b);
+            f2 = f(x2);
+        }
+        else {
+            x2 = b;
+            f2 = fb;
+            x1 = b - CGOLD*(b - a);
+            f1 = f(x1);
+        }
+
+        let mut status = Status::MaxIterations;
+        let mut i: u32 = 0;
+        while i < self.i_max {
+            if (x3 - x0).abs() <= self.xtol*(x1.abs() + x2.abs()) + 1e-10*self.xtol {
+                status = Status::XTolerance;
+                break;
+            }
+            if f2 < f1 {
+                x0 = x1;
+                x1 = x2;
+                x2 = x2 + CGOLD*(x3 - x2);
+                f1 = f2;
+                f2 = f(x2);
+            }
+            else {
+                x3 = x2;
+                x2 = x1;
+                x1 = x1 - CGOLD*(x1 - x0);
+                f2 = f1;
+                f1 = f(x1);
+            }
+            f_evals += 1;
+            i += 1;
+        }
+
+        let (x, f) = if f1 < f2 { (x1, f1) } else { (x2, f2) };
+        ScalarSolution {
+            x,
+            f,
+            success: status.is_success(),
+            status,
+            iter_num: i,
+            f_evals,
+            f_grad_evals: 0,
+        }
+    }
+}
+
+
+impl Default for GoldenSection {
+    fn default() -> GoldenSection {
+        GoldenSection::new()
+    }
+}
+
+
+/// Brent's method.
+///
+/// It combines parabolic interpolation with golden section steps, so it
+/// converges superlinearly for smooth functions while keeping the robustness
+/// of the golden section search (Numerical Recipes, `brent` and `dbrent`).
+pub struct Brent {
+    // max number of iterations
+    pub i_max: u32,
+
+    // relative tolerance of the position of the minimum. Values smaller than
+    // the square root of the machine epsilon are not useful.
+    pub xtol: f64,
+}
+
+
+impl Brent {
+    pub fn new() -> Brent {
+        Brent {
+            i_max: 500,
+            xtol: 1e-8,
+        }
+    }
+
+    /// Minimizes `f` starting with the bracket search from `a` and `b`.
+    pub fn minimize<F>(&self, mut f: F, a: f64, b: f64) -> ScalarSolution
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let mut f_evals = 0;
+        let bracket = bracket(|x| {f_evals += 1; f(x)}, a, b);
+        let mut sol = match bracket {
+            Some(bracket) => self.minimize_bracket(&mut f, &bracket),
+            None => return bracket_failure(&mut f, b, f_evals),
+        };
+        sol.f_evals += f_evals;
+        sol
+    }
+
+    /// Minimizes `f` inside a known bracket.
+    pub fn minimize_bracket<F>(&self, mut f: F, bracket: &Bracket) -> ScalarSolution
+    where
+        F: FnMut(f64) -> f64,
+    {
+        let mut a = bracket.a.min(bracket.c);
+        let mut b = bracket.a.max(bracket.c);
+        let (mut x, mut w, mut v) = (bracket.b, bracket.b, bracket.b);
+        let (mut fx, mut fw, mut fv) = (bracket.fb, bracket.fb, bracket.fb);
+        // d is the last step and e the step before it
+        let mut d: f64 = 0.0;
+        let mut e: f64 = 0.0;
+        let mut f_evals = 0;
+
+        let mut status = Status::MaxIterations;
+        let mut i: u32 = 0;
+        while i < self.i_max {
+            let xm = 0.5*(a + b);
+            let tol1 = self.xtol*x.abs() + 1e-10*self.xtol;
+            let tol2 = 2.0*tol1;
+            if (x - xm).abs() <= tol2 - 0.5*(b - a) {
+                status = Status::XTolerance;
+                break;
+            }
+            let mut golden = true;
+            if e.abs() > tol1 {
+                // parabolic step through x, w and v
+                let r = (x - w)*(fx - fv);
+                let mut q = (x - v)*(fx - fw);
+                let mut p = (x - v)*q - (x - w)*r;
+                q = 2.0*(q - r);
+                if q > 0.0 {
+                    p = -p;
+                }
+                q = q.abs();
+                // the parabolic step is only taken when it falls inside the
+                // bracket and it is smaller than half the step before last
+                if p.abs() < (0.5*q*e).abs() && p > q*(a - x) && p < q*(b - x) {
+                    e = d;
+                    d = p/q;
+                    let u = x + d;
+                    if u - a < tol2 || b - u < tol2 {
+                        d = tol1.copysign(xm - x);
+                    }
+                    golden = false;
+                }
+            }
+            if golden {
+                e = if x >= xm { a - x } else { b - x };
+                d = CGOLD*e;
+            }
+            let u = if d.abs() >= tol1 { x + d } else { x + tol1.copysign(d) };
+            let fu = f(u);
+            f_evals += 1;
+            if fu <= fx {
+                if u >= x {
+                    a = x;
+                }
+                else {
+                    b = x;
+                }
+                v = w;
+                fv = fw;
+                w = x;
+                fw = fx;
+                x = u;
+                fx = fu;
+            }
+            else {
+                if u < x {
+                    a = u;
+                }
+                else {
+                    b = u;
+                }
+                if fu <= fw || w == x {
+                    v = w;
+                    fv = fw;
+                    w = u;
+                    fw = fu;
+                }
+                else if fu <= fv || v == x || v == w {
+                    v = u;
+                    fv = fu;
+                }
+            }
+            i += 1;
+        }
+
+        ScalarSolution {
+            x,
+            f: fx,
+            success: status.is_success(),
+            status,
+            iter_num: i,
+            f_evals,
+            f_grad_evals: 0,
+        }
+    }
+
+    /// Minimizes a function written with dual numbers, starting with the
+    /// bracket search from `a` and `b`. The derivative is used to choose the
+    /// side of the bracket where the minimum is and to interpolate with the
+    /// secant of the derivative instead of a parabola.
+    pub fn minimize_dual<F>(&self, mut f: F, a: f64, b: f64) -> ScalarSolution
+    where
+        F: FnMut(DualScalar) -> DualScalar,
+    {
+        let mut f_evals = 0;
+        let bracket = bracket(|x| {f_evals += 1; f(DualScalar{re: x, du: 0.0}).re}, a, b);
+        let mut sol = match bracket {
+            Some(bracket) => self.minimize_dual_bracket(&mut f, &bracket),
+            None => return bracket_failure(|x| f(DualScalar{re: x, du: 0.0}).re, b, f_evals),
+        };
+        sol.f_evals += f_evals;
+        sol
+    }
+
+    /// Minimizes a function written with dual numbers inside a known bracket.
+    pub fn minimize_dual_bracket<F>(&self, mut f: F, bracket: &Bracket) -> ScalarSolution
+    where
+        F: FnMut(DualScalar) -> DualScalar,
+    {
+        let mut eval = |x: f64| f(DualScalar{re: x, du: 1.0});
+        let mut a = bracket.a.min(bracket.c);
+        let mut b = bracket.a.max(bracket.c);
+        let (mut x, mut w, mut v) = (bracket.b, bracket.b, bracket.b);
+        let phi = eval(x);
+        let (mut fx, mut fw, mut fv) = (phi.re, phi.re, phi.re);
+        let (mut dx, mut dw, mut dv) = (phi.du, phi.du, phi.du);
+        let mut d: f64 = 0.0;
+        let mut e: f64 = 0.0;
+        let mut f_evals = 1;
+
+        let mut status = Status::MaxIterations;
+        let mut i: u32 = 0;
+        while i < self.i_max {
+            let xm = 0.5*(a + b);
+            let tol1 = self.xtol*x.abs() + 1e-10*self.xtol;
+            let tol2 = 2.0*tol1;
+            if (x - xm).abs() <= tol2 - 0.5*(b - a) {
+                status = Status::XTolerance;
+                break;
+            }
+            let mut bisection = true;
+            if e.abs() > tol1 {
+                // secant steps of the derivative through (x, w) and (x, v)
+                let mut d1 = 2.0*(b - a);
+                let mut d2 = d1;
+                if dw != dx {
+                    d1 = (w - x)*dx/(dx - dw);
+                }
+                if dv != dx {
+                    d2 = (v - x)*dx/(dx - dv);
+                }
+                // the steps must be inside the bracket and go downhill
+                let ok1 = (a - x - d1)*(x + d1 - b) > 0.0 && dx*d1 <= 0.0;
+                let ok2 = (a - x - d2)*(x + d2 - b) > 0.0 && dx*d2 <= 0.0;
+                let e_old = e;
+                e = d;
+                if ok1 || ok2 {
+                    let step = if ok1 && ok2 {
+                        if d1.abs() < d2.abs() { d1 } else { d2 }
+                    }
+                    else if ok1 {
+                        d1
+                    }
+                    else {
+                        d2
+                    };
+                    if step.abs() <= (0.5*e_old).abs() {
+                        d = step;
+                        let u = x + d;
+                        if u - a < tol2 || b - u < tol2 {
+                            d = tol1.copysign(xm - x);
+                        }
+                        bisection = false;
+                    }
+                }
+            }
+            if bisection {
+                // the sign of the derivative tells which half has the minimum
+                e = if dx >= 0.0 { a - x } else { b - x };
+                d = 0.5*e;
+            }
+            let u;
+            let phi_u;
+            if d.abs() >= tol1 {
+                u = x + d;
+                phi_u = eval(u);
+                f_evals += 1;
+            }
+            else {
+                u = x + tol1.copysign(d);
+                phi_u = eval(u);
+                f_evals += 1;
+                // the minimum step goes uphill, x is the minimum
+                if phi_u.re > fx {
+                    status = Status::XTolerance;
+                    break;
+                }
+            }
+            let (fu, du) = (phi_u.re, phi_u.du);
+            if fu <= fx {
+                if u >= x {
+                    a = x;
+                }
+                else {
+                    b = x;
+                }
+                v = w;
+                fv = fw;
+                dv = dw;
+                w = x;
+                fw = fx;
+                dw = dx;
+                x = u;
+                fx = fu;
+                dx = du;
+            }
+            else {
+                if u < x {
+                    a = u;
+                }
+                else {
+                    b = u;
+                }
+                if fu <= fw || w == x {
+                    v = w;
+                    fv = fw;
+                    dv = dw;
+                    w = u;
+                    fw = fu;
+                    dw = du;
+                }
+                else if fu < fv || v == x || v == w {
+                    v = u;
+                    fv = fu;
+                    dv = du;
+                }
+            }
+            i += 1;
+        }
+
+        ScalarSolution {
+            x,
+            f: fx,
+            success: status.is_success(),
+            status,
+            iter_num: i,
+            f_evals,
+            f_grad_evals: f_evals,
+        }
+    }
+}
+
+
+impl Default for Brent {
+    fn default() -> Brent {
+        Brent::new()
+    }
+}
+
+
+// Solution returned when the minimum couldn't be bracketed
+fn bracket_failure<F>(mut f: F, x: f64, f_evals: u32) -> ScalarSolution
+where
+    F: FnMut(f64) -> f64,
+{
+    let fx = f(x);
+    let status = if fx.is_finite() { Status::MaxIterations } else { Status::NonFinite };
+    ScalarSolution {
+        x,
+        f: fx,
+        success: false,
+        status,
+        iter_num: 0,
+        f_evals: f_evals + 1,
+        f_grad_evals: 0,
+    }
+}
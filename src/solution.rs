@@ -3,25 +3,161 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 
 
-use ndarray::Array1;
+//! Result of a minimization.
+//!
+//! Every solver returns a [`Solution`] which contains the final position, the
+//! reason why the solver stopped ([`Status`]) and some statistics about the
+//! work done by the solver.
 
 
+use ndarray::{Array1, Array2, Array};
+use crate::line_search::LineSearchError;
+
+
+/// Result returned by the minimization algorithms.
+#[derive(Debug, Clone)]
 pub struct Solution {
-    // solution of the optimization
+    /// Solution of the optimization (last position of the solver).
     pub x: Array1<f64>,
 
-    // whether the solver found a solution or not
+    /// Whether the solver found a solution or not. This is `true` only when
+    /// `status` is a successful termination.
     pub success: bool,
 
-    // total number of iterations
+    /// Reason why the solver stopped.
+    pub status: Status,
+
+    /// Total number of iterations.
     pub iter_num: u32,
 
-    // number of function evaluations
+    /// Number of function evaluations.
     pub f_evals: u32,
 
-    // number of gradient evaluations
+    /// Number of gradient evaluations.
     pub f_grad_evals: u32,
 
-    // number of hessian evaluations
+    /// Number of hessian evaluations.
     pub f_hess_evals: u32,
+
+    /// Objective function value at `x`.
+    pub f: f64,
+
+    /// Norm of the gradient at `x` (norm of the projected gradient for bound
+    /// constrained solvers). It is NaN for derivative-free solvers.
+    pub grad_norm: f64,
+
+    /// Trust region radius at the end of the minimization. It is only filled
+    /// by trust region solvers.
+    pub trust_radius: Option<f64>,
+
+    /// State of the bounds of each variable at the solution. It is only filled
+    /// by bound constrained solvers.
+    pub active_bounds: Vec<ActiveBound>,
+
+    /// Residuals at `x`. It is only filled by least-squares solvers.
+    pub residuals: Option<Array1<f64>>,
+
+    /// Jacobian of the residuals at `x`. It is only filled by least-squares
+    /// solvers.
+    pub jacobian: Option<Array2<f64>>,
+
+    /// Lagrange multipliers of the equality constraints. It is only filled by
+    /// constrained solvers.
+    pub equality_multipliers: Option<Array1<f64>>,
+
+    /// Lagrange multipliers of the inequality constraints (they are zero for
+    /// the inactive constraints). It is only filled by constrained solvers.
+    pub inequality_multipliers: Option<Array1<f64>>,
+
+    /// Max violation of the constraints at `x`. It is only filled by
+    /// constrained solvers.
+    pub constraint_violation: Option<f64>,
+
+    /// Max residual of the first order optimality (KKT) conditions at `x`:
+    /// gradient of the lagrangian, constraint violation and complementarity.
+    /// It is only filled by constrained solvers that calculate it.
+    pub kkt_residual: Option<f64>,
+}
+
+
+impl Solution {
+    // Creates an empty solution for a problem with n variables. The status is
+    // initialized to `MaxIterations` so that solvers only need to change it
+    // when they stop before reaching the iteration limit.
+    pub(crate) fn new(n: usize) -> Solution {
+        Solution {
+            x: Array::zeros(n),
+            success: false,
+            status: Status::MaxIterations,
+            iter_num: 0,
+            f_evals: 0,
+            f_grad_evals: 0,
+            f_hess_evals: 0,
+            f: f64::NAN,
+            grad_norm: f64::NAN,
+            trust_radius: None,
+            active_bounds: Vec::new(),
+            residuals: None,
+            jacobian: None,
+            equality_multipliers: None,
+            inequality_multipliers: None,
+            constraint_violation: None,
+            kkt_residual: None,
+        }
+    }
+}
+
+
+/// Termination reason of a solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    /// The norm of the gradient is smaller than the gradient tolerance.
+    GradientTolerance,
+    /// The change of the position (or the size of the simplex) is smaller than
+    /// the position tolerance.
+    XTolerance,
+    /// The change of the objective function value is smaller than the function
+    /// tolerance.
+    FTolerance,
+    /// The norm of the residuals of a system of equations is smaller than the
+    /// residual tolerance.
+    ResidualTolerance,
+    /// The maximum number of iterations was reached.
+    MaxIterations,
+    /// The line search could not find a step length that decreases the
+    /// objective function (even along the steepest descent direction). It
+    /// contains the reason of the last failure.
+    LineSearchFailure(LineSearchError),
+    /// The objective function or its derivatives became NaN or infinite.
+    NonFinite,
+    /// The trust region radius became too small to make progress (or the
+    /// damping parameter too large for damped least-squares methods).
+    TrustRadiusCollapsed,
+    /// The constraints could not be satisfied (e.g. the penalty parameter of a
+    /// constrained solver reached its limit).
+    Infeasible,
+    /// The minimization was stopped by the user.
+    UserAbort,
+}
+
+
+impl Status {
+    /// Returns `true` when the status corresponds to a converged solution.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Status::GradientTolerance | Status::XTolerance | Status::FTolerance
+                 | Status::ResidualTolerance)
+    }
+}
+
+
+/// State of the bounds of a variable at the solution of a bound constrained
+/// problem.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActiveBound {
+    /// The variable is strictly inside its bounds.
+    Free,
+    /// The variable is at its lower bound.
+    Lower,
+    /// The variable is at its upper bound.
+    Upper,
 }
@@ -0,0 +1,251 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::LineSearchError;
+use crate::problem::{Objective, Gradient};
+use crate::projection::Projection;
+
+
+/// Spectral projected gradient (SPG) method.
+///
+/// Minimizes a function over a set given through the
+/// [`Projection`](crate::projection::Projection) trait. Each iteration moves
+/// along the projected gradient direction `d = P(x - λ*grad(f)) - x`, where
+/// the spectral step `λ = s^T*s/s^T*y` is the Barzilai–Borwein scaling of the
+/// last step. The step length along `d` is found with the nonmonotone
+/// backtracking line search of Grippo, Lampariello and Lucidi, which only
+/// asks for a decrease with respect to the largest of the last `m` function
+/// values (the algorithm SPG2 of Birgin, Martínez and Raydan). With `m = 1`
+/// the line search is monotone and the method is a projected gradient method.
+///
+/// The method stops when the projected gradient `P(x - grad(f)) - x` is
+/// within the tolerance, its norm is reported as `grad_norm` in the solution.
+///
+/// # Example
+/// ``` rust
+///use optimization::SPG;
+///use optimization::projection::Ball;
+///use optimization::problem::FnProblem;
+///use optimization::number_system::DualScalar;
+///use ndarray::arr1;
+///
+///// point of the unit disk closest to (3, 4)
+///let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+///    (x[0] - 3.0).powi(2) + (x[1] - 4.0).powi(2)
+///});
+///let mut spg = SPG::new(Ball::new(arr1(&[0.0, 0.0]), 1.0));
+///let sol = spg.minimize(&arr1(&[0.0, 0.0]), &mut problem);
+///assert!(sol.success);
+///assert!((sol.x[0] - 0.6).abs() < 1e-6);
+///assert!((sol.x[1] - 0.8).abs() < 1e-6);
+/// ```
+pub struct SPG<S> {
+    // max number of iterations
+    pub i_max: u32,
+
+    // projected gradient tolerance
+    pub gtol: f64,
+
+    // number of function values used by the nonmonotone line search
+    pub m: usize,
+
+    // sufficient decrease parameter of the line search
+    pub gamma: f64,
+
+    // bounds of the spectral step
+    pub lambda_min: f64,
+    pub lambda_max: f64,
+
+    // max number of function evaluations of each line search
+    pub ls_max: u32,
+
+    // projection onto the feasible set
+    pub projection: S,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>,
+}
+
+
+impl<S: Projection> SPG<S> {
+    pub fn new(projection: S) -> SPG<S> {
+        SPG {
+            i_max: 1000,
+            gtol: 1e-6,
+            m: 10,
+            gamma: 1e-4,
+            lambda_min: 1e-10,
+            lambda_max: 1e10,
+            ls_max: 40,
+            projection,
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P>(&mut self, x0: &Array1<f64>, problem: &mut P) -> Solution
+    where
+        P: Objective + Gradient,
+    {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        let diff_evals = problem.f_evals();
+        let n = x0.len();
+
+        let mut x_k = x0.clone();
+        self.projection.project(&mut x_k);
+        let mut grad_k: Array1<f64> = Array::zeros(n);
+        let mut f_k = self.eval_func(&x_k, &mut grad_k, problem);
+        let mut pg_k = self.projected_gradient(&x_k, &grad_k);
+
+        // last function values for the nonmonotone line search
+        let mut f_history = VecDeque::new();
+        f_history.push_back(f_k);
+
+        let pg_max = pg_k.iter().fold(0.0, |acc: f64, d| acc.max(d.abs()));
+        let mut lambda = if pg_max > 0.0 { 1.0/pg_max } else { 1.0 };
+        lambda = lambda.max(self.lambda_min).min(self.lambda_max);
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !f_k.is_finite() || !grad_k.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if pg_k.dot(&pg_k).sqrt() < self.gtol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            let mut d_k = &x_k - &(lambda*&grad_k);
+            self.projection.project(&mut d_k);
+            d_k -= &x_k;
+            let dphi_0 = grad_k.dot(&d_k);
+            let f_max = f_history.iter().fold(f64::NEG_INFINITY, |acc: f64, f| acc.max(*f));
+
+            // backtracking with safeguarded quadratic interpolation
+            let mut alpha = 1.0;
+            let mut f_trial = f64::NAN;
+            let mut accepted = false;
+            for _ in 0..self.ls_max {
+                f_trial = self.eval_real(&(&x_k + &(alpha*&d_k)), problem);
+                if f_trial <= f_max + self.gamma*alpha*dphi_0 {
+                    accepted = true;
+                    break;
+                }
+                if !f_trial.is_finite() {
+                    alpha *= 0.1;
+                    continue;
+                }
+                let alpha_q = -0.5*alpha*alpha*dphi_0/(f_trial - f_k - alpha*dphi_0);
+                alpha = if alpha_q >= 0.1*alpha && alpha_q <= 0.9*alpha { alpha_q } else { 0.5*alpha };
+            }
+            if !accepted {
+                solution.status = Status::LineSearchFailure(LineSearchError::MaxIterations);
+                break;
+            }
+
+            // the problem is already at the accepted step, only the gradient
+            // is missing
+            let s_k = alpha*&d_k;
+            let mut grad_new = Array::zeros(n);
+            self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+            problem.grad(&mut grad_new);
+            let y_k = &grad_new - &grad_k;
+            x_k += &s_k;
+            f_k = f_trial;
+            grad_k = grad_new;
+            pg_k = self.projected_gradient(&x_k, &grad_k);
+
+            f_history.push_back(f_k);
+            if f_history.len() > self.m.max(1) {
+                f_history.pop_front();
+            }
+
+            // Barzilai–Borwein step, the largest step is used when the
+            // curvature along s_k is not positive
+            let sy = s_k.dot(&y_k);
+            lambda = if sy > 0.0 {
+                (s_k.dot(&s_k)/sy).max(self.lambda_min).min(self.lambda_max)
+            }
+            else {
+                self.lambda_max
+            };
+
+            let iteration = Iteration {
+                k,
+                x_k: &x_k,
+                f_k,
+                grad_norm: pg_k.dot(&pg_k).sqrt(),
+                step_length: alpha,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        solution.x.assign(&x_k);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = f_k;
+        solution.grad_norm = pg_k.dot(&pg_k).sqrt();
+        solution.f_evals = self.f_calls.get() + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get();
+        solution
+    }
+
+    // P(x - grad) - x
+    fn projected_gradient(&self, x: &Array1<f64>, grad: &Array1<f64>) -> Array1<f64> {
+        let mut pg = x - grad;
+        self.projection.project(&mut pg);
+        pg - x
+    }
+
+    fn eval_func<P>(&self, x: &Array1<f64>, grad: &mut Array1<f64>, problem: &mut P) -> f64
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(x);
+        let f = problem.eval_real();
+        problem.grad(grad);
+        f
+    }
+
+    fn eval_real<P>(&self, x: &Array1<f64>, problem: &mut P) -> f64
+    where
+        P: Objective + Gradient,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.update_x(x);
+        problem.eval_real()
+    }
+}
@@ -0,0 +1,413 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2, Array, s};
+use std::cell::Cell;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::LineSearchError;
+use crate::problem::{Objective, Gradient, Constraints};
+use crate::qp::{QuadraticProgram, ActiveSet, QPStatus};
+use crate::linalg;
+
+
+/// Sequential quadratic programming (SQP) method.
+///
+/// Each iteration solves the quadratic program
+///
+/// `min 1/2*p^T*B*p + grad(f)^T*p  s.t.  c + J_c*p = 0,  g + J_g*p <= 0`
+///
+/// with the active-set method of the [`qp`](crate::qp) module, warm started
+/// with the active constraints of the previous iteration. `B` is a damped
+/// BFGS approximation of the hessian of the lagrangian `f + λ·c + ν·g`
+/// (Powell's damping keeps it positive definite even when the curvature of
+/// the lagrangian is negative).
+/// The step is then shortened with a backtracking line search on the L1
+/// merit function `f + ρ(|c|_1 + |max(0, g)|_1)`, where the penalty `ρ` is
+/// kept larger than the multipliers of the quadratic program.
+///
+/// When the linearized constraints are inconsistent (e.g. the jacobian is
+/// singular at the current point) the step is found with the elastic program
+///
+/// `min 1/2*p^T*B*p + grad(f)^T*p + ρ_e(|c + J_c*p|_1 + |max(0, g + J_g*p)|_1)`
+///
+/// which reduces the violation as much as the linearization allows. The
+/// elastic penalty `ρ_e` grows when the elastic step vanishes, and the status
+/// is `Infeasible` when the step still vanishes at `elastic_max`: the
+/// violation can't be reduced from that point.
+///
+/// The method stops when the gradient of the lagrangian, the constraint
+/// violation and the complementarity `|ν_i*g_i|` are within the tolerances.
+/// The solution contains the multipliers and the KKT residual.
+///
+/// This method is meant for small problems with few variables and many
+/// active constraints: the quadratic programs and the hessian approximation
+/// are dense.
+///
+/// # Example
+/// ``` rust
+///use optimization::SQP;
+///use optimization::problem::{FnProblem, FnConstraints};
+///use optimization::number_system::DualScalar;
+///use ndarray::arr1;
+///
+///// point of the unit circle closest to (2, 1)
+///let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+///    (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+///});
+///let mut constraints = FnConstraints::new(
+///    2,
+///    |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+///    |_: &[DualScalar]| vec![],
+///);
+///let sol = SQP::new().minimize(&arr1(&[1.0, 0.0]), &mut problem, &mut constraints);
+///assert!(sol.success);
+///assert!((sol.x[0] - 2.0/5f64.sqrt()).abs() < 1e-6);
+/// ```
+pub struct SQP {
+    // max number of iterations
+    pub i_max: u32,
+
+    // tolerance of the gradient of the lagrangian
+    pub gtol: f64,
+
+    // constraint tolerance (violation and complementarity)
+    pub ctol: f64,
+
+    // sufficient decrease parameter of the merit line search
+    pub c1: f64,
+
+    // max number of merit function evaluations of each line search
+    pub ls_max: u32,
+
+    // max penalty of the elastic programs
+    pub elastic_max: f64,
+
+    // solver of the quadratic subproblems
+    pub qp: ActiveSet,
+
+    // holder for function calls
+    f_calls: Cell<u32>,
+
+    // holder for gradient calls
+    f_grad_calls: Cell<u32>,
+}
+
+
+// Values of the problem at a position
+struct Point {
+    x: Array1<f64>,
+    f: f64,
+    grad: Array1<f64>,
+    c: Array1<f64>,
+    jac_c: Array2<f64>,
+    g: Array1<f64>,
+    jac_g: Array2<f64>,
+}
+
+
+impl Point {
+    // L1 norm of the constraint violation
+    fn infeasibility(&self) -> f64 {
+        self.c.iter().map(|c| c.abs()).sum::<f64>() + self.g.iter().map(|g| g.max(0.0)).sum::<f64>()
+    }
+
+    // Max constraint violation
+    fn violation(&self) -> f64 {
+        let eq = self.c.iter().fold(0.0, |acc: f64, c| acc.max(c.abs()));
+        self.g.iter().fold(eq, |acc, g| acc.max(*g))
+    }
+
+    // L1 norm of the violation of the constraints linearized along p
+    fn linearized_infeasibility(&self, p: &Array1<f64>) -> f64 {
+        let c = &self.c + &self.jac_c.dot(p);
+        let g = &self.g + &self.jac_g.dot(p);
+        c.iter().map(|c| c.abs()).sum::<f64>() + g.iter().map(|g| g.max(0.0)).sum::<f64>()
+    }
+
+    fn lagrangian_grad(&self, lambda: &Array1<f64>, nu: &Array1<f64>) -> Array1<f64> {
+        &self.grad + &self.jac_c.t().dot(lambda) + self.jac_g.t().dot(nu)
+    }
+}
+
+
+impl SQP {
+    pub fn new() -> SQP {
+        SQP {
+            i_max: 100,
+            gtol: 1e-6,
+            ctol: 1e-8,
+            c1: 1e-4,
+            ls_max: 40,
+            elastic_max: 1e8,
+            qp: ActiveSet::new(),
+            f_calls: Cell::new(0),
+            f_grad_calls: Cell::new(0),
+        }
+    }
+
+    pub fn minimize<P, C>(&mut self, x0: &Array1<f64>, problem: &mut P, constraints: &mut C) -> Solution
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+    {
+        self.minimize_with_observer(x0, problem, constraints, &mut NoObserver)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn minimize_with_observer<P, C, O>(&mut self,
+                                           x0: &Array1<f64>,
+                                           problem: &mut P,
+                                           constraints: &mut C,
+                                           observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        let diff_evals = problem.f_evals();
+        let n = x0.len();
+
+        let mut point = self.eval_point(x0, problem, constraints);
+        let mut lambda: Array1<f64> = Array::zeros(constraints.num_equality());
+        let mut nu: Array1<f64> = Array::zeros(constraints.num_inequality());
+        let mut B: Array2<f64> = Array::eye(n);
+        let mut rho = 0.0;
+        let mut grad_L = point.lagrangian_grad(&lambda, &nu);
+        // active constraints of the last subproblem
+        let mut working_set = Vec::new();
+        let mut elastic_penalty = 0.0;
+
+        let mut solution = Solution::new(n);
+
+        let mut k: u32 = 1;
+        while k < self.i_max {
+            if !point.f.is_finite() || !grad_L.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
+            if grad_L.dot(&grad_L).sqrt() <= self.gtol && point.violation() <= self.ctol
+                && complementarity(&point.g, &nu) <= self.ctol {
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+
+            let program = QuadraticProgram::new(B.clone(), point.grad.clone())
+                .with_equality(point.jac_c.clone(), -&point.c)
+                .with_inequality(point.jac_g.clone(), -&point.g);
+            let mut qp = self.qp.solve_from(&program, &Array::zeros(n), &working_set);
+            working_set = qp.working_set.clone();
+            if qp.status == QPStatus::Infeasible {
+                working_set.clear();
+                let grad_max = point.grad.iter().fold(1.0, |acc: f64, g| acc.max(g.abs()));
+                elastic_penalty = f64::max(elastic_penalty, 10.0*grad_max).min(self.elastic_max);
+                loop {
+                    qp = self.qp.solve(&elastic_program(&B, &point, elastic_penalty));
+                    qp.x = qp.x.slice(s![..n]).to_owned();
+                    qp.inequality_multipliers = qp.inequality_multipliers.slice(s![..point.g.len()]).to_owned();
+                    if qp.status != QPStatus::Optimal || qp.x.dot(&qp.x).sqrt() > self.gtol
+                        || elastic_penalty >= self.elastic_max {
+                        break;
+                    }
+                    elastic_penalty = (10.0*elastic_penalty).min(self.elastic_max);
+                }
+                if qp.status == QPStatus::Optimal && qp.x.dot(&qp.x).sqrt() <= self.gtol {
+                    // stationary point of the violation
+                    solution.status = Status::Infeasible;
+                    break;
+                }
+            }
+            let fallback = qp.status != QPStatus::Optimal;
+            let (p, y_eq, y_ineq) = if fallback {
+                // the subproblem failed, use the quasi-newton step of the
+                // lagrangian and keep the multipliers
+                let p = linalg::solve(&B, &(-&grad_L)).unwrap_or_else(|| -&grad_L);
+                (p, lambda.clone(), nu.clone())
+            }
+            else {
+                (qp.x, qp.equality_multipliers, qp.inequality_multipliers)
+            };
+
+            // the merit function decreases along p when the penalty is larger
+            // than the multipliers
+            let max_multiplier = y_eq.iter().chain(y_ineq.iter())
+                .fold(0.0, |acc: f64, y| acc.max(y.abs()));
+            if rho < 1.1*max_multiplier {
+                rho = 2.0*max_multiplier;
+            }
+            let merit_0 = point.f + rho*point.infeasibility();
+            let dmerit_0 = point.grad.dot(&p) + rho*(point.linearized_infeasibility(&p) - point.infeasibility());
+            if fallback && dmerit_0 >= 0.0 {
+                solution.status = Status::LineSearchFailure(LineSearchError::NotDescentDirection);
+                break;
+            }
+
+            let mut alpha = 1.0;
+            let mut accepted = false;
+            for _ in 0..self.ls_max {
+                let x_trial = &point.x + &(alpha*&p);
+                let merit = self.eval_merit(&x_trial, rho, problem, constraints);
+                if merit <= merit_0 + self.c1*alpha*dmerit_0.min(0.0) {
+                    accepted = true;
+                    break;
+                }
+                alpha *= if merit.is_finite() { 0.5 } else { 0.1 };
+            }
+            if !accepted {
+                solution.status = Status::LineSearchFailure(LineSearchError::MaxIterations);
+                break;
+            }
+
+            let new_point = self.eval_point(&(&point.x + &(alpha*&p)), problem, constraints);
+            lambda = &lambda + &(alpha*&(&y_eq - &lambda));
+            nu = &nu + &(alpha*&(&y_ineq - &nu));
+
+            // damped BFGS update of the hessian of the lagrangian
+            let s_k = &new_point.x - &point.x;
+            grad_L = new_point.lagrangian_grad(&lambda, &nu);
+            let y_k = &grad_L - &point.lagrangian_grad(&lambda, &nu);
+            let Bs = B.dot(&s_k);
+            let sBs = s_k.dot(&Bs);
+            let sy = s_k.dot(&y_k);
+            if sBs > 0.0 {
+                let theta = if sy >= 0.2*sBs { 1.0 } else { 0.8*sBs/(sBs - sy) };
+                let r_k = theta*&y_k + (1.0 - theta)*&Bs;
+                let sr = s_k.dot(&r_k);
+                for i in 0..n {
+                    for j in 0..n {
+                        B[[i, j]] += -Bs[i]*Bs[j]/sBs + r_k[i]*r_k[j]/sr;
+                    }
+                }
+            }
+            point = new_point;
+
+            let iteration = Iteration {
+                k,
+                x_k: &point.x,
+                f_k: point.f,
+                grad_norm: grad_L.dot(&grad_L).sqrt(),
+                step_length: alpha,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
+            k += 1;
+        }
+
+        let grad_norm = grad_L.dot(&grad_L).sqrt();
+        let max_grad = grad_L.iter().fold(0.0, |acc: f64, g| acc.max(g.abs()));
+        solution.x.assign(&point.x);
+        solution.success = solution.status.is_success();
+        solution.iter_num = k;
+        solution.f = point.f;
+        solution.grad_norm = grad_norm;
+        solution.f_evals = self.f_calls.get() + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get();
+        solution.constraint_violation = Some(point.violation());
+        solution.kkt_residual = Some(max_grad.max(point.violation()).max(complementarity(&point.g, &nu)));
+        solution.equality_multipliers = Some(lambda);
+        solution.inequality_multipliers = Some(nu);
+        solution
+    }
+
+    fn eval_point<P, C>(&self, x: &Array1<f64>, problem: &mut P, constraints: &mut C) -> Point
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+    {
+        let n = x.len();
+        let m_eq = constraints.num_equality();
+        let m_ineq = constraints.num_inequality();
+        self.f_calls.set(self.f_calls.get() + 1);
+        self.f_grad_calls.set(self.f_grad_calls.get() + 1);
+        problem.update_x(x);
+        let mut point = Point {
+            x: x.clone(),
+            f: problem.eval_real(),
+            grad: Array::zeros(n),
+            c: Array::zeros(m_eq),
+            jac_c: Array::zeros((m_eq, n)),
+            g: Array::zeros(m_ineq),
+            jac_g: Array::zeros((m_ineq, n)),
+        };
+        problem.grad(&mut point.grad);
+        constraints.equality(x, &mut point.c);
+        constraints.equality_jacobian(x, &mut point.jac_c);
+        constraints.inequality(x, &mut point.g);
+        constraints.inequality_jacobian(x, &mut point.jac_g);
+        point
+    }
+
+    fn eval_merit<P, C>(&self, x: &Array1<f64>, rho: f64, problem: &mut P, constraints: &mut C) -> f64
+    where
+        P: Objective + Gradient,
+        C: Constraints,
+    {
+        self.f_calls.set(self.f_calls.get() + 1);
+        problem.update_x(x);
+        let f = problem.eval_real();
+        let mut c = Array::zeros(constraints.num_equality());
+        let mut g = Array::zeros(constraints.num_inequality());
+        constraints.equality(x, &mut c);
+        constraints.inequality(x, &mut g);
+        f + rho*(c.iter().map(|c: &f64| c.abs()).sum::<f64>() + g.iter().map(|g: &f64| g.max(0.0)).sum::<f64>())
+    }
+}
+
+
+impl Default for SQP {
+    fn default() -> SQP {
+        SQP::new()
+    }
+}
+
+
+// Elastic version of the quadratic subproblem in the variables (p, u, v, w):
+// the violations c + J_c*p = u - v and g + J_g*p <= w are penalized with
+// penalty*(sum(u + v) + sum(w)), u, v, w >= 0. It is always feasible.
+#[allow(non_snake_case)]
+fn elastic_program(B: &Array2<f64>, point: &Point, penalty: f64) -> QuadraticProgram {
+    let n = point.x.len();
+    let m_eq = point.c.len();
+    let m_ineq = point.g.len();
+    let n_e = n + 2*m_eq + m_ineq;
+
+    let mut q: Array2<f64> = Array::zeros((n_e, n_e));
+    q.slice_mut(s![..n, ..n]).assign(B);
+    let mut c: Array1<f64> = Array::from_elem(n_e, penalty);
+    c.slice_mut(s![..n]).assign(&point.grad);
+
+    let mut a_eq: Array2<f64> = Array::zeros((m_eq, n_e));
+    a_eq.slice_mut(s![.., ..n]).assign(&point.jac_c);
+    let mut a_ineq: Array2<f64> = Array::zeros((m_ineq + n_e - n, n_e));
+    a_ineq.slice_mut(s![..m_ineq, ..n]).assign(&point.jac_g);
+    let mut b_ineq: Array1<f64> = Array::zeros(m_ineq + n_e - n);
+    b_ineq.slice_mut(s![..m_ineq]).assign(&(-&point.g));
+    for i in 0..m_eq {
+        a_eq[[i, n + i]] = -1.0;
+        a_eq[[i, n + m_eq + i]] = 1.0;
+    }
+    for i in 0..m_ineq {
+        a_ineq[[i, n + 2*m_eq + i]] = -1.0;
+    }
+    // nonnegative elastic variables
+    for j in 0..n_e - n {
+        a_ineq[[m_ineq + j, n + j]] = -1.0;
+    }
+    QuadraticProgram::new(q, c)
+        .with_equality(a_eq, -&point.c)
+        .with_inequality(a_ineq, b_ineq)
+}
+
+
+// Max violation of the complementarity condition ν_i*g_i = 0
+fn complementarity(g: &Array1<f64>, nu: &Array1<f64>) -> f64 {
+    g.iter().zip(nu.iter()).fold(0.0, |acc: f64, (g, nu)| acc.max((g*nu).abs()))
+}
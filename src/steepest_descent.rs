@@ -5,20 +5,23 @@
 
 use ndarray::{Array1, Array};
 use crate::number_system::Dual;
-use crate::Solution;
-use crate::LineSearch;
-use crate::StepValues;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::line_search::{LineSearch, StrongWolfe, StepValues};
 use std::cell::Cell;
 use crate::problem::{Objective, Gradient};
 
 
-pub struct SteepestDescent {
+pub struct SteepestDescent<L = StrongWolfe> {
     // max number of iterations
     pub i_max: u32,
 
     // gradient tolerance
     pub gtol: f64,
 
+    // line search used to find the step length
+    pub line_search: L,
+
     // current step direction
     p_k: Array1<f64>,
 
@@ -41,7 +44,20 @@ pub struct SteepestDescent {
 
 impl SteepestDescent {
     pub fn new() -> SteepestDescent {
+        // the gradient directions have no curvature information, so a nearly
+        // exact step is worth the extra evaluations
+        let mut line_search = StrongWolfe::new();
+        line_search.c2 = 0.1;
+        SteepestDescent::with_line_search(line_search)
+    }
+}
+
+
+impl<L: LineSearch> SteepestDescent<L> {
+    /// Creates the solver with the given line search.
+    pub fn with_line_search(line_search: L) -> SteepestDescent<L> {
         SteepestDescent {
+            line_search,
             i_max: 1000,
             gtol: 1e-6,
             p_k: Array1::zeros(1),
@@ -57,34 +73,51 @@ impl SteepestDescent {
     where
         P: Objective + Gradient,
     {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        let line_search_evals = (self.line_search.f_evals(), self.line_search.f_grad_evals());
+        let diff_evals = problem.f_evals();
         // start the procedure by setting up the system parameters
         self.set_up_parameters(x0, problem);
-        let mut line_search = LineSearch::new();
-        line_search.c2 = 0.1;
 
         let mut alpha_1: f64;
         let mut step: StepValues;
         let mut alpha_k: f64;
 
-        let mut solution = Solution {
-            x: Array::zeros(self.x_k.raw_dim()),
-            success: false,
-            iter_num: 0,
-            f_evals: 0,
-            f_grad_evals: 0,
-            f_hess_evals: 0,
-        };
+        let mut solution = Solution::new(x0.len());
 
         let mut k: u32 = 1;
         while k < self.i_max {
+            if !self.f_k.re.is_finite() || !self.f_k.du.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
             if self.f_k.du.dot(&self.f_k.du).sqrt() < self.gtol {
-                solution.success = true;
+                solution.status = Status::GradientTolerance;
                 break;
             }
             alpha_1 = self.guess_alpha();
 
             step = StepValues{x_k: &self.x_k, f_k: &self.f_k, p_k: &self.p_k, alpha_1};
-            alpha_k = line_search.find_alpha(step, problem);
+            alpha_k = match self.line_search.find_alpha(step, problem) {
+                Ok(alpha) => alpha,
+                Err(error) => {
+                    // the direction is already the steepest descent one
+                    solution.status = Status::LineSearchFailure(error);
+                    break;
+                },
+            };
 
             // calculate alpha*p_k
             self.p_k *= alpha_k;
@@ -96,19 +129,34 @@ impl SteepestDescent {
             self.p_k.assign(&self.f_k.du);
             self.p_k *= -1.0;
 
-//             println!("k={}, p_k={}, x_k={}, alpha_k={}", k, self.p_k, self.x_k, alpha_k);
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.re,
+                grad_norm: self.f_k.du.dot(&self.f_k.du).sqrt(),
+                step_length: alpha_k,
+                trust_radius: None,
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
 
             k += 1;
         }
 
         solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
         solution.iter_num = k;
+        solution.f = self.f_k.re;
+        solution.grad_norm = self.f_k.du.dot(&self.f_k.du).sqrt();
         // Note that grad evaluations counts both actual grad evaluations
         // (multivariate) and diff evaluations (univariate) which may not be
         // what one expects since diff may evaluate the object function only once
         // while grad will evaluate it multiple times
-        solution.f_evals = self.f_calls.get() + line_search.f_calls.get();
-        solution.f_grad_evals = self.f_grad_calls.get() + line_search.f_grad_calls.get();
+        solution.f_evals = self.f_calls.get() + self.line_search.f_evals() - line_search_evals.0
+                            + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + self.line_search.f_grad_evals() - line_search_evals.1;
         solution
     }
 
@@ -127,7 +175,15 @@ impl SteepestDescent {
 
     fn guess_alpha(&self) -> f64 {
         let phi_0_grad = self.f_k.du.dot(&self.p_k);
-        2.0*(self.f_k.re - self.f_k_old_re)/phi_0_grad
+        let alpha = 2.0*(self.f_k.re - self.f_k_old_re)/phi_0_grad;
+        // a negative guess means the function increased, fall back to the unit
+        // step
+        if alpha > 0.0 && alpha.is_finite() {
+            alpha
+        }
+        else {
+            1.0
+        }
     }
 
     fn eval_func<P>(&mut self, problem: &mut P)
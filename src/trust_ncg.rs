@@ -5,7 +5,9 @@
 
 use ndarray::{Array1, Array2, Array};
 use crate::number_system::HyperDual;
-use crate::Solution;
+use crate::{Solution, Status};
+use crate::observer::{Observer, NoObserver, Iteration, Control};
+use crate::trust_region::TrustRegion;
 use std::cell::Cell;
 use crate::problem::{Objective, Gradient, Hessian};
 
@@ -18,6 +20,9 @@ pub struct TrustNCG {
     // max trust region radius
     pub delta_max: f64,
 
+    // min trust region radius, the solver stops when the radius is smaller
+    pub delta_min: f64,
+
     // gradient tolerance
     pub gtol: f64,
 
@@ -55,6 +60,7 @@ impl TrustNCG {
         TrustNCG {
             i_max: 1000,
             delta_max: 100.0,
+            delta_min: 1e-12,
             gtol: 1e-6,
             p_k: Array1::zeros(1),
             x_k: Array1::zeros(1),
@@ -72,32 +78,48 @@ impl TrustNCG {
     where
         P: Objective + Gradient + Hessian,
     {
+        self.minimize_with_observer(x0, problem, &mut NoObserver)
+    }
+
+    pub fn minimize_with_observer<P, O>(&mut self,
+                                        x0: &Array1<f64>,
+                                        problem: &mut P,
+                                        observer: &mut O) -> Solution
+    where
+        P: Objective + Gradient + Hessian,
+        O: Observer,
+    {
+        self.f_calls.set(0);
+        self.f_grad_calls.set(0);
+        self.f_hess_calls.set(0);
+        let diff_evals = problem.f_evals();
+        let hess_evals = problem.grad_evals();
         self.set_up_parameters(x0, problem);
         let mut cg_steihaug = CGSteihaug::new(x0);
-        let mut delta = 1.0;
-        let eta = 0.15;  // eta can be between 0 to 1/4
+        let mut region = TrustRegion::new(1.0, self.delta_max);
         let mut rho: f64;
 
         // old function evaluation in case we need to discard a new step
         let mut old_fk = HyperDual::new(x0.len());
 
-        let mut solution = Solution {
-            x: Array::zeros(self.x_k.raw_dim()),
-            success: false,
-            iter_num: 0,
-            f_evals: 0,
-            f_grad_evals: 0,
-            f_hess_evals: 0,
-        };
+        let mut solution = Solution::new(x0.len());
 
         let mut k: u32 = 1;
         while k < self.i_max {
 
+            if !self.f_k.real.is_finite() || !self.f_k.grad.iter().all(|g| g.is_finite()) {
+                solution.status = Status::NonFinite;
+                break;
+            }
             if self.f_k.grad.dot(&self.f_k.grad).sqrt() < self.gtol {
-                solution.success = true;
+                solution.status = Status::GradientTolerance;
+                break;
+            }
+            if region.delta < self.delta_min {
+                solution.status = Status::TrustRadiusCollapsed;
                 break;
             }
-            cg_steihaug.solve_step(&self.f_k.grad, &self.f_k.hess, delta, &mut self.p_k);
+            cg_steihaug.solve_step(&self.f_k.grad, &self.f_k.hess, region.delta, &mut self.p_k);
             old_fk.real = self.f_k_old.real;
             old_fk.grad.assign(&self.f_k_old.grad);
             old_fk.hess.assign(&self.f_k_old.hess);
@@ -108,16 +130,15 @@ impl TrustNCG {
             self.x_k += & self.p_k;
             self.eval_func(problem);
             rho = self.calculate_rho();
-
-            if rho < 0.25 {
-                delta = 0.25*(self.p_k.dot(&self.p_k).sqrt());
+            if !self.f_k.real.is_finite() {
+                // the trial step left the region where the function is
+                // defined, so it is always rejected
+                rho = f64::NEG_INFINITY;
             }
-            else {
-                if rho > 0.75 && (self.p_k.dot(&self.p_k)).sqrt() == delta {
-                    delta = f64::min(2.0*delta, self.delta_max);
-                }
-            }
-            if rho < eta {
+
+            let mut step_length = self.p_k.dot(&self.p_k).sqrt();
+            if !region.update(rho, step_length) {
+                step_length = 0.0;
                 // roll back to previous step
                 self.x_k -= &self.p_k;
                 // reset f_k values
@@ -129,18 +150,34 @@ impl TrustNCG {
                 self.f_k_old.hess.assign(&old_fk.hess);
             }
 
-//             println!("k={}, p_k={}, x_k={}, rho={}, delta={}", k, self.p_k, self.x_k, rho, delta);
+            let iteration = Iteration {
+                k,
+                x_k: &self.x_k,
+                f_k: self.f_k.real,
+                grad_norm: self.f_k.grad.dot(&self.f_k.grad).sqrt(),
+                step_length,
+                trust_radius: Some(region.delta),
+            };
+            if observer.observe(&iteration) == Control::Stop {
+                solution.status = Status::UserAbort;
+                break;
+            }
+
             k += 1;
         }
 
         solution.x.assign(&self.x_k);
+        solution.success = solution.status.is_success();
         solution.iter_num = k;
+        solution.f = self.f_k.real;
+        solution.grad_norm = self.f_k.grad.dot(&self.f_k.grad).sqrt();
+        solution.trust_radius = Some(region.delta);
         // Note that grad evaluations counts both actual grad evaluations
         // (multivariate) and diff evaluations (univariate) which may not be
         // what one expects since diff may evaluate the object function only once
         // while grad will evaluate it multiple times
-        solution.f_evals = self.f_calls.get();
-        solution.f_grad_evals = self.f_grad_calls.get();
+        solution.f_evals = self.f_calls.get() + problem.f_evals() - diff_evals;
+        solution.f_grad_evals = self.f_grad_calls.get() + problem.grad_evals() - hess_evals;
         solution.f_hess_evals = self.f_hess_calls.get();
         solution
     }
@@ -241,10 +278,12 @@ impl CGSteihaug {
 
 
             if djBdj <= 0.0 {
-                // Find tau
-                // p = p + tau*d
-                let a = (r_0.dot(r_0)).powi(3) / (delta*r_0.dot(&B.dot(r_0)));
-                let tau = f64::min(a, 1.0);
+                // the model decreases along d until the boundary, find
+                // tau >= 0 such that |p + tau*d| = delta
+                let a = self.d.dot(&self.d);
+                let b = 2.0*self.p.dot(&self.d);
+                let c = self.p.dot(&self.p) - delta.powi(2);
+                let tau = (-b + (b.powi(2) - 4.0*a*c).sqrt())/(2.0*a);
                 let tmp = &self.p + tau*&self.d;
                 output.assign(&tmp);
                 return
@@ -0,0 +1,111 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+
+use ndarray::{Array1, Array2};
+
+
+// Trust region radius update shared by the trust region solvers. It follows
+// "Numerical Optimization" (Algorithm 4.1).
+pub struct TrustRegion {
+    // current trust region radius
+    pub delta: f64,
+
+    // max trust region radius
+    pub delta_max: f64,
+
+    // min ratio between the actual and predicted reduction for a step to be
+    // accepted (eta can be between 0 to 1/4)
+    pub eta: f64,
+}
+
+
+impl TrustRegion {
+    pub fn new(delta: f64, delta_max: f64) -> TrustRegion {
+        TrustRegion {
+            delta,
+            delta_max,
+            eta: 0.15,
+        }
+    }
+
+    // Updates the radius with the ratio rho between the actual and predicted
+    // reductions of the step and the norm of the step. Returns true when the
+    // step is accepted.
+    pub fn update(&mut self, rho: f64, step_norm: f64) -> bool {
+        if rho < 0.25 {
+            self.delta = 0.25*step_norm;
+        }
+        else if rho > 0.75 && self.at_boundary(step_norm) {
+            self.delta = f64::min(2.0*self.delta, self.delta_max);
+        }
+        rho >= self.eta
+    }
+
+    // The steps on the boundary are calculated by scaling a direction so their
+    // norm may differ from delta by rounding errors
+    fn at_boundary(&self, step_norm: f64) -> bool {
+        (step_norm - self.delta).abs() <= 1e-10*self.delta
+    }
+}
+
+
+// Powell's dogleg method obtained from "Numerical Optimization"
+// (Section 4.1) for the model m(p) = 0.5*|r + J*p|^2 with gradient g = J^T*r.
+// The step follows the steepest descent direction up to the Cauchy point and
+// then goes towards the gauss-newton step p_gn until it reaches the trust
+// region boundary.
+pub fn dogleg(j: &Array2<f64>, g: &Array1<f64>, p_gn: &Array1<f64>, delta: f64) -> Array1<f64> {
+    let gn_norm = p_gn.dot(p_gn).sqrt();
+    if gn_norm <= delta {
+        return p_gn.clone()
+    }
+
+    // minimizer of the model along the steepest descent direction
+    let g_norm2 = g.dot(g);
+    let jg = j.dot(g);
+    let jg_norm2 = jg.dot(&jg);
+    let g_norm = g_norm2.sqrt();
+    if jg_norm2 == 0.0 || g_norm2*g_norm/jg_norm2 >= delta {
+        return -delta/g_norm*g
+    }
+    let p_u = -g_norm2/jg_norm2*g;
+
+    // find tau such that |p_u + tau*(p_gn - p_u)| = delta
+    let d = p_gn - &p_u;
+    let a = d.dot(&d);
+    let b = 2.0*p_u.dot(&d);
+    let c = p_u.dot(&p_u) - delta.powi(2);
+    let tau = (-b + (b.powi(2) - 4.0*a*c).sqrt())/(2.0*a);
+    &p_u + &(tau*&d)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::TrustRegion;
+
+    #[test]
+    fn test_update() {
+        let mut region = TrustRegion::new(1.0, 3.0);
+
+        // very good step on the boundary expands the region
+        assert!(region.update(0.9, 1.0));
+        assert_eq!(region.delta, 2.0);
+        assert!(region.update(0.9, 2.0));
+        assert_eq!(region.delta, 3.0);
+
+        // very good step inside the region doesn't change it
+        assert!(region.update(0.9, 1.0));
+        assert_eq!(region.delta, 3.0);
+
+        // bad step is rejected and the region shrinks
+        assert!(!region.update(0.1, 2.0));
+        assert_eq!(region.delta, 0.5);
+
+        // poor step is accepted but the region shrinks
+        assert!(region.update(0.2, 0.5));
+        assert_eq!(region.delta, 0.125);
+    }
+}
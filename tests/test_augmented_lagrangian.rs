@@ -0,0 +1,268 @@
+use optimization::{AugmentedLagrangian, TrustNCG, LBFGS, Status};
+use optimization::problem::{Objective, Gradient, FnProblem, FnConstraints, Constraints};
+use optimization::number_system::{DualScalar, HyperDualScalar};
+use optimization::observer::{Iteration, Control};
+use ndarray::{arr1, Array1, Array2};
+use approx::assert_abs_diff_eq;
+
+
+fn no_constraints<T>(_: &[T]) -> Vec<T> {
+    vec![]
+}
+
+
+// Wrapper that counts the evaluations of the objective function and its
+// gradient made by all the inner minimizations.
+struct Counted<P> {
+    problem: P,
+    f_calls: u32,
+    grad_calls: u32,
+}
+
+
+impl<P: Objective> Objective for Counted<P> {
+    fn eval(&mut self) {
+        self.f_calls += 1;
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.f_calls += 1;
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Gradient> Gradient for Counted<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.grad_calls += 1;
+        self.problem.grad(output);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.grad_calls += 1;
+        self.problem.diff()
+    }
+}
+
+
+#[test]
+fn test_fn_constraints() {
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2)*x[1] - 1.0, x[0] + x[1]],
+        |x: &[DualScalar]| vec![x[1].exp()],
+    );
+    assert_eq!(constraints.num_equality(), 2);
+    assert_eq!(constraints.num_inequality(), 1);
+
+    let x = arr1(&[2.0, 0.5]);
+    let mut c = Array1::zeros(2);
+    let mut jac = Array2::zeros((2, 2));
+    constraints.equality(&x, &mut c);
+    constraints.equality_jacobian(&x, &mut jac);
+    assert_abs_diff_eq!(c[0], 1.0);
+    assert_abs_diff_eq!(c[1], 2.5);
+    assert_abs_diff_eq!(jac[[0, 0]], 2.0);
+    assert_abs_diff_eq!(jac[[0, 1]], 4.0);
+    assert_abs_diff_eq!(jac[[1, 0]], 1.0);
+    assert_abs_diff_eq!(jac[[1, 1]], 1.0);
+
+    let mut g = Array1::zeros(1);
+    let mut jac = Array2::zeros((1, 2));
+    constraints.inequality(&x, &mut g);
+    constraints.inequality_jacobian(&x, &mut jac);
+    assert_abs_diff_eq!(g[0], 0.5f64.exp());
+    assert_abs_diff_eq!(jac[[0, 0]], 0.0);
+    assert_abs_diff_eq!(jac[[0, 1]], 0.5f64.exp());
+}
+
+
+#[test]
+fn test_equality() {
+    // closest point to the origin on the line x0 + x1 = 1
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| x[0].powi(2) + x[1].powi(2));
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0] + x[1] - 1.0],
+        no_constraints,
+    );
+    let sol = AugmentedLagrangian::new().minimize(&arr1(&[3.0, -1.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    assert_abs_diff_eq!(sol.x[0], 0.5, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 0.5, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, 0.5, epsilon = 1e-6);
+    assert!(sol.constraint_violation.unwrap() <= 1e-8);
+    assert_abs_diff_eq!(sol.equality_multipliers.unwrap()[0], -1.0, epsilon = 1e-5);
+    assert_eq!(sol.inequality_multipliers.unwrap().len(), 0);
+}
+
+
+#[test]
+fn test_inequality() {
+    // the solution (1, 1) is on both constraints
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0].powi(2) - x[1], x[0] + x[1] - 2.0],
+    );
+    let mut solver = AugmentedLagrangian::new();
+    let sol = solver.minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+    let nu = sol.inequality_multipliers.unwrap();
+    assert_abs_diff_eq!(nu[0], 2.0/3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(nu[1], 2.0/3.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_inactive_inequality() {
+    // the unconstrained minimum (1, 2) satisfies the constraint
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 1.0).powi(2) + (x[1] - 2.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0] - 1.0],
+        |x: &[DualScalar]| vec![x[0] + x[1] - 5.0],
+    );
+    let sol = AugmentedLagrangian::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 2.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.equality_multipliers.unwrap()[0], 0.0, epsilon = 1e-5);
+    assert_eq!(sol.inequality_multipliers.unwrap()[0], 0.0);
+}
+
+
+#[test]
+fn test_inner_solvers() {
+    // point of the plane x0 + 2x1 + 3x2 = 10 closest to (1, 1, 1) with x2 <= 1
+    let objective = |x: &[HyperDualScalar]| {
+        (x[0] - 1.0).powi(2) + (x[1] - 1.0).powi(2) + (x[2] - 1.0).powi(2)
+    };
+    let equality = |x: &[DualScalar]| vec![x[0] + x[1]*2.0 + x[2]*3.0 - 10.0];
+    let inequality = |x: &[DualScalar]| vec![x[2] - 1.0];
+    let x0 = arr1(&[0.0, 0.0, 0.0]);
+
+    let mut problem = FnProblem::new(3, objective);
+    let mut constraints = FnConstraints::new(3, equality, inequality);
+    let mut solver = AugmentedLagrangian::with_solver(TrustNCG::new());
+    let sol = solver.minimize(&x0, &mut problem, &mut constraints);
+    assert!(sol.success);
+    assert!(sol.f_hess_evals > 0);
+    assert_abs_diff_eq!(sol.x[0], 1.8, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 2.6, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[2], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.equality_multipliers.unwrap()[0], -1.6, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.inequality_multipliers.unwrap()[0], 4.8, epsilon = 1e-5);
+
+    let mut problem = FnProblem::new(3, objective);
+    let mut solver = AugmentedLagrangian::with_solver(LBFGS::new());
+    let sol = solver.minimize(&x0, &mut problem, &mut constraints);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[1], 2.6, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_nonlinear_equality() {
+    // point of the unit circle closest to (2, 1), the multiplier is
+    // sqrt(5) - 1
+    let mut problem = FnProblem::new(2, |x: &[HyperDualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+        no_constraints,
+    );
+    let mut solver = AugmentedLagrangian::with_solver(TrustNCG::new());
+    let sol = solver.minimize(&arr1(&[0.5, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 2.0/5f64.sqrt(), epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0/5f64.sqrt(), epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.equality_multipliers.unwrap()[0], 5f64.sqrt() - 1.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_evaluation_counts() {
+    // the counts add the evaluations of all the inner minimizations
+    let mut problem = Counted {
+        problem: FnProblem::new(2, |x: &[DualScalar]| {
+            (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+        }),
+        f_calls: 0,
+        grad_calls: 0,
+    };
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+        |x: &[DualScalar]| vec![x[0] - 0.5],
+    );
+    let sol = AugmentedLagrangian::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert!(sol.iter_num > 2);
+    assert_eq!(sol.f_evals, problem.f_calls);
+    assert_eq!(sol.f_grad_evals, problem.grad_calls);
+}
+
+
+#[test]
+fn test_infeasible() {
+    let mut problem = FnProblem::new(1, |x: &[DualScalar]| x[0].powi(2));
+    let mut constraints = FnConstraints::new(
+        1,
+        |x: &[DualScalar]| vec![x[0] - 1.0, x[0] - 2.0],
+        no_constraints,
+    );
+    let sol = AugmentedLagrangian::new().minimize(&arr1(&[0.0]), &mut problem, &mut constraints);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::Infeasible);
+    // the least-squares compromise of the constraints
+    assert_abs_diff_eq!(sol.x[0], 1.5, epsilon = 1e-3);
+    assert_abs_diff_eq!(sol.constraint_violation.unwrap(), 0.5, epsilon = 1e-3);
+}
+
+
+#[test]
+fn test_observer() {
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| x[0].powi(2) + x[1].powi(2));
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0] + x[1] - 1.0],
+        no_constraints,
+    );
+    let mut outer = 0;
+    let mut observer = |iteration: &Iteration| {
+        outer += 1;
+        assert!(iteration.f_k.is_finite());
+        if iteration.k < 2 { Control::Continue } else { Control::Stop }
+    };
+    let sol = AugmentedLagrangian::new().minimize_with_observer(&arr1(&[3.0, -1.0]), &mut problem,
+                                                               &mut constraints, &mut observer);
+
+    assert_eq!(sol.status, Status::UserAbort);
+    assert_eq!(outer, 2);
+}
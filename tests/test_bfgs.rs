@@ -95,3 +95,18 @@ fn test_bfgs() {
     assert_abs_diff_eq!(sol.x[1], 0.201357, epsilon = 0.000001);
     assert_abs_diff_eq!(sol.x[2], 1.600000, epsilon = 0.000001);
 }
+
+
+#[test]
+fn test_repeated_minimize() {
+    // the counts of a solver used twice are the ones of each call
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = BFGS::new();
+    let mut problem = ProblemObjective{x: vec![DualScalar::new(); 3], value: DualScalar::new()};
+    let first = min.minimize(&x0, &mut problem);
+    let second = min.minimize(&x0, &mut problem);
+
+    assert!(first.f_evals > 0);
+    assert_eq!(second.f_evals, first.f_evals);
+    assert_eq!(second.f_grad_evals, first.f_grad_evals);
+}
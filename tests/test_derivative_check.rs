@@ -0,0 +1,168 @@
+use optimization::problem::{Objective, Gradient, Hessian, DerivativeChecker, Difference, FnProblem};
+use optimization::number_system::{DualScalar, HyperDualScalar};
+use ndarray::{Array1, Array2, arr1};
+
+
+// Problem with hand written derivatives of f = x0^2*x1 + exp(x1). The bugs
+// are introduced on purpose to test the checker.
+pub struct HandWritten {
+    x: Array1<f64>,
+    p: Array1<f64>,
+    wrong_grad: bool,
+    wrong_hess: bool,
+}
+
+
+impl HandWritten {
+    fn new(wrong_grad: bool, wrong_hess: bool) -> HandWritten {
+        HandWritten {
+            x: Array1::zeros(2),
+            p: Array1::zeros(2),
+            wrong_grad,
+            wrong_hess,
+        }
+    }
+}
+
+
+impl Objective for HandWritten {
+    fn eval(&mut self) {}
+
+    fn eval_real(&mut self) -> f64 {
+        self.x[0].powi(2)*self.x[1] + self.x[1].exp()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+        self.p.assign(p);
+    }
+}
+
+
+impl Gradient for HandWritten {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        output[0] = 2.0*self.x[0]*self.x[1];
+        output[1] = self.x[0].powi(2) + self.x[1].exp();
+        if self.wrong_grad {
+            // missing the exponential term
+            output[1] = self.x[0].powi(2);
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        let mut grad = Array1::zeros(2);
+        self.grad(&mut grad);
+        grad.dot(&self.p)
+    }
+}
+
+
+impl Hessian for HandWritten {
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        output[[0, 0]] = 2.0*self.x[1];
+        output[[0, 1]] = 2.0*self.x[0];
+        output[[1, 0]] = 2.0*self.x[0];
+        output[[1, 1]] = self.x[1].exp();
+        if self.wrong_hess {
+            output[[0, 1]] = self.x[0];
+        }
+    }
+}
+
+
+#[test]
+fn test_correct_derivatives() {
+    let x = arr1(&[1.5, -0.5]);
+    let checker = DerivativeChecker::new();
+    let mut problem = HandWritten::new(false, false);
+
+    let check = checker.gradient(&mut problem, &x);
+    assert!(check.passed(), "{}", check);
+    assert!(check.offending().is_empty());
+    assert!(check.max_error < 1e-8);
+
+    let check = checker.diff(&mut problem, &x, &arr1(&[1.0, 2.0]));
+    assert!(check.passed(), "{}", check);
+
+    let check = checker.hessian(&mut problem, &x);
+    assert!(check.passed(), "{}", check);
+    assert!(check.offending().is_empty());
+}
+
+
+#[test]
+fn test_wrong_gradient() {
+    let x = arr1(&[1.5, -0.5]);
+    let checker = DerivativeChecker::new();
+    let mut problem = HandWritten::new(true, false);
+
+    let check = checker.gradient(&mut problem, &x);
+    assert!(!check.passed());
+    assert_eq!(check.offending(), vec![1]);
+    assert!(check.errors[0] < 1e-8);
+    assert!(check.max_error > 0.1);
+    assert!(format!("{}", check).contains("expected"));
+
+    let check = checker.diff(&mut problem, &x, &arr1(&[0.0, 1.0]));
+    assert!(!check.passed());
+    // the wrong component doesn't affect this direction
+    let check = checker.diff(&mut problem, &x, &arr1(&[1.0, 0.0]));
+    assert!(check.passed());
+}
+
+
+#[test]
+fn test_wrong_hessian() {
+    let x = arr1(&[1.5, -0.5]);
+    let mut problem = HandWritten::new(false, true);
+    let check = DerivativeChecker::new().hessian(&mut problem, &x);
+
+    assert!(!check.passed());
+    assert_eq!(check.offending(), vec![(0, 1)]);
+}
+
+
+#[test]
+fn test_forward_difference() {
+    let x = arr1(&[1.5, -0.5]);
+    let mut checker = DerivativeChecker::new();
+    checker.difference = Difference::Forward;
+    checker.tolerance = 1e-5;
+    let mut problem = HandWritten::new(false, false);
+
+    assert!(checker.gradient(&mut problem, &x).passed());
+    assert!(checker.hessian(&mut problem, &x).passed());
+}
+
+
+#[test]
+fn test_fn_problem() {
+    let x = arr1(&[0.3, 1.2, -2.0]);
+    let checker = DerivativeChecker::new();
+
+    let mut problem = FnProblem::new(3, |x: &[DualScalar]| {
+        x[0].sin()*x[1].powi(3) + x[2].exp()*x[1]
+    });
+    assert!(checker.gradient(&mut problem, &x).passed());
+    assert!(checker.diff(&mut problem, &x, &arr1(&[1.0, -1.0, 0.5])).passed());
+
+    let mut problem = FnProblem::new(3, |x: &[HyperDualScalar]| {
+        x[0].sin()*x[1].powi(3) + x[2].exp()*x[1]
+    });
+    assert!(checker.hessian(&mut problem, &x).passed());
+}
+
+
+#[test]
+fn test_non_finite() {
+    let mut problem = FnProblem::new(1, |x: &[DualScalar]| x[0].sqrt());
+    let check = DerivativeChecker::new().gradient(&mut problem, &arr1(&[0.0]));
+
+    assert!(!check.passed());
+    assert!(check.max_error.is_nan());
+    assert_eq!(check.offending(), vec![0]);
+}
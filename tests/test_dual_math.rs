@@ -51,3 +51,297 @@ fn test_powi() {
     assert_abs_diff_eq!(dual2.du[1], grad2);
     assert_abs_diff_eq!(dual2.du[2], grad3);
 }
+
+
+#[test]
+fn test_sqrt() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.sqrt();
+    let real = x.sqrt();
+    let grad2 = grad1 * 0.5/x.sqrt();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_exp() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.exp();
+    let real = x.exp();
+    let grad2 = grad1 * x.exp();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_ln() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.ln();
+    let real = x.ln();
+    let grad2 = grad1 * 1.0/x;
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_log10() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.log10();
+    let real = x.log10();
+    let grad2 = grad1 * 1.0/(x*10.0_f64.ln());
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_powf() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.powf(2.5);
+    let real = x.powf(2.5);
+    let grad2 = grad1 * 2.5*x.powf(1.5);
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_tan() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.tan();
+    let real = x.tan();
+    let grad2 = grad1 * 1.0/x.cos().powi(2);
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_asin() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.asin();
+    let real = x.asin();
+    let grad2 = grad1 * 1.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_acos() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.acos();
+    let real = x.acos();
+    let grad2 = grad1 * -1.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_atan() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.atan();
+    let real = x.atan();
+    let grad2 = grad1 * 1.0/(1.0 + x*x);
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_sinh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.sinh();
+    let real = x.sinh();
+    let grad2 = grad1 * x.cosh();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_cosh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.cosh();
+    let real = x.cosh();
+    let grad2 = grad1 * x.sinh();
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_tanh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.tanh();
+    let real = x.tanh();
+    let grad2 = grad1 * 1.0/x.cosh().powi(2);
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_abs() {
+    let x = -0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.abs();
+    let real = 0.5;
+    let grad2 = -grad1;
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_recip() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.recip();
+    let real = 1.0/x;
+    let grad2 = grad1 * -1.0/(x*x);
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_cbrt() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let dual1 = Dual{re: x, du: grad1.clone()};
+    let dual2 = dual1.cbrt();
+    let real = x.cbrt();
+    let grad2 = grad1 * x.powf(-2.0/3.0)/3.0;
+
+    assert_abs_diff_eq!(dual2.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual2.du[i], grad2[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_pow() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let dual1 = Dual{re: a, du: grad_a.clone()};
+    let dual2 = Dual{re: b, du: grad_b.clone()};
+    let dual3 = dual1.pow(&dual2);
+    let real = a.powf(b);
+    let grad3 = grad_a * (b*a.powf(b - 1.0)) + grad_b * (a.powf(b)*a.ln());
+
+    assert_abs_diff_eq!(dual3.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual3.du[i], grad3[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_atan2() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let dual1 = Dual{re: a, du: grad_a.clone()};
+    let dual2 = Dual{re: b, du: grad_b.clone()};
+    let dual3 = dual1.atan2(&dual2);
+    let real = a.atan2(b);
+    let grad3 = grad_a * (b/(a*a + b*b)) + grad_b * (-a/(a*a + b*b));
+
+    assert_abs_diff_eq!(dual3.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual3.du[i], grad3[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_hypot() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let dual1 = Dual{re: a, du: grad_a.clone()};
+    let dual2 = Dual{re: b, du: grad_b.clone()};
+    let dual3 = dual1.hypot(&dual2);
+    let real = (a*a + b*b).sqrt();
+    let grad3 = grad_a * (a/(a*a + b*b).sqrt()) + grad_b * (b/(a*a + b*b).sqrt());
+
+    assert_abs_diff_eq!(dual3.re, real, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(dual3.du[i], grad3[i], epsilon = 1e-12);
+    }
+}
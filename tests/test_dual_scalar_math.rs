@@ -36,3 +36,240 @@ fn test_powi() {
     assert_abs_diff_eq!(test_dual.re, real);
     assert_abs_diff_eq!(test_dual.du, dual);
 }
+
+
+#[test]
+fn test_sqrt() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.sqrt();
+    let real = x.sqrt();
+    let dual = 3.0_f64 * 0.5/x.sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_exp() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.exp();
+    let real = x.exp();
+    let dual = 3.0_f64 * x.exp();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_ln() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.ln();
+    let real = x.ln();
+    let dual = 3.0_f64 * 1.0/x;
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_log10() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.log10();
+    let real = x.log10();
+    let dual = 3.0_f64 * 1.0/(x*10.0_f64.ln());
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_powf() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.powf(2.5);
+    let real = x.powf(2.5);
+    let dual = 3.0_f64 * 2.5*x.powf(1.5);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_tan() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.tan();
+    let real = x.tan();
+    let dual = 3.0_f64 * 1.0/x.cos().powi(2);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_asin() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.asin();
+    let real = x.asin();
+    let dual = 3.0_f64 * 1.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_acos() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.acos();
+    let real = x.acos();
+    let dual = -3.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_atan() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.atan();
+    let real = x.atan();
+    let dual = 3.0_f64 * 1.0/(1.0 + x*x);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_sinh() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.sinh();
+    let real = x.sinh();
+    let dual = 3.0_f64 * x.cosh();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_cosh() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.cosh();
+    let real = x.cosh();
+    let dual = 3.0_f64 * x.sinh();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_tanh() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.tanh();
+    let real = x.tanh();
+    let dual = 3.0_f64 * 1.0/x.cosh().powi(2);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_abs() {
+    let x = -0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.abs();
+    let real = 0.5;
+    let dual = -3.0_f64;
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_recip() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.recip();
+    let real = 1.0/x;
+    let dual = -3.0/(x*x);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_cbrt() {
+    let x = 0.5_f64;
+    let a = DualScalar{re: x, du:3.0};
+    let test_dual = a.cbrt();
+    let real = x.cbrt();
+    let dual = 3.0_f64 * x.powf(-2.0/3.0)/3.0;
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_pow() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = DualScalar{re: a, du:3.0};
+    let y = DualScalar{re: b, du:2.0};
+    let test_dual = x.pow(&y);
+    let real = a.powf(b);
+    let dual = 3.0_f64 * b*a.powf(b - 1.0) + 2.0_f64 * a.powf(b)*a.ln();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_atan2() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = DualScalar{re: a, du:3.0};
+    let y = DualScalar{re: b, du:2.0};
+    let test_dual = x.atan2(&y);
+    let real = a.atan2(b);
+    let dual = 3.0_f64 * b/(a*a + b*b) + 2.0_f64 * -a/(a*a + b*b);
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_hypot() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = DualScalar{re: a, du:3.0};
+    let y = DualScalar{re: b, du:2.0};
+    let test_dual = x.hypot(&y);
+    let real = (a*a + b*b).sqrt();
+    let dual = 3.0_f64 * a/(a*a + b*b).sqrt() + 2.0_f64 * b/(a*a + b*b).sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, real, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.du, dual, epsilon = 1e-12);
+}
@@ -0,0 +1,292 @@
+use optimization::{BFGS, LBFGS, LBFGSB, TrustNCG};
+use optimization::problem::{Objective, Gradient, Hessian, FiniteDiff, FiniteHessian, Difference,
+                            FnProblem, DualStep, DualObjective};
+use optimization::number_system::DualScalar;
+use ndarray::{Array1, Array2, arr1};
+use approx::assert_abs_diff_eq;
+
+
+// Objective function evaluated with plain f64 values, like a function coming
+// from an external library that can't use dual numbers.
+pub struct ExternalProblem {
+    x: Array1<f64>,
+}
+
+
+impl ExternalProblem {
+    fn new(n: usize) -> ExternalProblem {
+        ExternalProblem {
+            x: Array1::zeros(n),
+        }
+    }
+}
+
+
+// f = sin(x0)*x1^2 + exp(x2)*x0
+impl Objective for ExternalProblem {
+    fn eval(&mut self) {}
+
+    fn eval_real(&mut self) -> f64 {
+        let x = &self.x;
+        x[0].sin()*x[1].powi(2) + x[2].exp()*x[0]
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+    }
+}
+
+
+// The dual evaluation gives the exact derivatives of the same function.
+impl DualObjective for ExternalProblem {
+    fn eval_dual(&mut self, x: &[DualScalar]) -> DualScalar {
+        x[0].sin()*x[1].powi(2) + x[2].exp()*x[0]
+    }
+}
+
+
+// Wrapper that counts all the evaluations of the objective function and its
+// gradient, made either by the solvers or by the adapters.
+pub struct Counted<P> {
+    problem: P,
+    f_calls: u32,
+    grad_calls: u32,
+}
+
+
+impl<P> Counted<P> {
+    fn new(problem: P) -> Counted<P> {
+        Counted {
+            problem,
+            f_calls: 0,
+            grad_calls: 0,
+        }
+    }
+}
+
+
+impl<P: Objective> Objective for Counted<P> {
+    fn eval(&mut self) {
+        self.f_calls += 1;
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.f_calls += 1;
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Gradient> Gradient for Counted<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.grad_calls += 1;
+        self.problem.grad(output);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.grad_calls += 1;
+        self.problem.diff()
+    }
+}
+
+
+// Extended Rosenbrock function evaluated with f64 values
+pub struct Rosenbrock {
+    x: Array1<f64>,
+}
+
+
+impl Objective for Rosenbrock {
+    fn eval(&mut self) {}
+
+    fn eval_real(&mut self) -> f64 {
+        (1.0 - self.x[0]).powi(2) + 100.0*(self.x[1] - self.x[0].powi(2)).powi(2)
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+    }
+}
+
+
+fn exact_grad(x: &Array1<f64>) -> Array1<f64> {
+    arr1(&[
+        x[0].cos()*x[1].powi(2) + x[2].exp(),
+        2.0*x[0].sin()*x[1],
+        x[2].exp()*x[0],
+    ])
+}
+
+
+fn exact_hess(x: &Array1<f64>) -> Array2<f64> {
+    let mut hess = Array2::zeros((3, 3));
+    hess[[0, 0]] = -x[0].sin()*x[1].powi(2);
+    hess[[0, 1]] = 2.0*x[0].cos()*x[1];
+    hess[[0, 2]] = x[2].exp();
+    hess[[1, 1]] = 2.0*x[0].sin();
+    hess[[2, 2]] = x[2].exp()*x[0];
+    hess[[1, 0]] = hess[[0, 1]];
+    hess[[2, 0]] = hess[[0, 2]];
+    hess
+}
+
+
+#[test]
+fn test_gradient() {
+    let x = arr1(&[0.5, -2.0, 1.5]);
+    let exact = exact_grad(&x);
+    let mut grad = Array1::zeros(3);
+
+    let mut problem = FiniteDiff::new(ExternalProblem::new(3));
+    problem.update_x(&x);
+    problem.grad(&mut grad);
+    for i in 0..3 {
+        assert_abs_diff_eq!(grad[i], exact[i], epsilon = 1e-9);
+    }
+    assert_eq!(problem.f_evals(), 6);
+
+    let mut problem = FiniteDiff::new(ExternalProblem::new(3));
+    problem.difference = Difference::Forward;
+    problem.update_x(&x);
+    problem.grad(&mut grad);
+    for i in 0..3 {
+        assert_abs_diff_eq!(grad[i], exact[i], epsilon = 1e-6);
+    }
+    assert_eq!(problem.f_evals(), 4);
+
+    // the value at x is reused when the function was already evaluated
+    problem.eval_real();
+    problem.grad(&mut grad);
+    assert_eq!(problem.f_evals(), 7);
+}
+
+
+#[test]
+fn test_diff() {
+    let x = arr1(&[0.5, -2.0, 1.5]);
+    let p = arr1(&[1.0, 0.5, -1.0]);
+    let alpha = 0.3;
+    let exact = exact_grad(&(&x + &(alpha*&p))).dot(&p);
+
+    for difference in &[Difference::Forward, Difference::Central] {
+        let mut problem = FiniteDiff::new(ExternalProblem::new(3));
+        problem.difference = *difference;
+        problem.move_step(&x, &p, alpha);
+        assert_abs_diff_eq!(problem.diff(), exact, epsilon = 1e-6);
+        // the problem is left at the position of the step
+        assert_abs_diff_eq!(problem.inner().x[0], x[0] + alpha*p[0]);
+    }
+}
+
+
+#[test]
+fn test_dual_step() {
+    let x = arr1(&[0.5, -2.0, 1.5]);
+    let p = arr1(&[1.0, 0.5, -1.0]);
+    let exact = exact_grad(&x);
+    let mut grad = Array1::zeros(3);
+
+    let mut problem = DualStep::new(ExternalProblem::new(3));
+    problem.update_x(&x);
+    problem.grad(&mut grad);
+    for i in 0..3 {
+        assert_abs_diff_eq!(grad[i], exact[i], epsilon = 1e-14);
+    }
+    assert_eq!(problem.f_evals(), 3);
+
+    let alpha = 0.3;
+    problem.move_step(&x, &p, alpha);
+    assert_abs_diff_eq!(problem.diff(), exact_grad(&(&x + &(alpha*&p))).dot(&p), epsilon = 1e-14);
+    assert_eq!(problem.f_evals(), 4);
+}
+
+
+#[test]
+fn test_minimize() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let rosenbrock = || Counted::new(Rosenbrock{x: Array1::zeros(2)});
+
+    // the solution counts the evaluations of the solver and the differences
+    let mut problem = FiniteDiff::new(rosenbrock());
+    let sol = BFGS::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+    assert_eq!(sol.f_evals, problem.inner().f_calls);
+
+    let mut problem = FiniteDiff::new(rosenbrock());
+    let sol = LBFGS::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_eq!(sol.f_evals, problem.inner().f_calls);
+
+    let mut min = LBFGSB::new(arr1(&[-2.0, -2.0]), arr1(&[0.5, 2.0]));
+    let mut problem = FiniteDiff::new(rosenbrock());
+    let sol = min.minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 0.5, epsilon = 1e-5);
+    assert_eq!(sol.f_evals, problem.inner().f_calls);
+}
+
+
+#[test]
+fn test_hessian() {
+    let x = arr1(&[0.5, -2.0, 1.5]);
+    let exact = exact_hess(&x);
+    let mut hess = Array2::zeros((3, 3));
+
+    // differences of the exact gradient
+    let problem = FnProblem::new(3, |x: &[DualScalar]| x[0].sin()*x[1].powi(2) + x[2].exp()*x[0]);
+    let mut problem = FiniteHessian::new(problem);
+    problem.update_x(&x);
+    problem.hess(&mut hess);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_abs_diff_eq!(hess[[i, j]], exact[[i, j]], epsilon = 1e-8);
+            assert_eq!(hess[[i, j]], hess[[j, i]]);
+        }
+    }
+    assert_eq!(problem.grad_evals(), 6);
+
+    // differences of the finite difference gradient
+    let mut problem = FiniteHessian::new(FiniteDiff::new(ExternalProblem::new(3)));
+    problem.update_x(&x);
+    problem.hess(&mut hess);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_abs_diff_eq!(hess[[i, j]], exact[[i, j]], epsilon = 1e-4);
+        }
+    }
+    assert_eq!(problem.f_evals(), 36);
+}
+
+
+#[test]
+fn test_trust_ncg() {
+    let x0 = arr1(&[2.0, -1.0, 0.5]);
+    let problem = FnProblem::new(3, |x: &[DualScalar]| {
+        (x[0] - 1.0).powi(2) + (x[1] - x[0]).powi(2)*10.0 + (x[2] + x[1]).powi(4) + x[2].powi(2)
+    });
+    let mut problem = FiniteHessian::new(Counted::new(problem));
+    let sol = TrustNCG::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    // the gradients of the hessian differences are counted by the solver
+    assert!(problem.grad_evals() > 0);
+    assert_eq!(sol.f_grad_evals, problem.inner().grad_calls);
+}
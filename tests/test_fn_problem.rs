@@ -0,0 +1,127 @@
+use optimization::{BFGS, TrustNCG};
+use optimization::number_system::{DualScalar, HyperDualScalar as HDual, HyperDual};
+use optimization::problem::{FnProblem, Objective, Gradient, Hessian};
+use ndarray::{Array1, Array2, Array, arr1, arr2};
+use approx::assert_abs_diff_eq;
+
+
+// f(x, y) = x^2*y + sin(y)
+// grad f = [2xy, x^2 + cos(y)]
+// hess f = [[2y, 2x], [2x, -sin(y)]]
+fn expected_values(x: f64, y: f64) -> (f64, Array1<f64>, Array2<f64>) {
+    let value = x.powi(2)*y + y.sin();
+    let grad = arr1(&[2.0*x*y, x.powi(2) + y.cos()]);
+    let hess = arr2(&[[2.0*y, 2.0*x], [2.0*x, -y.sin()]]);
+    (value, grad, hess)
+}
+
+
+#[test]
+fn test_fn_problem_dual_scalar() {
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    let x = arr1(&[1.5, -0.5]);
+    let (value, grad, _) = expected_values(x[0], x[1]);
+
+    problem.update_x(&x);
+    assert_abs_diff_eq!(problem.eval_real(), value, epsilon = 1e-12);
+
+    let mut output = Array::zeros(2);
+    problem.grad(&mut output);
+    assert_abs_diff_eq!(output[0], grad[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(output[1], grad[1], epsilon = 1e-12);
+
+    // derivative along p at alpha = 0 is grad*p
+    let p = arr1(&[1.0, 2.0]);
+    problem.move_step(&x, &p, 0.0);
+    assert_abs_diff_eq!(problem.diff(), grad.dot(&p), epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_fn_problem_hyperdual_scalar() {
+    let mut problem = FnProblem::new(2, |x: &[HDual]| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    let x = arr1(&[1.5, -0.5]);
+    let (value, grad, hess) = expected_values(x[0], x[1]);
+
+    // move_step before calculating the derivatives to check that the dual
+    // parts of the step are removed
+    let p = arr1(&[1.0, 2.0]);
+    problem.move_step(&x, &p, 0.0);
+    assert_abs_diff_eq!(problem.diff(), grad.dot(&p), epsilon = 1e-12);
+
+    problem.update_x(&x);
+    assert_abs_diff_eq!(problem.eval_real(), value, epsilon = 1e-12);
+
+    let mut output = Array::zeros(2);
+    problem.grad(&mut output);
+    let mut output_hess = Array::zeros((2, 2));
+    problem.hess(&mut output_hess);
+    for i in 0..2 {
+        assert_abs_diff_eq!(output[i], grad[i], epsilon = 1e-12);
+        for j in 0..2 {
+            assert_abs_diff_eq!(output_hess[[i, j]], hess[[i, j]], epsilon = 1e-12);
+        }
+    }
+}
+
+
+#[test]
+fn test_fn_problem_hyperdual() {
+    let mut problem = FnProblem::new(2, |x: &[HyperDual]| {
+        &x[0].powi(2)*&x[1] + x[1].sin()
+    });
+    let x = arr1(&[1.5, -0.5]);
+    let (value, grad, hess) = expected_values(x[0], x[1]);
+
+    problem.update_x(&x);
+    assert_abs_diff_eq!(problem.eval_real(), value, epsilon = 1e-12);
+
+    let mut output = Array::zeros(2);
+    problem.grad(&mut output);
+    let mut output_hess = Array::zeros((2, 2));
+    problem.hess(&mut output_hess);
+    for i in 0..2 {
+        assert_abs_diff_eq!(output[i], grad[i], epsilon = 1e-12);
+        for j in 0..2 {
+            assert_abs_diff_eq!(output_hess[[i, j]], hess[[i, j]], epsilon = 1e-12);
+        }
+    }
+
+    let p = arr1(&[1.0, 2.0]);
+    problem.move_step(&x, &p, 0.0);
+    assert_abs_diff_eq!(problem.diff(), grad.dot(&p), epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_fn_problem_minimize() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+
+    let mut problem = FnProblem::new(3, |x: &[DualScalar]| {
+        let u1 = x[0].cos()*x[1].sin() - 0.05;
+        let u2 = x[1].sin() - 0.2;
+        let u3 = x[2].powi(2) - 2.56;
+        u1.powi(2) + u2.powi(2) + u3.powi(2)
+    });
+    let sol = BFGS::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.318116, epsilon = 0.000001);
+    assert_abs_diff_eq!(sol.x[1], 0.201357, epsilon = 0.000001);
+    assert_abs_diff_eq!(sol.x[2], 1.600000, epsilon = 0.000001);
+
+    let mut problem = FnProblem::new(3, |x: &[HyperDual]| {
+        let u1 = &x[0].cos()*&x[1].sin() - 0.05;
+        let u2 = x[1].sin() - 0.2;
+        let u3 = x[2].powi(2) - 2.56;
+        u1.powi(2) + u2.powi(2) + u3.powi(2)
+    });
+    let sol = TrustNCG::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.318116, epsilon = 0.000001);
+    assert_abs_diff_eq!(sol.x[1], 0.201357, epsilon = 0.000001);
+    assert_abs_diff_eq!(sol.x[2], 1.600000, epsilon = 0.000001);
+}
@@ -0,0 +1,59 @@
+use optimization::GaussNewton;
+use optimization::problem::FnResiduals;
+use optimization::number_system::DualScalar;
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+#[test]
+fn test_gauss_newton() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut problem = FnResiduals::new(3, 3, |x: &[DualScalar]| {
+        vec![
+            x[0].cos()*x[1].sin() - 0.05,
+            x[1].sin() - 0.2,
+            x[2].powi(2) - 2.56,
+        ]
+    });
+    let sol = GaussNewton::new().minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert!(sol.trust_radius.is_some());
+    assert_abs_diff_eq!(sol.f, 0.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(sol.x[1].sin(), 0.2, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[0].cos(), 0.25, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[2].abs(), 1.6, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_gauss_newton_rosenbrock() {
+    // the first steps are limited by the trust region
+    let x0 = arr1::<f64>(&[-1.2, 1.0]);
+    let mut problem = FnResiduals::new(2, 2, |x: &[DualScalar]| {
+        vec![(x[1] - x[0].powi(2))*10.0, 1.0 - x[0]]
+    });
+    let sol = GaussNewton::new().minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_gauss_newton_rank_deficient() {
+    // the first two residuals depend on x0 + x1 only, so the jacobian is rank
+    // deficient at every point
+    let x0 = arr1::<f64>(&[0.0, 0.0, 0.0]);
+    let mut problem = FnResiduals::new(3, 3, |x: &[DualScalar]| {
+        let s = x[0] + x[1];
+        vec![s - 2.0, s.powi(2) - 4.0, x[2].exp() - 1.0]
+    });
+    let sol = GaussNewton::new().minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert!(sol.x.iter().all(|x_i| x_i.is_finite()));
+    assert_abs_diff_eq!(sol.x[0] + sol.x[1], 2.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[2], 0.0, epsilon = 1e-6);
+}
@@ -107,3 +107,375 @@ fn test_powi() {
     assert_abs_diff_eq!(hdual2.hess[[2,1]], hess21);
     assert_abs_diff_eq!(hdual2.hess[[2,2]], hess22);
 }
+
+#[test]
+fn test_sqrt() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.sqrt();
+    let (f1, f2) = (0.5/x.sqrt(), -0.25*x.powf(-1.5));
+
+    assert_abs_diff_eq!(hdual2.real, x.sqrt(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_exp() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.exp();
+    let (f1, f2) = (x.exp(), x.exp());
+
+    assert_abs_diff_eq!(hdual2.real, x.exp(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_ln() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.ln();
+    let (f1, f2) = (1.0/x, -1.0/(x*x));
+
+    assert_abs_diff_eq!(hdual2.real, x.ln(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_log10() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.log10();
+    let (f1, f2) = (1.0/(x*10.0_f64.ln()), -1.0/(x*x*10.0_f64.ln()));
+
+    assert_abs_diff_eq!(hdual2.real, x.log10(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_powf() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.powf(2.5);
+    let (f1, f2) = (2.5*x.powf(1.5), 3.75*x.powf(0.5));
+
+    assert_abs_diff_eq!(hdual2.real, x.powf(2.5), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_tan() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.tan();
+    let (f1, f2) = (1.0/x.cos().powi(2), 2.0*x.tan()/x.cos().powi(2));
+
+    assert_abs_diff_eq!(hdual2.real, x.tan(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_asin() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.asin();
+    let (f1, f2) = (1.0/(1.0 - x*x).sqrt(), x*(1.0 - x*x).powf(-1.5));
+
+    assert_abs_diff_eq!(hdual2.real, x.asin(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_acos() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.acos();
+    let (f1, f2) = (-1.0/(1.0 - x*x).sqrt(), -x*(1.0 - x*x).powf(-1.5));
+
+    assert_abs_diff_eq!(hdual2.real, x.acos(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_atan() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.atan();
+    let (f1, f2) = (1.0/(1.0 + x*x), -2.0*x/(1.0 + x*x).powi(2));
+
+    assert_abs_diff_eq!(hdual2.real, x.atan(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_sinh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.sinh();
+    let (f1, f2) = (x.cosh(), x.sinh());
+
+    assert_abs_diff_eq!(hdual2.real, x.sinh(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_cosh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.cosh();
+    let (f1, f2) = (x.sinh(), x.cosh());
+
+    assert_abs_diff_eq!(hdual2.real, x.cosh(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_tanh() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.tanh();
+    let (f1, f2) = (1.0/x.cosh().powi(2), -2.0*x.sinh()/x.cosh().powi(3));
+
+    assert_abs_diff_eq!(hdual2.real, x.tanh(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_abs() {
+    let x = -0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.abs();
+    let (f1, f2) = (-1.0, 0.0);
+
+    assert_abs_diff_eq!(hdual2.real, 0.5, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_recip() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.recip();
+    let (f1, f2) = (-1.0/(x*x), 2.0/(x*x*x));
+
+    assert_abs_diff_eq!(hdual2.real, 1.0/x, epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_cbrt() {
+    let x = 0.5_f64;
+    let grad1 = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let hess1: Array2<f64> = Array::eye(3);
+    let hdual1 = HyperDual{real: x, grad: grad1.clone(), hess: hess1.clone()};
+    let hdual2 = hdual1.cbrt();
+    let (f1, f2) = (x.powf(-2.0/3.0)/3.0, -2.0/9.0*x.powf(-5.0/3.0));
+
+    assert_abs_diff_eq!(hdual2.real, x.cbrt(), epsilon = 1e-12);
+    for i in 0..3 {
+        assert_abs_diff_eq!(hdual2.grad[i], grad1[i] * f1, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = f2 * grad1[i] * grad1[j] + f1 * hess1[[i, j]];
+            assert_abs_diff_eq!(hdual2.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_pow() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let hess_a: Array2<f64> = Array::eye(3);
+    let hess_b: Array2<f64> = Array::eye(3) * 2.0;
+    let hdual1 = HyperDual{real: a, grad: grad_a.clone(), hess: hess_a.clone()};
+    let hdual2 = HyperDual{real: b, grad: grad_b.clone(), hess: hess_b.clone()};
+    let hdual3 = hdual1.pow(&hdual2);
+    let (g_a, g_b) = (b*a.powf(b - 1.0), a.powf(b)*a.ln());
+    let g_aa = b*(b - 1.0)*a.powf(b - 2.0);
+    let g_ab = a.powf(b - 1.0) + b*a.powf(b - 1.0)*a.ln();
+    let g_bb = a.powf(b)*a.ln().powi(2);
+
+    assert_abs_diff_eq!(hdual3.real, a.powf(b), epsilon = 1e-12);
+    for i in 0..3 {
+        let grad = g_a * grad_a[i] + g_b * grad_b[i];
+        assert_abs_diff_eq!(hdual3.grad[i], grad, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = g_a * hess_a[[i, j]] + g_b * hess_b[[i, j]]
+                + g_aa * grad_a[i] * grad_a[j]
+                + g_ab * (grad_a[i] * grad_b[j] + grad_b[i] * grad_a[j])
+                + g_bb * grad_b[i] * grad_b[j];
+            assert_abs_diff_eq!(hdual3.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_atan2() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let hess_a: Array2<f64> = Array::eye(3);
+    let hess_b: Array2<f64> = Array::eye(3) * 2.0;
+    let hdual1 = HyperDual{real: a, grad: grad_a.clone(), hess: hess_a.clone()};
+    let hdual2 = HyperDual{real: b, grad: grad_b.clone(), hess: hess_b.clone()};
+    let hdual3 = hdual1.atan2(&hdual2);
+    let (g_a, g_b) = (b/(a*a + b*b), -a/(a*a + b*b));
+    let g_aa = -2.0*a*b/(a*a + b*b).powi(2);
+    let g_ab = (a*a - b*b)/(a*a + b*b).powi(2);
+    let g_bb = 2.0*a*b/(a*a + b*b).powi(2);
+
+    assert_abs_diff_eq!(hdual3.real, a.atan2(b), epsilon = 1e-12);
+    for i in 0..3 {
+        let grad = g_a * grad_a[i] + g_b * grad_b[i];
+        assert_abs_diff_eq!(hdual3.grad[i], grad, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = g_a * hess_a[[i, j]] + g_b * hess_b[[i, j]]
+                + g_aa * grad_a[i] * grad_a[j]
+                + g_ab * (grad_a[i] * grad_b[j] + grad_b[i] * grad_a[j])
+                + g_bb * grad_b[i] * grad_b[j];
+            assert_abs_diff_eq!(hdual3.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_hypot() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let grad_a = arr1::<f64>(&[3.0, 4.0, 5.0]);
+    let grad_b = arr1::<f64>(&[1.0, 2.0, 0.0]);
+    let hess_a: Array2<f64> = Array::eye(3);
+    let hess_b: Array2<f64> = Array::eye(3) * 2.0;
+    let hdual1 = HyperDual{real: a, grad: grad_a.clone(), hess: hess_a.clone()};
+    let hdual2 = HyperDual{real: b, grad: grad_b.clone(), hess: hess_b.clone()};
+    let hdual3 = hdual1.hypot(&hdual2);
+    let (g_a, g_b) = (a/(a*a + b*b).sqrt(), b/(a*a + b*b).sqrt());
+    let g_aa = b*b/(a*a + b*b).powf(1.5);
+    let g_ab = -a*b/(a*a + b*b).powf(1.5);
+    let g_bb = a*a/(a*a + b*b).powf(1.5);
+
+    assert_abs_diff_eq!(hdual3.real, (a*a + b*b).sqrt(), epsilon = 1e-12);
+    for i in 0..3 {
+        let grad = g_a * grad_a[i] + g_b * grad_b[i];
+        assert_abs_diff_eq!(hdual3.grad[i], grad, epsilon = 1e-12);
+        for j in 0..3 {
+            let hess = g_a * hess_a[[i, j]] + g_b * hess_b[[i, j]]
+                + g_aa * grad_a[i] * grad_a[j]
+                + g_ab * (grad_a[i] * grad_b[j] + grad_b[i] * grad_a[j])
+                + g_bb * grad_b[i] * grad_b[j];
+            assert_abs_diff_eq!(hdual3.hess[[i, j]], hess, epsilon = 1e-12);
+        }
+    }
+}
@@ -43,3 +43,294 @@ fn test_powi() {
     assert_abs_diff_eq!(test_dual.e2, e1);
     assert_abs_diff_eq!(test_dual.e1e2, e1e2);
 }
+
+#[test]
+fn test_sqrt() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.sqrt();
+    let re = x.sqrt();
+    let e1 = 3.0_f64 * 0.5/x.sqrt();
+    let e1e2 = 9.0_f64 * -0.25*x.powf(-1.5) + 2.0_f64 * 0.5/x.sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_exp() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.exp();
+    let re = x.exp();
+    let e1 = 3.0_f64 * x.exp();
+    let e1e2 = 9.0_f64 * x.exp() + 2.0_f64 * x.exp();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_ln() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.ln();
+    let re = x.ln();
+    let e1 = 3.0_f64 * 1.0/x;
+    let e1e2 = -9.0/(x*x) + 2.0_f64 * 1.0/x;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_log10() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.log10();
+    let re = x.log10();
+    let e1 = 3.0_f64 * 1.0/(x*10.0_f64.ln());
+    let e1e2 = -9.0/(x*x*10.0_f64.ln()) + 2.0_f64 * 1.0/(x*10.0_f64.ln());
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_powf() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.powf(2.5);
+    let re = x.powf(2.5);
+    let e1 = 3.0_f64 * 2.5*x.powf(1.5);
+    let e1e2 = 9.0_f64 * 3.75*x.powf(0.5) + 2.0_f64 * 2.5*x.powf(1.5);
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_tan() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.tan();
+    let re = x.tan();
+    let e1 = 3.0_f64 * 1.0/x.cos().powi(2);
+    let e1e2 = 9.0_f64 * 2.0*x.tan()/x.cos().powi(2) + 2.0_f64 * 1.0/x.cos().powi(2);
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_asin() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.asin();
+    let re = x.asin();
+    let e1 = 3.0_f64 * 1.0/(1.0 - x*x).sqrt();
+    let e1e2 = 9.0_f64 * x*(1.0 - x*x).powf(-1.5) + 2.0_f64 * 1.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_acos() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.acos();
+    let re = x.acos();
+    let e1 = -3.0/(1.0 - x*x).sqrt();
+    let e1e2 = 9.0_f64 * -x*(1.0 - x*x).powf(-1.5) + -2.0/(1.0 - x*x).sqrt();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_atan() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.atan();
+    let re = x.atan();
+    let e1 = 3.0_f64 * 1.0/(1.0 + x*x);
+    let e1e2 = 9.0_f64 * -2.0*x/(1.0 + x*x).powi(2) + 2.0_f64 * 1.0/(1.0 + x*x);
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_sinh() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.sinh();
+    let re = x.sinh();
+    let e1 = 3.0_f64 * x.cosh();
+    let e1e2 = 9.0_f64 * x.sinh() + 2.0_f64 * x.cosh();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_cosh() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.cosh();
+    let re = x.cosh();
+    let e1 = 3.0_f64 * x.sinh();
+    let e1e2 = 9.0_f64 * x.cosh() + 2.0_f64 * x.sinh();
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_tanh() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.tanh();
+    let re = x.tanh();
+    let e1 = 3.0_f64 * 1.0/x.cosh().powi(2);
+    let e1e2 = 9.0_f64 * -2.0*x.sinh()/x.cosh().powi(3) + 2.0_f64 * 1.0/x.cosh().powi(2);
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_abs() {
+    let x = -0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.abs();
+    let re = 0.5;
+    let e1 = -3.0_f64;
+    let e1e2 = -2.0_f64;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_recip() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.recip();
+    let re = 1.0/x;
+    let e1 = -3.0/(x*x);
+    let e1e2 = 9.0_f64 * 2.0/(x*x*x) + -2.0/(x*x);
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_cbrt() {
+    let x = 0.5_f64;
+    let a = HDual{re: x, e1: 3.0, e2: 3.0, e1e2: 2.0};
+    let test_dual = a.cbrt();
+    let re = x.cbrt();
+    let e1 = 3.0_f64 * x.powf(-2.0/3.0)/3.0;
+    let e1e2 = 9.0_f64 * -2.0/9.0*x.powf(-5.0/3.0) + 2.0_f64 * x.powf(-2.0/3.0)/3.0;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_pow() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = HDual{re: a, e1: 3.0, e2: 1.0, e1e2: 2.0};
+    let y = HDual{re: b, e1: 2.0, e2: 4.0, e1e2: 1.0};
+    let test_dual = x.pow(&y);
+    let (g_a, g_b) = (b*a.powf(b - 1.0), a.powf(b)*a.ln());
+    let g_aa = b*(b - 1.0)*a.powf(b - 2.0);
+    let g_ab = a.powf(b - 1.0) + b*a.powf(b - 1.0)*a.ln();
+    let g_bb = a.powf(b)*a.ln().powi(2);
+    let re = a.powf(b);
+    let e1 = 3.0_f64 * g_a + 2.0_f64 * g_b;
+    let e2 = 1.0_f64 * g_a + 4.0_f64 * g_b;
+    let e1e2 = 2.0_f64 * g_a + 1.0_f64 * g_b + 3.0_f64 * g_aa
+        + (12.0_f64 + 2.0_f64) * g_ab + 8.0_f64 * g_bb;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e2, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_atan2() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = HDual{re: a, e1: 3.0, e2: 1.0, e1e2: 2.0};
+    let y = HDual{re: b, e1: 2.0, e2: 4.0, e1e2: 1.0};
+    let test_dual = x.atan2(&y);
+    let (g_a, g_b) = (b/(a*a + b*b), -a/(a*a + b*b));
+    let g_aa = -2.0*a*b/(a*a + b*b).powi(2);
+    let g_ab = (a*a - b*b)/(a*a + b*b).powi(2);
+    let g_bb = 2.0*a*b/(a*a + b*b).powi(2);
+    let re = a.atan2(b);
+    let e1 = 3.0_f64 * g_a + 2.0_f64 * g_b;
+    let e2 = 1.0_f64 * g_a + 4.0_f64 * g_b;
+    let e1e2 = 2.0_f64 * g_a + 1.0_f64 * g_b + 3.0_f64 * g_aa
+        + (12.0_f64 + 2.0_f64) * g_ab + 8.0_f64 * g_bb;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e2, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
+
+#[test]
+fn test_hypot() {
+    let (a, b) = (0.5_f64, 1.5_f64);
+    let x = HDual{re: a, e1: 3.0, e2: 1.0, e1e2: 2.0};
+    let y = HDual{re: b, e1: 2.0, e2: 4.0, e1e2: 1.0};
+    let test_dual = x.hypot(&y);
+    let (g_a, g_b) = (a/(a*a + b*b).sqrt(), b/(a*a + b*b).sqrt());
+    let g_aa = b*b/(a*a + b*b).powf(1.5);
+    let g_ab = -a*b/(a*a + b*b).powf(1.5);
+    let g_bb = a*a/(a*a + b*b).powf(1.5);
+    let re = (a*a + b*b).sqrt();
+    let e1 = 3.0_f64 * g_a + 2.0_f64 * g_b;
+    let e2 = 1.0_f64 * g_a + 4.0_f64 * g_b;
+    let e1e2 = 2.0_f64 * g_a + 1.0_f64 * g_b + 3.0_f64 * g_aa
+        + (12.0_f64 + 2.0_f64) * g_ab + 8.0_f64 * g_bb;
+
+    assert_abs_diff_eq!(test_dual.re, re, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1, e1, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e2, e2, epsilon = 1e-12);
+    assert_abs_diff_eq!(test_dual.e1e2, e1e2, epsilon = 1e-12);
+}
@@ -0,0 +1,227 @@
+use optimization::{InteriorPoint, Status};
+use optimization::problem::{Objective, Gradient, Hessian, FnProblem, FnConstraints, Constraints};
+use optimization::number_system::{DualScalar, HyperDualScalar};
+use optimization::observer::{Iteration, Control};
+use ndarray::{arr1, Array1, Array2};
+use approx::assert_abs_diff_eq;
+
+
+fn no_constraints<T>(_: &[T]) -> Vec<T> {
+    vec![]
+}
+
+
+// Wrapper that counts the evaluations of the objective function.
+struct Counted<P> {
+    problem: P,
+    f_calls: u32,
+}
+
+
+impl<P: Objective> Objective for Counted<P> {
+    fn eval(&mut self) {
+        self.f_calls += 1;
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.f_calls += 1;
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Gradient> Gradient for Counted<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.problem.grad(output);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.problem.diff()
+    }
+}
+
+
+impl<P: Hessian> Hessian for Counted<P> {
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        self.problem.hess(output);
+    }
+}
+
+
+#[test]
+fn test_constraint_hessians() {
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2)*x[1], x[1].sin()],
+        |x: &[DualScalar]| vec![x[0]*x[1]],
+    );
+    let x = arr1(&[1.5, 0.5]);
+    let mut hess = Array2::zeros((2, 2));
+
+    // 2*hess(x0^2*x1) - hess(sin(x1))
+    constraints.equality_hessian(&x, &arr1(&[2.0, -1.0]), &mut hess);
+    assert_abs_diff_eq!(hess[[0, 0]], 4.0*x[1], epsilon = 1e-8);
+    assert_abs_diff_eq!(hess[[0, 1]], 4.0*x[0], epsilon = 1e-8);
+    assert_abs_diff_eq!(hess[[1, 0]], 4.0*x[0], epsilon = 1e-8);
+    assert_abs_diff_eq!(hess[[1, 1]], x[1].sin(), epsilon = 1e-8);
+
+    constraints.inequality_hessian(&x, &arr1(&[3.0]), &mut hess);
+    assert_abs_diff_eq!(hess[[0, 0]], 0.0, epsilon = 1e-8);
+    assert_abs_diff_eq!(hess[[0, 1]], 3.0, epsilon = 1e-8);
+}
+
+
+#[test]
+fn test_bounds() {
+    // the unconstrained minimum (2, -1) is outside of the box [0, 1]^2
+    let mut problem = FnProblem::new(2, |x: &[HyperDualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] + 1.0).powi(2) + x[0]*x[1]
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![-x[0], x[0] - 1.0, -x[1], x[1] - 1.0],
+    );
+    let sol = InteriorPoint::new().minimize(&arr1(&[0.5, 0.5]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-7);
+    assert_abs_diff_eq!(sol.x[1], 0.0, epsilon = 1e-7);
+    // grad(f) at (1, 0) is (-2, 3)
+    let z = sol.inequality_multipliers.unwrap();
+    assert_abs_diff_eq!(z[1], 2.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(z[2], 3.0, epsilon = 1e-6);
+    assert!(z[0] < 1e-6 && z[3] < 1e-6);
+    assert!(sol.kkt_residual.unwrap() <= 1e-8);
+    assert!(sol.f_hess_evals > 0);
+}
+
+
+#[test]
+fn test_nonlinear_constraints() {
+    let mut problem = FnProblem::new(2, |x: &[HyperDualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0].powi(2) - x[1], x[0] + x[1] - 2.0],
+    );
+    let sol = InteriorPoint::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+    let z = sol.inequality_multipliers.unwrap();
+    assert_abs_diff_eq!(z[0], 2.0/3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(z[1], 2.0/3.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_hock_schittkowski_71() {
+    let mut problem = FnProblem::new(4, |x: &[HyperDualScalar]| {
+        x[0]*x[3]*(x[0] + x[1] + x[2]) + x[2]
+    });
+    let mut constraints = FnConstraints::new(
+        4,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) + x[2].powi(2) + x[3].powi(2) - 40.0],
+        |x: &[DualScalar]| {
+            let mut g = vec![-x[0]*x[1]*x[2]*x[3] + 25.0];
+            for x_i in x.iter() {
+                g.push(-*x_i + 1.0);
+                g.push(*x_i - 5.0);
+            }
+            g
+        },
+    );
+    let sol = InteriorPoint::new().minimize(&arr1(&[1.0, 5.0, 5.0, 1.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.f, 17.0140173, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 4.7429994, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[2], 3.8211503, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[3], 1.3794082, epsilon = 1e-5);
+    // x0 = 1 is the lower bound, so it is approached from the inside
+    assert!(sol.x[0] >= 1.0 - 1e-8);
+    assert!(sol.constraint_violation.unwrap() <= 1e-8);
+}
+
+
+#[test]
+fn test_nonconvex() {
+    // the lagrangian is not convex at the start, so the hessian is shifted
+    let mut problem = FnProblem::new(2, |x: &[HyperDualScalar]| -x[0]*x[1]);
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0] + x[1]*2.0 - 4.0, -x[0], -x[1]],
+    );
+    let sol = InteriorPoint::new().minimize(&arr1(&[0.1, 0.1]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 2.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_observer() {
+    let mut problem = FnProblem::new(1, |x: &[HyperDualScalar]| -x[0].cos() - x[0]*2.0);
+    let mut constraints = FnConstraints::new(
+        1,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0] - 0.5, -x[0] - 1.0],
+    );
+    let mut inside = true;
+    let mut observer = |iteration: &Iteration| {
+        // the iterates never cross the limits
+        inside &= iteration.x_k[0] <= 0.5 && iteration.x_k[0] >= -1.0;
+        if iteration.k < 3 { Control::Continue } else { Control::Stop }
+    };
+    let sol = InteriorPoint::new().minimize_with_observer(&arr1(&[0.0]), &mut problem,
+                                                          &mut constraints, &mut observer);
+
+    assert!(inside);
+    assert_eq!(sol.status, Status::UserAbort);
+    assert_eq!(sol.iter_num, 3);
+}
+
+
+#[test]
+fn test_evaluations() {
+    let mut problem = Counted {
+        problem: FnProblem::new(2, |x: &[HyperDualScalar]| {
+            (x[0] - 2.0).powi(2) + (x[1] + 1.0).powi(2) + x[0]*x[1]
+        }),
+        f_calls: 0,
+    };
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![-x[0], x[0] - 1.0, -x[1], x[1] - 1.0],
+    );
+    let mut grad_norm = f64::NAN;
+    let mut observer = |iteration: &Iteration| {
+        grad_norm = iteration.grad_norm;
+        Control::Continue
+    };
+    let sol = InteriorPoint::new().minimize_with_observer(&arr1(&[0.5, 0.5]), &mut problem,
+                                                          &mut constraints, &mut observer);
+
+    assert!(sol.success);
+    assert_eq!(sol.f_evals, problem.f_calls);
+    // the observer gets the gradient of the lagrangian at the new point, so
+    // the last one is the gradient of the solution
+    assert_eq!(grad_norm, sol.grad_norm);
+}
@@ -0,0 +1,121 @@
+use optimization::LevenbergMarquardt;
+use optimization::problem::{LeastSquares, FnResiduals};
+use optimization::number_system::DualScalar;
+use ndarray::{Array1, Array2, Array, arr1};
+use approx::assert_abs_diff_eq;
+
+
+// Rosenbrock function written as a least-squares problem with hand-written
+// residuals and jacobian
+pub struct Rosenbrock;
+
+
+impl LeastSquares for Rosenbrock {
+    fn num_residuals(&self) -> usize {
+        2
+    }
+
+    fn residuals(&mut self, x: &Array1<f64>, output: &mut Array1<f64>) {
+        output[0] = 10.0*(x[1] - x[0].powi(2));
+        output[1] = 1.0 - x[0];
+    }
+
+    fn jacobian(&mut self, x: &Array1<f64>, output: &mut Array2<f64>) {
+        output[[0, 0]] = -20.0*x[0];
+        output[[0, 1]] = 10.0;
+        output[[1, 0]] = -1.0;
+        output[[1, 1]] = 0.0;
+    }
+}
+
+
+#[test]
+fn test_levenberg_marquardt() {
+    let x0 = arr1::<f64>(&[-1.2, 1.0]);
+    let mut min = LevenbergMarquardt::new();
+    let sol = min.minimize(&x0, &mut Rosenbrock);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+
+    // residuals and jacobian at the solution
+    let residuals = sol.residuals.unwrap();
+    let jacobian = sol.jacobian.unwrap();
+    assert_abs_diff_eq!(residuals[0], 0.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(residuals[1], 0.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(jacobian[[0, 0]], -20.0, epsilon = 1e-4);
+    assert_abs_diff_eq!(jacobian[[0, 1]], 10.0);
+}
+
+
+#[test]
+fn test_geodesic_acceleration() {
+    let x0 = arr1::<f64>(&[-1.2, 1.0]);
+    let mut min = LevenbergMarquardt::new();
+    min.geodesic_acceleration = true;
+    let sol = min.minimize(&x0, &mut Rosenbrock);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_badly_scaled() {
+    // the columns of the jacobian differ by eight orders of magnitude, the
+    // damping must follow the scale of each variable
+    let mut problem = FnResiduals::new(2, 2, |x: &[DualScalar]| {
+        vec![(x[0] - 1.0)*1e4, (x[1] - 1.0)*1e-4 + (x[0] - 1.0).powi(2)]
+    });
+    let sol = LevenbergMarquardt::new().minimize(&arr1(&[0.0, 0.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-8);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_curve_fitting() {
+    // fit y = a*exp(b*t) to data generated with a = 2, b = -0.5 plus a small
+    // perturbation, so the residuals at the solution are not zero
+    let t: Vec<f64> = (0..20).map(|i| 0.25*f64::from(i)).collect();
+    let y: Vec<f64> = t.iter().enumerate()
+        .map(|(i, t_i)| 2.0*(-0.5*t_i).exp() + 0.01*(-1.0_f64).powi(i as i32))
+        .collect();
+    let m = t.len();
+    let mut problem = FnResiduals::new(2, m, |x: &[DualScalar]| {
+        (0..m).map(|i| (x[1]*t[i]).exp()*x[0] - y[i]).collect()
+    });
+
+    let x0 = arr1::<f64>(&[1.0, 0.0]);
+    let sol = LevenbergMarquardt::new().minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 2.0, epsilon = 1e-2);
+    assert_abs_diff_eq!(sol.x[1], -0.5, epsilon = 1e-2);
+    let residuals = sol.residuals.unwrap();
+    assert_eq!(residuals.len(), m);
+    assert_abs_diff_eq!(sol.f, 0.5*residuals.dot(&residuals));
+    assert!(sol.f > 0.0);
+}
+
+
+#[test]
+fn test_fn_residuals_jacobian() {
+    let mut problem = FnResiduals::new(2, 2, |x: &[DualScalar]| {
+        vec![(x[1] - x[0].powi(2))*10.0, 1.0 - x[0]]
+    });
+    let x = arr1::<f64>(&[0.5, 2.0]);
+    let mut jacobian = Array::zeros((2, 2));
+    let mut expected = Array::zeros((2, 2));
+    problem.jacobian(&x, &mut jacobian);
+    Rosenbrock.jacobian(&x, &mut expected);
+    for i in 0..2 {
+        for j in 0..2 {
+            assert_abs_diff_eq!(jacobian[[i, j]], expected[[i, j]]);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use optimization::{BFGS, LBFGS, NCG, NewtonCG, SteepestDescent, Status};
+use optimization::line_search::{LineSearch, LineSearchError, StepValues};
+use optimization::line_search::{StrongWolfe, MoreThuente, HagerZhang, Backtracking};
+use optimization::number_system::{Dual, DualScalar};
+use optimization::problem::{FnProblem, Objective, Gradient};
+use ndarray::{Array1, arr1};
+use approx::assert_abs_diff_eq;
+
+
+fn rosenbrock(x: &[DualScalar]) -> DualScalar {
+    (1.0 - x[0]).powi(2) + (x[1] - x[0].powi(2)).powi(2)*100.0
+}
+
+
+// Checks that the step found by the line search satisfies the Wolfe conditions
+// along the steepest descent direction of the Rosenbrock function
+fn check_wolfe<L: LineSearch>(line_search: &mut L, c1: f64, c2: f64) {
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let x_k = arr1(&[-1.2, 1.0]);
+    let mut f_k = Dual::new(2);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+    let p_k: Array1<f64> = -&f_k.du;
+    let dphi_0 = f_k.du.dot(&p_k);
+
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    let alpha = line_search.find_alpha(step, &mut problem).unwrap();
+    assert!(alpha > 0.0);
+
+    problem.move_step(&x_k, &p_k, alpha);
+    let phi = problem.eval_real();
+    let dphi = problem.diff();
+    assert!(phi <= f_k.re + c1*alpha*dphi_0);
+    assert!(dphi.abs() <= -c2*dphi_0);
+    assert!(line_search.f_evals() > 0);
+    assert_eq!(line_search.f_evals(), line_search.f_grad_evals());
+}
+
+
+#[test]
+fn test_strong_wolfe_conditions() {
+    check_wolfe(&mut StrongWolfe::new(), 1e-4, 0.9);
+}
+
+
+#[test]
+fn test_more_thuente_conditions() {
+    let mut line_search = MoreThuente::new();
+    line_search.c2 = 0.1;
+    check_wolfe(&mut line_search, 1e-4, 0.1);
+}
+
+
+#[test]
+fn test_hager_zhang_conditions() {
+    // without the approximate Wolfe conditions the step satisfies the Wolfe
+    // conditions
+    let mut line_search = HagerZhang::new();
+    line_search.approximate_wolfe = false;
+    line_search.sigma = 0.1;
+    check_wolfe(&mut line_search, 0.1, 0.1);
+}
+
+
+#[test]
+fn test_exact_quadratic_step() {
+    // the minimum of the quadratic along p is at alpha = 1
+    let quadratic = |x: &[DualScalar]| {
+        (x[0] - 10.0).powi(2) + (x[1] - 2.0).powi(2) + (x[2] + 5.0).powi(2)
+    };
+    let x_k = arr1(&[0.0, 0.0, 0.0]);
+    let p_k = arr1(&[10.0, 2.0, -5.0]);
+    let mut f_k = Dual::new(3);
+    let mut problem = FnProblem::new(3, quadratic);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+
+    let mut more_thuente = MoreThuente::new();
+    more_thuente.c2 = 0.1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 0.3};
+    assert_abs_diff_eq!(more_thuente.find_alpha(step, &mut problem).unwrap(), 1.0, epsilon = 1e-8);
+
+    let mut hager_zhang = HagerZhang::new();
+    hager_zhang.sigma = 0.1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 0.3};
+    let alpha = hager_zhang.find_alpha(step, &mut problem).unwrap();
+    // only the weak Wolfe conditions are satisfied, they hold for this range
+    assert!((0.9..=1.8).contains(&alpha));
+}
+
+
+#[test]
+fn test_not_descent_direction() {
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let x_k = arr1(&[-1.2, 1.0]);
+    let mut f_k = Dual::new(2);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+    let p_k = f_k.du.clone();
+
+    let error = Err(LineSearchError::NotDescentDirection);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(StrongWolfe::new().find_alpha(step, &mut problem), error);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(MoreThuente::new().find_alpha(step, &mut problem), error);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(HagerZhang::new().find_alpha(step, &mut problem), error);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(Backtracking::new().find_alpha(step, &mut problem), error);
+}
+
+
+#[test]
+fn test_solvers_with_more_thuente() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnProblem::new(2, rosenbrock);
+
+    let sol = BFGS::with_line_search(MoreThuente::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+
+    let sol = LBFGS::with_line_search(MoreThuente::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+
+    let mut line_search = MoreThuente::new();
+    line_search.c2 = 0.1;
+    let sol = NCG::with_line_search(line_search).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+
+    let sol = NewtonCG::with_line_search(MoreThuente::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_solvers_with_hager_zhang() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnProblem::new(2, rosenbrock);
+
+    let sol = BFGS::with_line_search(HagerZhang::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+
+    let sol = LBFGS::with_line_search(HagerZhang::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+
+    let sol = NewtonCG::with_line_search(HagerZhang::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_steepest_descent_with_line_search() {
+    // a simple quadratic since steepest descent is too slow for Rosenbrock
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 3.0).powi(2) + (x[1] + 1.0).powi(2)*4.0
+    });
+    let x0 = arr1(&[0.0, 0.0]);
+
+    let sol = SteepestDescent::with_line_search(MoreThuente::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], -1.0, epsilon = 1e-5);
+
+    let sol = SteepestDescent::with_line_search(HagerZhang::new()).minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], -1.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_line_search_evals_counted() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let mut min = BFGS::with_line_search(HagerZhang::new());
+    let sol = min.minimize(&x0, &mut problem);
+    // one evaluation at the start and one after each step plus the line search
+    // evaluations
+    assert_eq!(sol.f_evals, sol.iter_num + min.line_search.f_evals());
+}
+
+
+// Quadratic problem that counts the calls to `diff`
+struct CountingProblem {
+    x: Vec<DualScalar>,
+    value: DualScalar,
+    diff_calls: u32,
+}
+
+
+impl Objective for CountingProblem {
+    fn eval(&mut self) {
+        self.value = (self.x[0] - 3.0).powi(2) + (self.x[1] + 1.0).powi(2)*4.0;
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i] = DualScalar{re: x[i], du: 0.0};
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = DualScalar{re: alpha, du: 1.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+
+impl Gradient for CountingProblem {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].du = 1.0;
+            self.eval();
+            output[i] = self.value.du;
+            self.x[i].du = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.diff_calls += 1;
+        self.eval();
+        self.value.du
+    }
+}
+
+
+#[test]
+fn test_backtracking_armijo() {
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let x_k = arr1(&[-1.2, 1.0]);
+    let mut f_k = Dual::new(2);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+    let p_k: Array1<f64> = -&f_k.du;
+    let dphi_0 = f_k.du.dot(&p_k);
+
+    let mut line_search = Backtracking::new();
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    let alpha = line_search.find_alpha(step, &mut problem).unwrap();
+    // the unit step is too long so the step is reduced
+    assert!(alpha > 0.0 && alpha < 1.0);
+    problem.move_step(&x_k, &p_k, alpha);
+    assert!(problem.eval_real() <= f_k.re + line_search.c1*alpha*dphi_0);
+    assert!(line_search.f_evals() > 1);
+    assert_eq!(line_search.f_grad_evals(), 0);
+}
+
+
+#[test]
+fn test_backtracking_exact_quadratic_step() {
+    // the quadratic interpolation is exact, so the minimum along the
+    // direction is found after one reduction
+    let mut problem = FnProblem::new(1, |x: &[DualScalar]| (x[0] - 1.0).powi(2));
+    let x_k = arr1(&[0.0]);
+    let p_k = arr1(&[4.0]);
+    let mut f_k = Dual::new(1);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+
+    let mut line_search = Backtracking::new();
+    line_search.rho_lo = 0.01;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_abs_diff_eq!(line_search.find_alpha(step, &mut problem).unwrap(), 0.25, epsilon = 1e-12);
+    assert_eq!(line_search.f_evals(), 2);
+}
+
+
+#[test]
+fn test_steepest_descent_with_backtracking() {
+    let mut problem = CountingProblem {
+        x: vec![DualScalar::new(); 2],
+        value: DualScalar::new(),
+        diff_calls: 0,
+    };
+    let x0 = arr1(&[0.0, 0.0]);
+
+    let mut min = SteepestDescent::with_line_search(Backtracking::new());
+    let sol = min.minimize(&x0, &mut problem);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], -1.0, epsilon = 1e-5);
+    assert_eq!(problem.diff_calls, 0);
+}
+
+
+#[test]
+fn test_non_finite_phi() {
+    // the function is only defined for x < 1 (NaN beyond it) and the minimum
+    // is at x = 0.5, the line searches step back from the NaN values
+    let x_k = arr1(&[0.0]);
+    let p_k = arr1(&[1.0]);
+    let mut f_k = Dual::new(1);
+    let mut problem = FnProblem::new(1, |x: &[DualScalar]| {
+        -(1.0 - x[0]).ln() - x[0]*2.0
+    });
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 10.0};
+    let alpha = StrongWolfe::new().find_alpha(step, &mut problem).unwrap();
+    assert!(alpha > 0.0 && alpha < 1.0);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 10.0};
+    let alpha = MoreThuente::new().find_alpha(step, &mut problem).unwrap();
+    assert!(alpha > 0.0 && alpha < 1.0);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 10.0};
+    let alpha = HagerZhang::new().find_alpha(step, &mut problem).unwrap();
+    assert!(alpha > 0.0 && alpha < 1.0);
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 10.0};
+    let alpha = Backtracking::new().find_alpha(step, &mut problem).unwrap();
+    assert!(alpha > 0.0 && alpha < 1.0);
+}
+
+
+#[test]
+fn test_max_iterations() {
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let x_k = arr1(&[-1.2, 1.0]);
+    let mut f_k = Dual::new(2);
+    problem.update_x(&x_k);
+    f_k.re = problem.eval_real();
+    problem.grad(&mut f_k.du);
+    let p_k: Array1<f64> = -&f_k.du;
+    let error = Err(LineSearchError::MaxIterations);
+
+    let mut strong_wolfe = StrongWolfe::new();
+    strong_wolfe.i_max = 1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(strong_wolfe.find_alpha(step, &mut problem), error);
+
+    let mut more_thuente = MoreThuente::new();
+    more_thuente.i_max = 1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(more_thuente.find_alpha(step, &mut problem), error);
+
+    let mut hager_zhang = HagerZhang::new();
+    hager_zhang.i_max = 1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(hager_zhang.find_alpha(step, &mut problem), error);
+
+    let mut backtracking = Backtracking::new();
+    backtracking.i_max = 1;
+    let step = StepValues{x_k: &x_k, f_k: &f_k, p_k: &p_k, alpha_1: 1.0};
+    assert_eq!(backtracking.find_alpha(step, &mut problem), error);
+}
+
+
+#[test]
+fn test_failure_status() {
+    // a line search that can't do a single evaluation always fails, the
+    // solver restarts along the steepest descent direction once and then stops
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let mut line_search = StrongWolfe::new();
+    line_search.i_max = 1;
+    let mut min = BFGS::with_line_search(line_search);
+    let sol = min.minimize(&x0, &mut problem);
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::LineSearchFailure(LineSearchError::MaxIterations));
+    assert_eq!(sol.iter_num, 1);
+}
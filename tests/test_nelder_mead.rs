@@ -0,0 +1,138 @@
+use optimization::{NelderMead, Status};
+use optimization::observer::{Iteration, Control};
+use optimization::problem::Objective;
+use ndarray::{Array1, arr1, arr2};
+use approx::assert_abs_diff_eq;
+
+
+// Objective function evaluated with plain f64 values, like a function coming
+// from an external library that can't use dual numbers.
+pub struct ExternalProblem {
+    x: Array1<f64>,
+    value: f64,
+}
+
+
+impl ExternalProblem {
+    fn new(n: usize) -> ExternalProblem {
+        ExternalProblem {
+            x: Array1::zeros(n),
+            value: 0.0,
+        }
+    }
+}
+
+
+// Extended Rosenbrock function
+impl Objective for ExternalProblem {
+    fn eval(&mut self) {
+        let x = &self.x;
+        self.value = 0.0;
+        for i in 0..x.len() - 1 {
+            self.value += 100.0*(x[i + 1] - x[i].powi(2)).powi(2) + (1.0 - x[i]).powi(2);
+        }
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+    }
+}
+
+
+#[test]
+fn test_rosenbrock() {
+    let mut problem = ExternalProblem::new(2);
+    let mut min = NelderMead::new();
+    let sol = min.minimize(&arr1(&[-1.2, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::XTolerance);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, 0.0, epsilon = 1e-12);
+    assert!(sol.grad_norm.is_nan());
+    assert!(sol.f_evals > sol.iter_num);
+    assert_eq!(sol.f_grad_evals, 0);
+}
+
+
+#[test]
+fn test_adaptive_high_dimension() {
+    // the adaptive coefficients need fewer iterations than the standard ones
+    let n = 8;
+    let mut problem = ExternalProblem::new(n);
+    let x0 = Array1::zeros(n);
+    let mut min = NelderMead::new();
+    min.adaptive = true;
+    min.i_max = 100000;
+    let sol = min.minimize(&x0, &mut problem);
+    min.adaptive = false;
+    let standard = min.minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    for i in 0..n {
+        assert_abs_diff_eq!(sol.x[i], 1.0, epsilon = 1e-6);
+    }
+    assert!(sol.iter_num < standard.iter_num);
+}
+
+
+#[test]
+fn test_initial_simplex() {
+    let mut problem = ExternalProblem::new(2);
+    let mut min = NelderMead::new();
+    min.initial_simplex = Some(arr2(&[[2.0, 2.0], [2.5, 2.0], [2.0, 2.5]]));
+    let sol = min.minimize(&arr1(&[2.0, 2.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_tolerances() {
+    let mut problem = ExternalProblem::new(2);
+    let mut min = NelderMead::new();
+    min.xtol = 1e-2;
+    min.ftol = 1e-2;
+    let loose = min.minimize(&arr1(&[-1.2, 1.0]), &mut problem);
+    min.xtol = 1e-10;
+    min.ftol = 1e-14;
+    let tight = min.minimize(&arr1(&[-1.2, 1.0]), &mut problem);
+
+    assert!(loose.success);
+    assert!(tight.success);
+    assert!(loose.iter_num < tight.iter_num);
+    assert!(tight.f <= loose.f);
+}
+
+
+#[test]
+fn test_user_abort() {
+    let mut problem = ExternalProblem::new(2);
+    let mut min = NelderMead::new();
+    let mut observer = |iteration: &Iteration| {
+        assert!(iteration.grad_norm.is_nan());
+        if iteration.k < 10 {
+            Control::Continue
+        }
+        else {
+            Control::Stop
+        }
+    };
+    let sol = min.minimize_with_observer(&arr1(&[-1.2, 1.0]), &mut problem, &mut observer);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::UserAbort);
+    assert_eq!(sol.iter_num, 10);
+}
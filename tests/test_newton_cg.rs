@@ -0,0 +1,148 @@
+use optimization::NewtonCG;
+use optimization::number_system::{DualScalar, HyperDualScalar as HDual, HyperDual};
+use optimization::problem::{FnProblem, TapeProblem, Objective, Gradient, HessianVector, Hessian};
+use ndarray::{Array1, Array2, Array, arr1};
+use approx::assert_abs_diff_eq;
+
+
+// Wrapper that counts the gradient evaluations, including the ones of the
+// default hessian-vector product.
+struct Counted<P> {
+    problem: P,
+    grad_calls: u32,
+}
+
+
+impl<P: Objective> Objective for Counted<P> {
+    fn eval(&mut self) {
+        self.problem.eval();
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.problem.eval_real()
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.problem.update_x(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.problem.move_step(x, p, alpha);
+    }
+}
+
+
+impl<P: Gradient> Gradient for Counted<P> {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        self.grad_calls += 1;
+        self.problem.grad(output);
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.grad_calls += 1;
+        self.problem.diff()
+    }
+}
+
+
+impl<P: Objective + Gradient> HessianVector for Counted<P> {}
+
+
+#[test]
+fn test_newton_cg() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = NewtonCG::new();
+    let mut problem = FnProblem::new(3, |x: &[HDual]| {
+        let u1 = x[0].cos()*x[1].sin() - 0.05;
+        let u2 = x[1].sin() - 0.2;
+        let u3 = x[2].powi(2) - 2.56;
+        u1.powi(2) + u2.powi(2) + u3.powi(2)
+    });
+    let sol = min.minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert!(sol.f_hess_evals > 0);
+    assert_abs_diff_eq!(sol.f, 0.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(sol.x[1].sin(), 0.2, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[0].cos(), 0.25, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[2].abs(), 1.6, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_newton_cg_rosenbrock() {
+    // extended rosenbrock function, the hessian-vector products are
+    // calculated in forward over reverse mode
+    let n = 50;
+    let mut problem = TapeProblem::new(n, |x| {
+        let mut f = x[0]*0.0;
+        for i in 0..x.len() - 1 {
+            f = f + (x[i+1] - x[i].powi(2)).powi(2)*100.0 + (1.0 - x[i]).powi(2);
+        }
+        f
+    });
+    let x0: Array1<f64> = Array::zeros(n);
+
+    let sol = NewtonCG::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    for i in 0..n {
+        assert_abs_diff_eq!(sol.x[i], 1.0, epsilon = 1e-4);
+    }
+}
+
+
+#[test]
+fn test_hess_vec() {
+    // f(x, y) = x^2*y + sin(y)
+    let x = arr1(&[1.5, -0.5]);
+    let v = arr1(&[1.0, 2.0]);
+
+    let mut problem = FnProblem::new(2, |x: &[HyperDual]| {
+        &x[0].powi(2)*&x[1] + x[1].sin()
+    });
+    let mut hess: Array2<f64> = Array::zeros((2, 2));
+    problem.update_x(&x);
+    problem.hess(&mut hess);
+    let expected = hess.dot(&v);
+
+    let mut output = Array::zeros(2);
+    problem.hess_vec(&x, &v, &mut output);
+    assert_abs_diff_eq!(output[0], expected[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(output[1], expected[1], epsilon = 1e-12);
+
+    let mut problem = FnProblem::new(2, |x: &[HDual]| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    problem.hess_vec(&x, &v, &mut output);
+    assert_abs_diff_eq!(output[0], expected[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(output[1], expected[1], epsilon = 1e-12);
+
+    // default implementation
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    problem.hess_vec(&x, &v, &mut output);
+    assert_abs_diff_eq!(output[0], expected[0], epsilon = 1e-6);
+    assert_abs_diff_eq!(output[1], expected[1], epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_grad_evals() {
+    // each product of the default implementation costs two gradients
+    let mut problem = Counted {
+        problem: FnProblem::new(3, |x: &[DualScalar]| {
+            let u1 = x[0].cos()*x[1].sin() - 0.05;
+            let u2 = x[1].sin() - 0.2;
+            let u3 = x[2].powi(2) - 2.56;
+            u1.powi(2) + u2.powi(2) + u3.powi(2)
+        }),
+        grad_calls: 0,
+    };
+    let sol = NewtonCG::new().minimize(&arr1(&[1.0, 1.0, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert!(sol.f_hess_evals > 0);
+    assert_eq!(sol.f_grad_evals, problem.grad_calls);
+    assert!(sol.f_grad_evals >= 2*sol.f_hess_evals);
+}
@@ -0,0 +1,116 @@
+use optimization::{BFGS, NCG, Status};
+use optimization::observer::{Iteration, Control};
+use optimization::number_system::DualScalar;
+use optimization::problem::{Objective, Gradient};
+use ndarray::{Array1, arr1};
+
+// Objective function. In this case it only contains a vector of DualScalars
+// representing the variables. Note that it could also contain a vector of
+// error functions in a least-squares problem.
+pub struct ProblemObjective {
+    x: Vec<DualScalar>,
+    value: DualScalar,
+}
+
+
+// Implementation of the Objective trait. The method `eval` is the method that
+// actually evaluates the objective function. The methods `eval_real`, `grad`,
+// and `diff` will call `eval` in order to evaluate the function and then pick
+// the real or dual value of the result.
+impl Objective for ProblemObjective {
+    fn eval(&mut self) {
+        let x1 = &self.x[0];
+        let x2 = &self.x[1];
+        let x3 = &self.x[2];
+
+        let u1 = x1.cos()*x2.sin() - 0.05;
+        let u2 = x2.sin() - 0.2;
+        let u3 = x3.powi(2) - 2.56;
+
+        self.value = u1.powi(2) + u2.powi(2) + u3.powi(2)
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].du = 0.0; // make sure we are removing any dual part
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = DualScalar{re: alpha, du: 1.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+// Implementation of the gradient trait. Note that both `grad` and `diff` call
+// `eval` in order to obtain the corresponding derivatives.
+impl Gradient for ProblemObjective {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].du = 1.0;
+            self.eval();
+            output[i] = self.value.du;
+            self.x[i].du = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.du
+    }
+}
+
+
+#[test]
+fn test_observer_history() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = BFGS::new();
+    let mut problem = ProblemObjective{x: vec![DualScalar::new(); 3], value: DualScalar::new()};
+
+    let mut iterations = Vec::new();
+    let mut values = Vec::new();
+    let mut observer = |iteration: &Iteration| {
+        iterations.push(iteration.k);
+        values.push(iteration.f_k);
+        assert!(iteration.step_length > 0.0);
+        assert!(iteration.trust_radius.is_none());
+        Control::Continue
+    };
+    let sol = min.minimize_with_observer(&x0, &mut problem, &mut observer);
+
+    assert!(sol.success);
+    // the observer is called once for every completed iteration
+    assert_eq!(iterations.len() as u32, sol.iter_num - 1);
+    assert_eq!(iterations[0], 1);
+    assert_eq!(*values.last().unwrap(), sol.f);
+}
+
+
+#[test]
+fn test_observer_stop() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = NCG::new();
+    let mut problem = ProblemObjective{x: vec![DualScalar::new(); 3], value: DualScalar::new()};
+
+    let mut observer = |iteration: &Iteration| {
+        if iteration.k == 3 {
+            Control::Stop
+        }
+        else {
+            Control::Continue
+        }
+    };
+    let sol = min.minimize_with_observer(&x0, &mut problem, &mut observer);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::UserAbort);
+    assert_eq!(sol.iter_num, 3);
+}
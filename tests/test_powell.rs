@@ -0,0 +1,128 @@
+use optimization::{Powell, Status};
+use optimization::problem::Objective;
+use ndarray::{Array1, arr1, arr2};
+use approx::assert_abs_diff_eq;
+
+
+// Objective function evaluated with plain f64 values, like a function coming
+// from an external library that can't use dual numbers.
+pub struct ExternalProblem<F> {
+    x: Array1<f64>,
+    value: f64,
+    func: F,
+}
+
+
+impl<F: Fn(&Array1<f64>) -> f64> ExternalProblem<F> {
+    fn new(n: usize, func: F) -> ExternalProblem<F> {
+        ExternalProblem {
+            x: Array1::zeros(n),
+            value: 0.0,
+            func,
+        }
+    }
+}
+
+
+impl<F: Fn(&Array1<f64>) -> f64> Objective for ExternalProblem<F> {
+    fn eval(&mut self) {
+        self.value = (self.func)(&self.x);
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        self.x.assign(x);
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        self.x.assign(&(x + &(alpha*p)));
+    }
+}
+
+
+fn rosenbrock(x: &Array1<f64>) -> f64 {
+    let mut value = 0.0;
+    for i in 0..x.len() - 1 {
+        value += 100.0*(x[i + 1] - x[i].powi(2)).powi(2) + (1.0 - x[i]).powi(2);
+    }
+    value
+}
+
+
+#[test]
+fn test_rosenbrock() {
+    let mut problem = ExternalProblem::new(2, rosenbrock);
+    let mut min = Powell::new();
+    let sol = min.minimize(&arr1(&[-1.2, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::FTolerance);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-5);
+    assert!(sol.grad_norm.is_nan());
+    assert_eq!(sol.f_grad_evals, 0);
+}
+
+
+#[test]
+fn test_rosenbrock_4d() {
+    let mut problem = ExternalProblem::new(4, rosenbrock);
+    let mut min = Powell::new();
+    let sol = min.minimize(&arr1(&[-1.2, 1.0, -1.2, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    for i in 0..4 {
+        assert_abs_diff_eq!(sol.x[i], 1.0, epsilon = 1e-4);
+    }
+}
+
+
+#[test]
+fn test_quadratic() {
+    // the directions become conjugate, so a quadratic function is minimized
+    // in a few iterations
+    let quadratic = |x: &Array1<f64>| {
+        (x[0] - 1.0).powi(2) + (x[0] - x[1]).powi(2)*3.0 + (x[1] + x[2] - 2.0).powi(2)*2.0
+    };
+    let mut problem = ExternalProblem::new(3, quadratic);
+    let mut min = Powell::new();
+    let sol = min.minimize(&arr1(&[0.0, 0.0, 0.0]), &mut problem);
+
+    assert!(sol.success);
+    assert!(sol.iter_num < 10);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[2], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_initial_directions() {
+    // with the eigenvectors of the hessian as directions the minimum is found
+    // in the first iteration
+    let quadratic = |x: &Array1<f64>| (x[0] + x[1] - 2.0).powi(2) + (x[0] - x[1]).powi(2)*10.0;
+    let mut problem = ExternalProblem::new(2, quadratic);
+    let mut min = Powell::new();
+    min.initial_directions = Some(arr2(&[[1.0, 1.0], [1.0, -1.0]]));
+    let sol = min.minimize(&arr1(&[3.0, -2.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.iter_num, 2);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_non_finite() {
+    let mut problem = ExternalProblem::new(1, |x: &Array1<f64>| x[0].ln());
+    let mut min = Powell::new();
+    let sol = min.minimize(&arr1(&[-1.0]), &mut problem);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::NonFinite);
+}
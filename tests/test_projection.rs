@@ -0,0 +1,62 @@
+use optimization::projection::{Projection, Bounds, Ball, Simplex, UnitQuaternions};
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+#[test]
+fn test_bounds() {
+    let bounds = Bounds::new(arr1(&[0.0, f64::NEG_INFINITY, -1.0]), arr1(&[1.0, 2.0, f64::INFINITY]));
+    let mut x = arr1(&[-3.0, 5.0, 0.5]);
+    bounds.project(&mut x);
+
+    assert_eq!(x, arr1(&[0.0, 2.0, 0.5]));
+}
+
+
+#[test]
+fn test_ball() {
+    let ball = Ball::new(arr1(&[1.0, 1.0]), 2.0);
+
+    // points inside the ball don't move
+    let mut x = arr1(&[2.0, 0.0]);
+    ball.project(&mut x);
+    assert_eq!(x, arr1(&[2.0, 0.0]));
+
+    let mut x = arr1(&[4.0, 5.0]);
+    ball.project(&mut x);
+    assert_abs_diff_eq!(x[0], 1.0 + 1.2, epsilon = 1e-12);
+    assert_abs_diff_eq!(x[1], 1.0 + 1.6, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_simplex() {
+    let simplex = Simplex::new(1.0);
+
+    let mut x = arr1(&[0.5, 0.3, -0.2]);
+    simplex.project(&mut x);
+    assert_abs_diff_eq!(x[0], 0.6, epsilon = 1e-12);
+    assert_abs_diff_eq!(x[1], 0.4, epsilon = 1e-12);
+    assert_abs_diff_eq!(x[2], 0.0, epsilon = 1e-12);
+
+    // points of the simplex don't move
+    let mut x = arr1(&[0.2, 0.5, 0.3]);
+    simplex.project(&mut x);
+    assert_abs_diff_eq!(x[0], 0.2, epsilon = 1e-12);
+    assert_abs_diff_eq!(x[1], 0.5, epsilon = 1e-12);
+    assert_abs_diff_eq!(x[2], 0.3, epsilon = 1e-12);
+
+    let simplex = Simplex::new(3.0);
+    let mut x = arr1(&[10.0, 0.0, 0.0, 0.0]);
+    simplex.project(&mut x);
+    assert_eq!(x, arr1(&[3.0, 0.0, 0.0, 0.0]));
+}
+
+
+#[test]
+fn test_unit_quaternions() {
+    let mut x = arr1(&[2.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+    UnitQuaternions::new().project(&mut x);
+
+    assert_eq!(x, arr1(&[1.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 1.0, 0.0, 0.0, 0.0]));
+}
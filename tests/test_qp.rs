@@ -0,0 +1,158 @@
+use optimization::qp::{QuadraticProgram, ActiveSet, QPStatus};
+use ndarray::{arr1, arr2, s, Array1, Array2};
+use approx::assert_abs_diff_eq;
+
+
+// "Numerical Optimization" example 16.4
+fn example_16_4() -> QuadraticProgram {
+    QuadraticProgram::new(2.0*Array2::eye(2), arr1(&[-2.0, -5.0]))
+        .with_inequality(
+            arr2(&[[-1.0, 2.0], [1.0, 2.0], [1.0, -2.0], [-1.0, 0.0], [0.0, -1.0]]),
+            arr1(&[2.0, 6.0, 2.0, 0.0, 0.0]),
+        )
+}
+
+
+#[test]
+fn test_inequality() {
+    let program = example_16_4();
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, QPStatus::Optimal);
+    assert_abs_diff_eq!(sol.x[0], 1.4, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], 1.7, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.f, program.eval(&sol.x), epsilon = 1e-12);
+    assert_eq!(sol.working_set, vec![0]);
+    assert_abs_diff_eq!(sol.inequality_multipliers[0], 0.8, epsilon = 1e-10);
+    assert!(sol.inequality_multipliers.iter().skip(1).all(|nu| *nu == 0.0));
+}
+
+
+#[test]
+fn test_equality() {
+    // "Numerical Optimization" example 16.2
+    let program = QuadraticProgram::new(
+        arr2(&[[6.0, 2.0, 1.0], [2.0, 5.0, 2.0], [1.0, 2.0, 4.0]]),
+        arr1(&[-8.0, -3.0, -3.0]),
+    ).with_equality(arr2(&[[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]]), arr1(&[3.0, 0.0]));
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 2.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], -1.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[2], 1.0, epsilon = 1e-10);
+    // Q*x + c + A_eq^T*y = 0
+    assert_abs_diff_eq!(sol.equality_multipliers[0], -3.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.equality_multipliers[1], 2.0, epsilon = 1e-10);
+}
+
+
+#[test]
+fn test_linear_program() {
+    let program = QuadraticProgram::new(Array2::zeros((2, 2)), arr1(&[-1.0, -1.0]))
+        .with_inequality(
+            arr2(&[[1.0, 2.0], [3.0, 1.0], [-1.0, 0.0], [0.0, -1.0]]),
+            arr1(&[4.0, 6.0, 0.0, 0.0]),
+        );
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.6, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], 1.2, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.f, -2.8, epsilon = 1e-10);
+    let mut working_set = sol.working_set.clone();
+    working_set.sort_unstable();
+    assert_eq!(working_set, vec![0, 1]);
+}
+
+
+#[test]
+fn test_unbounded() {
+    let program = QuadraticProgram::new(Array2::zeros((2, 2)), arr1(&[-1.0, 0.0]))
+        .with_inequality(arr2(&[[0.0, 1.0], [-1.0, 0.0]]), arr1(&[1.0, 0.0]));
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, QPStatus::Unbounded);
+}
+
+
+#[test]
+fn test_infeasible_inequalities() {
+    // x0 >= 2 and x0 + x1 <= 1 with x1 >= 0
+    let program = QuadraticProgram::new(Array2::eye(2), arr1(&[0.0, 0.0]))
+        .with_inequality(arr2(&[[-1.0, 0.0], [1.0, 1.0], [0.0, -1.0]]), arr1(&[-2.0, 1.0, 0.0]));
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, QPStatus::Infeasible);
+}
+
+
+#[test]
+fn test_infeasible_equalities() {
+    let program = QuadraticProgram::new(Array2::eye(2), arr1(&[0.0, 0.0]))
+        .with_equality(arr2(&[[1.0, 1.0], [2.0, 2.0]]), arr1(&[1.0, 1.0]));
+    let sol = ActiveSet::new().solve(&program);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, QPStatus::Infeasible);
+}
+
+
+#[test]
+fn test_warm_start() {
+    let program = example_16_4();
+    let solver = ActiveSet::new();
+    let cold = solver.solve(&program);
+    let warm = solver.solve_from(&program, &Array1::zeros(2), &cold.working_set);
+
+    assert!(warm.success);
+    assert!(warm.iter_num < cold.iter_num);
+    assert_eq!(warm.working_set, cold.working_set);
+    assert_abs_diff_eq!(warm.x[0], cold.x[0], epsilon = 1e-10);
+    assert_abs_diff_eq!(warm.x[1], cold.x[1], epsilon = 1e-10);
+
+    // a wrong working set is corrected by the iterations
+    let wrong = solver.solve_from(&program, &Array1::zeros(2), &[1, 2]);
+    assert!(wrong.success);
+    assert_abs_diff_eq!(wrong.x[0], 1.4, epsilon = 1e-10);
+    assert_abs_diff_eq!(wrong.x[1], 1.7, epsilon = 1e-10);
+}
+
+
+#[test]
+fn test_large_gradient() {
+    // elastic program of the inconsistent equalities p = 0 and p = 1: the
+    // violations are u - v, the steps are rounding noise at the minimum of
+    // the working set compared with the large penalty
+    let penalty = 2e7;
+    let mut q = Array2::zeros((5, 5));
+    q[[0, 0]] = 1.0;
+    let program = QuadraticProgram::new(q, arr1(&[2.0, penalty, penalty, penalty, penalty]))
+        .with_equality(arr2(&[[1.0, -1.0, 0.0, 1.0, 0.0], [1.0, 0.0, -1.0, 0.0, 1.0]]), arr1(&[0.0, 1.0]))
+        .with_inequality(-Array2::eye(5).slice(s![1.., ..]).to_owned(), Array1::zeros(4));
+    let sol = ActiveSet::new().solve(&program);
+
+    assert_eq!(sol.status, QPStatus::Optimal);
+    assert!(sol.iter_num < 20);
+    // the rounding errors are relative to the penalty
+    assert_abs_diff_eq!(sol.x[0], 0.0, epsilon = 1e-15*penalty);
+    assert_abs_diff_eq!(sol.f, penalty, epsilon = 1e-8*penalty);
+}
+
+
+#[test]
+fn test_max_iterations() {
+    // the starting point violates x >= 0.5, phase 1 can't finish in one
+    // iteration
+    let program = QuadraticProgram::new(Array2::eye(2), arr1(&[-1.0, -1.0]))
+        .with_inequality(arr2(&[[-1.0, 0.0], [0.0, -1.0], [1.0, 1.0]]), arr1(&[-0.5, -0.5, 3.0]));
+    let mut solver = ActiveSet::new();
+    solver.i_max = 1;
+    let sol = solver.solve(&program);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, QPStatus::MaxIterations);
+}
@@ -0,0 +1,96 @@
+use optimization::number_system::{Real, DualScalar, Dual, HyperDualScalar, HyperDual};
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+// Generic function that can be evaluated in any number system
+// f(x, y) = (x*cos(y) - 0.5)^2/y + sin(x)
+fn func<T: Real>(x: &[T]) -> T {
+    let u = x[0].clone()*x[1].cos() - 0.5;
+    u.powi(2)/x[1].clone() + x[0].sin()
+}
+
+// derivatives of f obtained analytically
+fn expected(x: f64, y: f64) -> (f64, [f64; 2], [[f64; 2]; 2]) {
+    let u = x*y.cos() - 0.5;
+    let u_x = y.cos();
+    let u_y = -x*y.sin();
+    let u_yy = -x*y.cos();
+    let u_xy = -y.sin();
+    let value = u.powi(2)/y + x.sin();
+    let f_x = 2.0*u*u_x/y + x.cos();
+    let f_y = 2.0*u*u_y/y - u.powi(2)/y.powi(2);
+    let f_xx = 2.0*u_x.powi(2)/y - x.sin();
+    let f_xy = 2.0*(u_y*u_x + u*u_xy)/y - 2.0*u*u_x/y.powi(2);
+    let f_yy = 2.0*(u_y.powi(2) + u*u_yy)/y - 4.0*u*u_y/y.powi(2)
+               + 2.0*u.powi(2)/y.powi(3);
+    (value, [f_x, f_y], [[f_xx, f_xy], [f_xy, f_yy]])
+}
+
+
+#[test]
+fn test_real_f64() {
+    let (value, _, _) = expected(0.7, 1.3);
+    assert_abs_diff_eq!(func(&[0.7, 1.3]), value, epsilon = 1e-12);
+    assert_abs_diff_eq!(0.7.constant(2.0), 2.0);
+}
+
+
+#[test]
+fn test_real_dual_scalar() {
+    let (value, grad, _) = expected(0.7, 1.3);
+    let x = DualScalar{re: 0.7, du: 1.0};
+    let y = DualScalar{re: 1.3, du: 0.0};
+    let result = func(&[x, y]);
+    assert_abs_diff_eq!(result.re(), value, epsilon = 1e-12);
+    assert_abs_diff_eq!(result.du, grad[0], epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_real_dual() {
+    let (value, grad, _) = expected(0.7, 1.3);
+    let x = Dual{re: 0.7, du: arr1(&[1.0, 0.0])};
+    let y = Dual{re: 1.3, du: arr1(&[0.0, 1.0])};
+    let result = func(&[x, y]);
+    assert_abs_diff_eq!(result.re(), value, epsilon = 1e-12);
+    assert_abs_diff_eq!(result.du[0], grad[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(result.du[1], grad[1], epsilon = 1e-12);
+
+    let constant = result.constant(3.0);
+    assert_eq!(constant.re, 3.0);
+    assert_eq!(constant.du, arr1(&[0.0, 0.0]));
+}
+
+
+#[test]
+fn test_real_hyperdual_scalar() {
+    let (value, grad, hess) = expected(0.7, 1.3);
+    let x = HyperDualScalar{re: 0.7, e1: 1.0, e2: 0.0, e1e2: 0.0};
+    let y = HyperDualScalar{re: 1.3, e1: 0.0, e2: 1.0, e1e2: 0.0};
+    let result = func(&[x, y]);
+    assert_abs_diff_eq!(result.re(), value, epsilon = 1e-12);
+    assert_abs_diff_eq!(result.e1, grad[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(result.e2, grad[1], epsilon = 1e-12);
+    assert_abs_diff_eq!(result.e1e2, hess[0][1], epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_real_hyperdual() {
+    let (value, grad, hess) = expected(0.7, 1.3);
+    let mut x = HyperDual::new(2);
+    x.real = 0.7;
+    x.grad[0] = 1.0;
+    let mut y = HyperDual::new(2);
+    y.real = 1.3;
+    y.grad[1] = 1.0;
+    let result = func(&[x, y]);
+    assert_abs_diff_eq!(result.re(), value, epsilon = 1e-12);
+    for i in 0..2 {
+        assert_abs_diff_eq!(result.grad[i], grad[i], epsilon = 1e-12);
+        for (j, hess_ij) in hess[i].iter().enumerate() {
+            assert_abs_diff_eq!(result.hess[[i, j]], *hess_ij, epsilon = 1e-12);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use optimization::Status;
+use optimization::root::{Newton, Hybrid, Broyden};
+use optimization::problem::FnResiduals;
+use optimization::number_system::DualScalar;
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+// Intersection of two circles, the roots are (4, 3) and (4, -3)
+fn circles(x: &[DualScalar]) -> Vec<DualScalar> {
+    vec![
+        x[0].powi(2) + x[1].powi(2) - 25.0,
+        (x[0] - 8.0).powi(2) + x[1].powi(2) - 25.0,
+    ]
+}
+
+
+// Rosenbrock function written as a system of equations, the root is (1, 1)
+fn rosenbrock(x: &[DualScalar]) -> Vec<DualScalar> {
+    vec![
+        (x[1] - x[0].powi(2))*10.0,
+        -x[0] + 1.0,
+    ]
+}
+
+
+#[test]
+fn test_newton() {
+    let mut problem = FnResiduals::new(2, 2, circles);
+    let sol = Newton::new().solve(&arr1(&[1.0, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::ResidualTolerance);
+    assert_abs_diff_eq!(sol.x[0], 4.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], 3.0, epsilon = 1e-10);
+    assert!(sol.residual_norm <= 1e-10);
+    assert_eq!(sol.rank, 2);
+    assert!(!sol.is_overdetermined());
+    assert!(!sol.is_underdetermined());
+    // quadratic convergence
+    assert!(sol.iter_num < 10);
+}
+
+
+#[test]
+fn test_rosenbrock() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnResiduals::new(2, 2, rosenbrock);
+    let newton = Newton::new().solve(&x0, &mut problem);
+    let hybrid = Hybrid::new().solve(&x0, &mut problem);
+    let broyden = Broyden::new().solve(&x0, &mut problem);
+
+    for sol in &[newton, hybrid, broyden] {
+        assert!(sol.success);
+        assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-8);
+        assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-8);
+    }
+}
+
+
+#[test]
+fn test_hybrid() {
+    let mut problem = FnResiduals::new(2, 2, circles);
+    let sol = Hybrid::new().solve(&arr1(&[20.0, -30.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 4.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], -3.0, epsilon = 1e-10);
+}
+
+
+#[test]
+fn test_broyden() {
+    let x0 = arr1(&[1.0, 1.0]);
+    let mut problem = FnResiduals::new(2, 2, circles);
+    let newton = Newton::new().solve(&x0, &mut problem);
+    let mut problem = FnResiduals::new(2, 2, circles);
+    let sol = Broyden::new().solve(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 4.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1], 3.0, epsilon = 1e-10);
+    assert!(sol.f_jac_evals < newton.f_jac_evals);
+}
+
+
+#[test]
+fn test_underdetermined() {
+    // a point on a circle, any point of the circle is a root
+    let mut problem = FnResiduals::new(2, 1, |x: &[DualScalar]| {
+        vec![x[0].powi(2) + x[1].powi(2) - 25.0]
+    });
+    let sol = Newton::new().solve(&arr1(&[1.0, 2.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x.dot(&sol.x), 25.0, epsilon = 1e-8);
+    assert_eq!(sol.rank, 1);
+    assert!(sol.is_underdetermined());
+    assert!(!sol.is_overdetermined());
+}
+
+
+#[test]
+fn test_redundant() {
+    // the third equation is a combination of the first two
+    let mut problem = FnResiduals::new(2, 3, |x: &[DualScalar]| {
+        let mut r = circles(x);
+        r.push(r[0]*2.0 - r[1]);
+        r
+    });
+    let sol = Hybrid::new().solve(&arr1(&[1.0, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.rank, 2);
+    assert!(sol.is_overdetermined());
+    assert!(!sol.is_underdetermined());
+}
+
+
+#[test]
+fn test_inconsistent() {
+    let mut problem = FnResiduals::new(1, 2, |x: &[DualScalar]| {
+        vec![x[0] - 1.0, x[0] - 2.0]
+    });
+    let newton = Newton::new().solve(&arr1(&[0.0]), &mut problem);
+    let hybrid = Hybrid::new().solve(&arr1(&[0.0]), &mut problem);
+
+    for sol in &[newton, hybrid] {
+        assert!(!sol.success);
+        assert_abs_diff_eq!(sol.x[0], 1.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(sol.residual_norm, 0.5_f64.sqrt(), epsilon = 1e-8);
+        assert!(sol.is_overdetermined());
+    }
+}
+
+
+#[test]
+fn test_non_finite() {
+    let mut problem = FnResiduals::new(1, 1, |x: &[DualScalar]| vec![x[0].ln()]);
+    let sol = Newton::new().solve(&arr1(&[-1.0]), &mut problem);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::NonFinite);
+}
+
+
+#[test]
+fn test_repeated_solve() {
+    let x0 = arr1(&[-1.2, 1.0]);
+    let mut problem = FnResiduals::new(2, 2, rosenbrock);
+    let mut newton = Newton::new();
+    let mut hybrid = Hybrid::new();
+    let mut broyden = Broyden::new();
+    let first = [newton.solve(&x0, &mut problem), hybrid.solve(&x0, &mut problem), broyden.solve(&x0, &mut problem)];
+    let second = [newton.solve(&x0, &mut problem), hybrid.solve(&x0, &mut problem), broyden.solve(&x0, &mut problem)];
+
+    for (first, second) in first.iter().zip(second.iter()) {
+        assert_eq!(second.f_evals, first.f_evals);
+        assert_eq!(second.f_jac_evals, first.f_jac_evals);
+    }
+}
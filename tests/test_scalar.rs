@@ -0,0 +1,103 @@
+use optimization::Status;
+use optimization::scalar::{bracket, Bracket, Brent, GoldenSection};
+use optimization::number_system::DualScalar;
+use approx::assert_abs_diff_eq;
+
+
+fn quartic(x: f64) -> f64 {
+    (x - 3.0).powi(4) + (x - 3.0).powi(2) - 1.0
+}
+
+
+#[test]
+fn test_bracket() {
+    let b = bracket(quartic, 0.0, 1.0).unwrap();
+
+    assert!((b.a - b.b)*(b.b - b.c) > 0.0);
+    assert!(b.fb < b.fa && b.fb < b.fc);
+    assert_eq!(b.fb, quartic(b.b));
+
+    // the search also goes downhill when the first step is uphill
+    let b = bracket(quartic, 6.0, 7.0).unwrap();
+    assert!(b.a.min(b.c) < 3.0 && 3.0 < b.a.max(b.c));
+}
+
+
+#[test]
+fn test_bracket_failure() {
+    assert_eq!(bracket(|x: f64| -x, 0.0, 1.0), None);
+    assert_eq!(bracket(|x: f64| -x.sqrt(), 0.0, 1.0), None);
+
+    let sol = Brent::new().minimize(|x: f64| -x, 0.0, 1.0);
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::MaxIterations);
+}
+
+
+#[test]
+fn test_golden_section() {
+    let sol = GoldenSection::new().minimize(quartic, 0.0, 1.0);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::XTolerance);
+    assert_abs_diff_eq!(sol.x, 3.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, -1.0, epsilon = 1e-12);
+    assert_eq!(sol.f_grad_evals, 0);
+}
+
+
+#[test]
+fn test_brent() {
+    let golden = GoldenSection::new().minimize(quartic, 0.0, 1.0);
+    let sol = Brent::new().minimize(quartic, 0.0, 1.0);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x, 3.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, -1.0, epsilon = 1e-12);
+    // the parabolic steps converge faster than the golden section
+    assert!(sol.f_evals < golden.f_evals);
+}
+
+
+#[test]
+fn test_minimize_bracket() {
+    // non smooth function with the minimum at x = 0.3
+    let f = |x: f64| (x - 0.3).abs();
+    let b = Bracket{a: 0.0, b: 0.5, c: 2.0, fa: f(0.0), fb: f(0.5), fc: f(2.0)};
+
+    let golden = GoldenSection::new().minimize_bracket(f, &b);
+    let brent = Brent::new().minimize_bracket(f, &b);
+
+    assert!(golden.success);
+    assert!(brent.success);
+    assert_abs_diff_eq!(golden.x, 0.3, epsilon = 1e-7);
+    assert_abs_diff_eq!(brent.x, 0.3, epsilon = 1e-7);
+}
+
+
+#[test]
+fn test_brent_dual() {
+    let f = |x: DualScalar| (x - 3.0).powi(4) + (x - 3.0).powi(2) - 1.0;
+    let sol = Brent::new().minimize_dual(f, 0.0, 1.0);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x, 3.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, -1.0, epsilon = 1e-12);
+    assert!(sol.f_grad_evals > 0);
+
+    let sol = Brent::new().minimize_dual(|x: DualScalar| x.cos(), 2.0, 3.0);
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x, std::f64::consts::PI, epsilon = 1e-7);
+}
+
+
+#[test]
+fn test_max_iterations() {
+    let mut brent = Brent::new();
+    brent.i_max = 3;
+    let sol = brent.minimize(quartic, 0.0, 1.0);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::MaxIterations);
+    assert_eq!(sol.iter_num, 3);
+}
@@ -0,0 +1,233 @@
+use optimization::{BFGS, TrustNCG, Solution, Status};
+use optimization::number_system::{DualScalar, HyperDualScalar as HDual};
+use optimization::problem::{Objective, Gradient, Hessian};
+use ndarray::{Array1, Array2, arr1};
+use approx::assert_abs_diff_eq;
+
+// Objective function. In this case it only contains a vector of DualScalars
+// representing the variables. Note that it could also contain a vector of
+// error functions in a least-squares problem.
+pub struct ProblemObjective {
+    x: Vec<DualScalar>,
+    value: DualScalar,
+}
+
+
+// Implementation of the Objective trait. The method `eval` is the method that
+// actually evaluates the objective function. The methods `eval_real`, `grad`,
+// and `diff` will call `eval` in order to evaluate the function and then pick
+// the real or dual value of the result.
+impl Objective for ProblemObjective {
+    fn eval(&mut self) {
+        let x1 = &self.x[0];
+        let x2 = &self.x[1];
+        let x3 = &self.x[2];
+
+        let u1 = x1.cos()*x2.sin() - 0.05;
+        let u2 = x2.sin() - 0.2;
+        let u3 = x3.powi(2) - 2.56;
+
+        self.value = u1.powi(2) + u2.powi(2) + u3.powi(2)
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].du = 0.0; // make sure we are removing any dual part
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = DualScalar{re: alpha, du: 1.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+// Implementation of the gradient trait. Note that both `grad` and `diff` call
+// `eval` in order to obtain the corresponding derivatives.
+impl Gradient for ProblemObjective {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].du = 1.0;
+            self.eval();
+            output[i] = self.value.du;
+            self.x[i].du = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.du
+    }
+}
+
+
+// Objective function whose value is always NaN (as if the variables left the
+// domain of the function)
+pub struct NanObjective {
+    x: Vec<DualScalar>,
+}
+
+
+impl Objective for NanObjective {
+    fn eval(&mut self) {}
+
+    fn eval_real(&mut self) -> f64 {
+        f64::NAN
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+        }
+    }
+
+    fn move_step(&mut self, _x: &Array1<f64>, _p: &Array1<f64>, _alpha: f64) {}
+}
+
+impl Gradient for NanObjective {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        output.fill(f64::NAN);
+    }
+
+    fn diff(&mut self) -> f64 {
+        f64::NAN
+    }
+}
+
+
+// Same objective function as ProblemObjective but using hyperdual numbers so
+// that the hessian can also be calculated.
+pub struct HessianObjective {
+    x: Vec<HDual>,
+    value: HDual,
+}
+
+
+impl Objective for HessianObjective {
+    fn eval(&mut self) {
+        let x1 = &self.x[0];
+        let x2 = &self.x[1];
+        let x3 = &self.x[2];
+
+        let u1 = x1.cos()*x2.sin() - 0.05;
+        let u2 = x2.sin() - 0.2;
+        let u3 = x3.powi(2) - 2.56;
+
+        self.value = u1.powi(2) + u2.powi(2) + u3.powi(2);
+    }
+
+    fn eval_real(&mut self) -> f64 {
+        self.eval();
+        self.value.re
+    }
+
+    fn update_x(&mut self, x: &Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].re = x[i];
+            self.x[i].e1 = 0.0;
+        }
+    }
+
+    fn move_step(&mut self, x: &Array1<f64>, p: &Array1<f64>, alpha: f64) {
+        let a = HDual{re: alpha, e1: 1.0, e2: 1.0, e1e2: 0.0};
+        for i in 0..self.x.len() {
+            self.x[i] = x[i] + a*p[i];
+        }
+    }
+}
+
+impl Gradient for HessianObjective {
+    fn grad(&mut self, output: &mut Array1<f64>) {
+        for i in 0..self.x.len() {
+            self.x[i].e1 = 1.0;
+            self.eval();
+            output[i] = self.value.e1;
+            self.x[i].e1 = 0.0;
+        }
+    }
+
+    fn diff(&mut self) -> f64 {
+        self.eval();
+        self.value.e1
+    }
+}
+
+impl Hessian for HessianObjective {
+    fn hess(&mut self, output: &mut Array2<f64>) {
+        for i in 0..self.x.len() {
+            for j in i..self.x.len() {
+                self.x[i].e1 = 1.0;
+                self.x[j].e2 = 1.0;
+                self.eval();
+                output[[i,j]] = self.value.e1e2;
+                output[[j,i]] = self.value.e1e2;
+                self.x[i].e1 = 0.0;
+                self.x[j].e2 = 0.0;
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_solution_gradient_tolerance() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = BFGS::new();
+    let mut problem = ProblemObjective{x: vec![DualScalar::new(); 3], value: DualScalar::new()};
+    let sol: Solution = min.minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    assert!(sol.grad_norm < min.gtol);
+    assert_abs_diff_eq!(sol.f, 0.0, epsilon = 1e-10);
+    assert!(sol.trust_radius.is_none());
+}
+
+
+#[test]
+fn test_solution_max_iterations() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = BFGS::new();
+    min.i_max = 3;
+    let mut problem = ProblemObjective{x: vec![DualScalar::new(); 3], value: DualScalar::new()};
+    let sol = min.minimize(&x0, &mut problem);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::MaxIterations);
+    assert!(sol.grad_norm > min.gtol);
+}
+
+
+#[test]
+fn test_solution_non_finite() {
+    let x0 = arr1::<f64>(&[1.0, 1.0]);
+    let mut min = BFGS::new();
+    let mut problem = NanObjective{x: vec![DualScalar::new(); 2]};
+    let sol = min.minimize(&x0, &mut problem);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::NonFinite);
+    assert!(sol.f.is_nan());
+}
+
+
+#[test]
+fn test_solution_trust_radius() {
+    let x0 = arr1::<f64>(&[1.0, 1.0, 1.0]);
+    let mut min = TrustNCG::new();
+    let mut problem = HessianObjective{x: vec![HDual::new(); 3], value: HDual::new()};
+    let sol = min.minimize(&x0, &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    let delta = sol.trust_radius.unwrap();
+    assert!(delta > 0.0 && delta <= min.delta_max);
+}
@@ -0,0 +1,96 @@
+use optimization::{SPG, Status};
+use optimization::projection::{Bounds, Ball, Simplex, UnitQuaternions};
+use optimization::problem::FnProblem;
+use optimization::number_system::DualScalar;
+use optimization::observer::{Iteration, Control};
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+fn rosenbrock(x: &[DualScalar]) -> DualScalar {
+    (1.0 - x[0]).powi(2) + 100.0*(x[1] - x[0].powi(2)).powi(2)
+}
+
+
+#[test]
+fn test_bounds() {
+    // the minimum of the rosenbrock function with x0 <= 0.5 is at (0.5, 0.25)
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let bounds = Bounds::new(arr1(&[-2.0, -2.0]), arr1(&[0.5, 2.0]));
+    let mut spg = SPG::new(bounds);
+    spg.i_max = 10000;
+    let sol = spg.minimize(&arr1(&[-1.2, 1.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    assert_abs_diff_eq!(sol.x[0], 0.5, epsilon = 1e-12);
+    assert_abs_diff_eq!(sol.x[1], 0.25, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.f, 0.25, epsilon = 1e-8);
+}
+
+
+#[test]
+fn test_ball() {
+    // the minimum of the rosenbrock function is outside of the ball
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let mut spg = SPG::new(Ball::new(arr1(&[0.0, 0.0]), 1.0));
+    spg.i_max = 10000;
+    let sol = spg.minimize(&arr1(&[0.0, 0.0]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x.dot(&sol.x), 1.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[0], 0.7864, epsilon = 1e-4);
+    assert_abs_diff_eq!(sol.x[1], 0.6177, epsilon = 1e-4);
+}
+
+
+#[test]
+fn test_simplex() {
+    // weighted distance to a point outside of the simplex
+    let mut problem = FnProblem::new(3, |x: &[DualScalar]| {
+        (x[0] - 1.0).powi(2) + 2.0*(x[1] - 1.0).powi(2) + 4.0*(x[2] + 1.0).powi(2)
+    });
+    let sol = SPG::new(Simplex::new(1.0)).minimize(&arr1(&[0.0, 0.0, 1.0]), &mut problem);
+
+    // x0 - 1 = 2*(x1 - 1) with x2 = 0
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0/3.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 2.0/3.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[2], 0.0, epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_unit_quaternion() {
+    // the unit vector with the largest value of q^T*M*q is the eigenvector of
+    // the largest eigenvalue of M
+    let mut problem = FnProblem::new(4, |q: &[DualScalar]| {
+        -(q[0].powi(2) + 3.0*q[1].powi(2) + 2.0*q[2].powi(2) + q[0]*q[3])
+    });
+    let sol = SPG::new(UnitQuaternions::new()).minimize(&arr1(&[0.5, 0.5, 0.5, 0.5]), &mut problem);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.f, -3.0, epsilon = 1e-10);
+    assert_abs_diff_eq!(sol.x[1].abs(), 1.0, epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_monotone_observer() {
+    // with one function value the line search is monotone
+    let mut problem = FnProblem::new(2, rosenbrock);
+    let mut spg = SPG::new(Bounds::new(arr1(&[-2.0, -2.0]), arr1(&[0.5, 2.0])));
+    spg.m = 1;
+    spg.i_max = 10000;
+    let mut f = Vec::new();
+    let mut observer = |iteration: &Iteration| {
+        f.push(iteration.f_k);
+        Control::Continue
+    };
+    let sol = spg.minimize_with_observer(&arr1(&[-1.2, 1.0]), &mut problem, &mut observer);
+
+    assert!(sol.success);
+    assert_eq!(f.len() as u32, sol.iter_num - 1);
+    assert!(f.windows(2).all(|f| f[1] <= f[0]));
+    assert_abs_diff_eq!(sol.x[1], 0.25, epsilon = 1e-6);
+}
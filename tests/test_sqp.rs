@@ -0,0 +1,172 @@
+use optimization::{SQP, Status};
+use optimization::problem::{FnProblem, FnConstraints};
+use optimization::number_system::DualScalar;
+use optimization::observer::{Iteration, Control};
+use optimization::line_search::LineSearchError;
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+fn no_constraints<T>(_: &[T]) -> Vec<T> {
+    vec![]
+}
+
+
+#[test]
+fn test_equality() {
+    // point of the unit circle closest to (2, 1)
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+        no_constraints,
+    );
+    let sol = SQP::new().minimize(&arr1(&[1.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_eq!(sol.status, Status::GradientTolerance);
+    assert_abs_diff_eq!(sol.x[0], 2.0/5f64.sqrt(), epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0/5f64.sqrt(), epsilon = 1e-6);
+    // grad(f) + 2λx = 0
+    assert_abs_diff_eq!(sol.equality_multipliers.unwrap()[0], 5f64.sqrt() - 1.0, epsilon = 1e-5);
+    assert!(sol.kkt_residual.unwrap() <= 1e-6);
+    assert!(sol.constraint_violation.unwrap() <= 1e-8);
+}
+
+
+#[test]
+fn test_degenerate_jacobian() {
+    // the jacobian of the circle vanishes at the origin, so the linearized
+    // constraint has no solution at the starting point
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+        no_constraints,
+    );
+    let sol = SQP::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 2.0/5f64.sqrt(), epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0/5f64.sqrt(), epsilon = 1e-6);
+}
+
+
+#[test]
+fn test_inequality() {
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0].powi(2) - x[1], x[0] + x[1] - 2.0],
+    );
+    let sol = SQP::new().minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 1.0, epsilon = 1e-6);
+    let nu = sol.inequality_multipliers.unwrap();
+    assert_abs_diff_eq!(nu[0], 2.0/3.0, epsilon = 1e-5);
+    assert_abs_diff_eq!(nu[1], 2.0/3.0, epsilon = 1e-5);
+}
+
+
+#[test]
+fn test_hock_schittkowski_71() {
+    let mut problem = FnProblem::new(4, |x: &[DualScalar]| {
+        x[0]*x[3]*(x[0] + x[1] + x[2]) + x[2]
+    });
+    let mut constraints = FnConstraints::new(
+        4,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) + x[2].powi(2) + x[3].powi(2) - 40.0],
+        |x: &[DualScalar]| {
+            let mut g = vec![-x[0]*x[1]*x[2]*x[3] + 25.0];
+            for x_i in x.iter() {
+                g.push(-*x_i + 1.0);
+                g.push(*x_i - 5.0);
+            }
+            g
+        },
+    );
+    let sol = SQP::new().minimize(&arr1(&[1.0, 5.0, 5.0, 1.0]), &mut problem, &mut constraints);
+
+    assert!(sol.success);
+    assert_abs_diff_eq!(sol.f, 17.0140173, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[0], 1.0, epsilon = 1e-6);
+    assert_abs_diff_eq!(sol.x[1], 4.7429994, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[2], 3.8211503, epsilon = 1e-5);
+    assert_abs_diff_eq!(sol.x[3], 1.3794082, epsilon = 1e-5);
+    let nu = sol.inequality_multipliers.unwrap();
+    // active constraints: the product and the lower bound of x0
+    assert!(nu[0] > 0.0 && nu[1] > 0.0);
+    assert!(nu.iter().skip(2).all(|nu| nu.abs() < 1e-8));
+}
+
+
+#[test]
+fn test_infeasible() {
+    let mut problem = FnProblem::new(1, |x: &[DualScalar]| x[0].powi(2));
+    let mut constraints = FnConstraints::new(
+        1,
+        |x: &[DualScalar]| vec![x[0] - 1.0, x[0] - 2.0],
+        no_constraints,
+    );
+    let sol = SQP::new().minimize(&arr1(&[0.0]), &mut problem, &mut constraints);
+
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::Infeasible);
+}
+
+
+#[test]
+fn test_subproblem_failure() {
+    // the quadratic programs can't finish, the steps fall back to the
+    // quasi-newton step of the lagrangian
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        no_constraints,
+        |x: &[DualScalar]| vec![x[0] + x[1] - 1.0, -x[0], -x[1]],
+    );
+    let mut sqp = SQP::new();
+    sqp.qp.i_max = 1;
+    let sol = sqp.minimize(&arr1(&[0.0, 0.0]), &mut problem, &mut constraints);
+
+    // without multipliers the fallback steps only minimize f, they stop
+    // when they no longer decrease the merit function
+    assert!(!sol.success);
+    assert_eq!(sol.status, Status::LineSearchFailure(LineSearchError::NotDescentDirection));
+    assert!(sol.x.iter().all(|x| x.is_finite()));
+}
+
+
+#[test]
+fn test_observer() {
+    let mut problem = FnProblem::new(2, |x: &[DualScalar]| {
+        (x[0] - 2.0).powi(2) + (x[1] - 1.0).powi(2)
+    });
+    let mut constraints = FnConstraints::new(
+        2,
+        |x: &[DualScalar]| vec![x[0].powi(2) + x[1].powi(2) - 1.0],
+        no_constraints,
+    );
+    let mut steps = Vec::new();
+    let mut observer = |iteration: &Iteration| {
+        steps.push(iteration.step_length);
+        Control::Continue
+    };
+    let sol = SQP::new().minimize_with_observer(&arr1(&[1.0, 0.0]), &mut problem,
+                                                &mut constraints, &mut observer);
+
+    assert!(sol.success);
+    assert_eq!(steps.len() as u32, sol.iter_num - 1);
+    assert!(steps.iter().all(|alpha| *alpha > 0.0 && *alpha <= 1.0));
+}
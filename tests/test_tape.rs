@@ -0,0 +1,112 @@
+use optimization::number_system::{Tape, DualScalar, HyperDual, Real};
+use ndarray::arr1;
+use approx::assert_abs_diff_eq;
+
+
+// generic function used to compare the reverse mode against the hyperdual
+// numbers
+fn func<T: Real>(x: &[T]) -> T {
+    let u = x[0].clone()*x[1].clone() - x[2].exp();
+    let v = (x[0].powi(2) + x[2].powi(2)).sqrt();
+    u.sin() + x[1].atan2(&x[0]) + v.ln()*x[1].clone() + (x[2].clone()/x[0].clone())
+}
+
+
+#[test]
+fn test_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(1.5);
+    let y = tape.var(-0.5);
+    let f = x.powi(2)*y + y.sin() - 2.0/x;
+    let grad = f.grad();
+
+    assert_abs_diff_eq!(f.value(), 1.5_f64.powi(2)*-0.5 + (-0.5_f64).sin() - 2.0/1.5);
+    assert_abs_diff_eq!(grad.wrt(&x), 2.0*1.5*-0.5 + 2.0/1.5_f64.powi(2), epsilon = 1e-12);
+    assert_abs_diff_eq!(grad.wrt(&y), 1.5_f64.powi(2) + (-0.5_f64).cos(), epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_gradient_generic() {
+    let values = [0.7, 1.3, -0.4];
+    let tape = Tape::new();
+    let x = tape.vars(&values);
+    let grad = func(&x).grad().wrt_all(&x);
+
+    let n = values.len();
+    let hdual: Vec<HyperDual> = values.iter().enumerate().map(|(i, v)| {
+        let mut x_i = HyperDual::new(n);
+        x_i.real = *v;
+        x_i.grad[i] = 1.0;
+        x_i
+    }).collect();
+    let expected = func(&hdual);
+
+    for (i, grad_i) in grad.iter().enumerate() {
+        assert_abs_diff_eq!(*grad_i, expected.grad[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_hessian_vector_product() {
+    let values = [0.7, 1.3, -0.4];
+    let v = arr1(&[1.0, -2.0, 0.5]);
+    let n = values.len();
+
+    let tape = Tape::new();
+    let x: Vec<_> = values.iter().zip(v.iter())
+        .map(|(re, du)| tape.var(DualScalar{re: *re, du: *du}))
+        .collect();
+    let adjoints = func(&x).grad().wrt_all(&x);
+
+    let hdual: Vec<HyperDual> = values.iter().enumerate().map(|(i, v)| {
+        let mut x_i = HyperDual::new(n);
+        x_i.real = *v;
+        x_i.grad[i] = 1.0;
+        x_i
+    }).collect();
+    let expected = func(&hdual);
+    let hess_v = expected.hess.dot(&v);
+
+    for (i, adjoint) in adjoints.iter().enumerate() {
+        assert_abs_diff_eq!(adjoint.re, expected.grad[i], epsilon = 1e-12);
+        assert_abs_diff_eq!(adjoint.du, hess_v[i], epsilon = 1e-12);
+    }
+}
+
+
+#[test]
+fn test_reused_variable() {
+    // the same variable used many times must accumulate its adjoints
+    let tape = Tape::new();
+    let x = tape.var(2.0);
+    let mut f = x;
+    for _ in 0..4 {
+        f = f*x;
+    }
+    assert_abs_diff_eq!(f.value(), 32.0);
+    assert_abs_diff_eq!(f.grad().wrt(&x), 5.0*16.0);
+}
+
+
+#[test]
+fn test_clear() {
+    let mut tape = Tape::new();
+    {
+        let x = tape.var(1.0);
+        let _ = x.sin()*x.cos();
+    }
+    assert_eq!(tape.len(), 4);
+    tape.clear();
+    assert!(tape.is_empty());
+
+    let x = tape.var(3.0);
+    let f = x.hypot(&tape.var(4.0));
+    assert_abs_diff_eq!(f.value(), 5.0);
+    assert_abs_diff_eq!(f.grad().wrt(&x), 0.6, epsilon = 1e-12);
+
+    // variables created after f don't depend on it
+    let y = tape.var(1.0);
+    assert_abs_diff_eq!(f.grad().wrt(&y), 0.0);
+}
@@ -0,0 +1,76 @@
+use optimization::{BFGS, LBFGS};
+use optimization::problem::{TapeProblem, Objective, Gradient, HessianVector};
+use ndarray::{Array, arr1};
+use approx::assert_abs_diff_eq;
+
+
+#[test]
+fn test_tape_problem() {
+    // f(x, y) = x^2*y + sin(y)
+    let mut problem = TapeProblem::new(2, |x| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    let x = arr1(&[1.5_f64, -0.5]);
+    let grad = arr1(&[2.0*x[0]*x[1], x[0].powi(2) + x[1].cos()]);
+
+    problem.update_x(&x);
+    assert_abs_diff_eq!(problem.eval_real(), x[0].powi(2)*x[1] + x[1].sin(), epsilon = 1e-12);
+
+    let mut output = Array::zeros(2);
+    problem.grad(&mut output);
+    assert_abs_diff_eq!(output[0], grad[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(output[1], grad[1], epsilon = 1e-12);
+
+    // derivative along p at alpha = 0 is grad*p
+    let p = arr1(&[1.0, 2.0]);
+    problem.move_step(&x, &p, 0.0);
+    assert_abs_diff_eq!(problem.diff(), grad.dot(&p), epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_hess_vec() {
+    // f(x, y) = x^2*y + sin(y)
+    let mut problem = TapeProblem::new(2, |x| {
+        x[0].powi(2)*x[1] + x[1].sin()
+    });
+    let x = arr1(&[1.5_f64, -0.5]);
+    let v = arr1(&[0.3, -2.0]);
+    // H = [[2y, 2x], [2x, -sin(y)]]
+    let hv = arr1(&[2.0*x[1]*v[0] + 2.0*x[0]*v[1], 2.0*x[0]*v[0] - x[1].sin()*v[1]]);
+
+    let mut output = Array::zeros(2);
+    problem.hess_vec(&x, &v, &mut output);
+    assert_abs_diff_eq!(output[0], hv[0], epsilon = 1e-12);
+    assert_abs_diff_eq!(output[1], hv[1], epsilon = 1e-12);
+
+    // the problem is left at x
+    assert_abs_diff_eq!(problem.eval_real(), x[0].powi(2)*x[1] + x[1].sin(), epsilon = 1e-12);
+}
+
+
+#[test]
+fn test_tape_problem_minimize() {
+    // extended rosenbrock function with many variables
+    let n = 50;
+    let mut problem = TapeProblem::new(n, |x| {
+        let mut f = x[0]*0.0;
+        for i in 0..x.len() - 1 {
+            f = f + (x[i+1] - x[i].powi(2)).powi(2)*100.0 + (1.0 - x[i]).powi(2);
+        }
+        f
+    });
+    let x0 = Array::zeros(n);
+
+    let sol = LBFGS::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    for i in 0..n {
+        assert_abs_diff_eq!(sol.x[i], 1.0, epsilon = 1e-4);
+    }
+
+    let sol = BFGS::new().minimize(&x0, &mut problem);
+    assert!(sol.success);
+    for i in 0..n {
+        assert_abs_diff_eq!(sol.x[i], 1.0, epsilon = 1e-4);
+    }
+}